@@ -18,6 +18,7 @@
         buildInputs = [
           pkg-config
           openssl.dev
+          dbus.dev
         ];
       };
     }
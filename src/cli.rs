@@ -0,0 +1,200 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use itertools::Itertools;
+
+use crate::{
+    nanofab::{Booking, Login, Project},
+    schedule::TimeTable,
+};
+
+/// Check NanoFab tools and bookings. Without a command, opens the interactive UI.
+#[derive(Debug, Parser)]
+#[command(name = "nanofab-cli")]
+pub struct Cli {
+    /// Log scraped fields to ~/.nanofab-cli/debug.log
+    #[arg(short, long, global = true)]
+    pub verbose: bool,
+    /// Log in as this user rather than with the saved login
+    #[arg(long, global = true)]
+    pub username: Option<String>,
+    /// Password for `--username`, asked for if it isn't given and there is a terminal to
+    /// type it in
+    #[arg(long, global = true, env = "NANOFAB_PASSWORD", hide_env_values = true)]
+    pub password: Option<String>,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+impl Cli {
+    /// The login given with `--username`, if any, with the password from `--password` or
+    /// else from `ask_password`
+    pub fn login(&self, ask_password: impl FnOnce() -> Result<String>) -> Result<Option<Login>> {
+        let Some(username) = self.username.clone() else {
+            return Ok(None);
+        };
+        let password = match self.password.clone() {
+            Some(password) => password,
+            None => ask_password()?,
+        };
+        Ok(Some(Login { username, password }))
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Print the openings of a tool
+    Openings(OpeningsArgs),
+    /// Print your upcoming bookings
+    Bookings,
+    /// Print your projects as `id<TAB>name` lines
+    Projects,
+    /// Write the openings when every one of the tools is free to an iCalendar file
+    ExportIcs {
+        file: PathBuf,
+        #[arg(required = true)]
+        tools: Vec<String>,
+    },
+    /// Render the coming week of a tool's bookings as an SVG timeline
+    #[cfg(feature = "svg")]
+    ExportSvg { tool: String, file: PathBuf },
+}
+
+#[derive(Debug, Args)]
+pub struct OpeningsArgs {
+    /// Label of the tool, or enough of it to match just one
+    pub tool: String,
+    /// Only look this many days ahead, up to a year
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..=366))]
+    pub days: Option<u64>,
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+    /// Setup time to trim from the start of each opening, in minutes
+    #[arg(long, value_name = "MINUTES")]
+    pub lead: Option<i64>,
+    /// Cleanup time to trim from the end of each opening, in minutes
+    #[arg(long, value_name = "MINUTES")]
+    pub trail: Option<i64>,
+    /// Hide openings shorter than this, in minutes
+    #[arg(long, value_name = "MINUTES")]
+    pub min: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// The same listing as the interactive UI
+    Text,
+    /// A tab-separated start, end and length in minutes per opening, for scripts
+    Tsv,
+}
+
+/// What the `openings` command prints
+pub fn format_openings(openings: &TimeTable<()>, format: OutputFormat) -> String {
+    match format {
+        OutputFormat::Text => format!("{openings}\n"),
+        OutputFormat::Tsv => openings.to_tsv(),
+    }
+}
+
+/// What the `bookings` command prints
+pub fn format_bookings(bookings: &TimeTable<Booking>) -> String {
+    format!("{bookings}\n")
+}
+
+/// What the `projects` command prints
+pub fn format_projects(projects: &[impl std::borrow::Borrow<Project>]) -> String {
+    projects.iter().map(|p| format!("{}\t{}\n", p.borrow().id, p.borrow().name)).join("")
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::bail;
+    use chrono::NaiveDateTime;
+    use clap::CommandFactory;
+
+    use super::*;
+    use crate::schedule::TimeSlot;
+
+    #[test]
+    fn test_cli_definition() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_parse_openings() {
+        let cli = Cli::try_parse_from([
+            "nanofab-cli",
+            "openings",
+            "Heidelberg MLA150",
+            "--days",
+            "14",
+            "--format",
+            "tsv",
+            "-v",
+        ])
+        .unwrap();
+        assert!(cli.verbose);
+        let Some(Command::Openings(args)) = cli.command else { panic!("Not `openings`") };
+        assert_eq!(args.tool, "Heidelberg MLA150");
+        assert_eq!(args.days, Some(14));
+        assert_eq!(args.format, OutputFormat::Tsv);
+        assert_eq!(args.lead, None);
+        let days =
+            |days| Cli::try_parse_from(["nanofab-cli", "openings", "MLA150", "--days", days]);
+        assert!(days("366").is_ok());
+        assert!(days("0").is_err());
+        assert!(days("1000000").is_err());
+    }
+
+    #[test]
+    fn test_parse_login() {
+        let cli = Cli::try_parse_from(["nanofab-cli", "bookings"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Bookings)));
+        let no_prompt = || bail!("Asked for a password");
+        assert!(cli.login(no_prompt).unwrap().is_none());
+        let cli = Cli::try_parse_from([
+            "nanofab-cli",
+            "projects",
+            "--username",
+            "wjames",
+            "--password",
+            "hunter2",
+        ])
+        .unwrap();
+        let login = cli.login(no_prompt).unwrap().unwrap();
+        assert_eq!((login.username.as_str(), login.password.as_str()), ("wjames", "hunter2"));
+        // A username without a password asks for one rather than using the saved login
+        let cli = Cli::try_parse_from(["nanofab-cli", "bookings", "--username", "wjames"]).unwrap();
+        if cli.password.is_none() {
+            let login = cli.login(|| Ok("typed".into())).unwrap().unwrap();
+            assert_eq!(login.password, "typed");
+            assert!(cli.login(no_prompt).is_err());
+        }
+        let command = Cli::command();
+        let password = command.get_arguments().find(|arg| arg.get_id() == "password").unwrap();
+        assert_eq!(password.get_env().and_then(|env| env.to_str()), Some("NANOFAB_PASSWORD"));
+        assert!(Cli::try_parse_from(["nanofab-cli", "export-ics", "out.ics"]).is_err());
+        assert!(Cli::try_parse_from(["nanofab-cli"]).unwrap().command.is_none());
+    }
+
+    #[test]
+    fn test_format_openings() {
+        let dt = |s| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap();
+        let openings = TimeTable::new([TimeSlot::new(
+            Some(dt("2022-11-24 09:00")),
+            Some(dt("2022-11-24 11:30")),
+            (),
+        )]);
+        let text = format_openings(&openings, OutputFormat::Text);
+        assert_eq!(text, "[  Thursday Nov 24 2022   ]\n 9:00am - 11:30am\n\n");
+        let tsv = format_openings(&openings, OutputFormat::Tsv);
+        assert_eq!(tsv, "2022-11-24T09:00:00\t2022-11-24T11:30:00\t150\n");
+    }
+
+    #[test]
+    fn test_format_projects() {
+        let project = |name: &str, id: &str| Project { name: name.to_string(), id: id.to_string() };
+        let projects = [project("Thin films", "12"), project("Etching", "7")];
+        assert_eq!(format_projects(&projects), "12\tThin films\n7\tEtching\n");
+    }
+}
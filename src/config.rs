@@ -0,0 +1,108 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use chrono::{NaiveDate, NaiveTime, Weekday};
+use serde::Deserialize;
+
+use crate::{nanofab::ProjectSort, term_ui::Theme};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub team: Team,
+    pub openings: Openings,
+    pub history: History,
+    pub projects: Projects,
+    pub session: Session,
+    pub theme: Theme,
+}
+impl Config {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let raw = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read `{}`", path.display()))?;
+        ron::from_str(&raw).with_context(|| format!("Failed to parse `{}`", path.display()))
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Team {
+    /// Emails of the group members whose bookings make up the team schedule
+    pub members: Vec<String>,
+    /// Labels of the tools the group books
+    pub tools: Vec<String>,
+}
+impl Team {
+    pub fn member_index(&self, email: &str) -> Option<usize> {
+        self.members.iter().position(|member| member.eq_ignore_ascii_case(email.trim()))
+    }
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct Openings {
+    /// Setup time trimmed from the start of every opening, in minutes
+    pub lead_minutes: i64,
+    /// Cleanup time trimmed from the end of every opening, in minutes
+    pub trail_minutes: i64,
+    /// Openings shorter than this many minutes are hidden
+    pub min_minutes: i64,
+    /// Openings are clipped to start no earlier than this time of day, like `Some("07:00:00")`
+    pub earliest_start: Option<NaiveTime>,
+    /// Working hours as `(open, close)`, like `Some(("06:00:00", "22:00:00"))`.
+    /// Openings outside them are hidden. 8am to 5pm when unset.
+    pub hours: Option<(NaiveTime, NaiveTime)>,
+    /// Days the lab is closed, like `Some(["Fri", "Sat"])`. Saturday and Sunday when unset.
+    pub closed_days: Option<Vec<Weekday>>,
+    /// Dates the lab is closed, like `["2022-12-25"]`
+    pub holidays: Vec<NaiveDate>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct History {
+    /// How many days back the tool history view looks
+    pub days: u64,
+}
+impl Default for History {
+    fn default() -> Self {
+        Self { days: 30 }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Projects {
+    /// Initial order of project lists, one of `Server`, `Name` or `Id`
+    pub sort: ProjectSort,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    /// Log out and return to the login screen after this many minutes
+    /// without input at the main menu. Off when unset.
+    pub idle_timeout_minutes: Option<u64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_member_index() {
+        let team = Team {
+            members: vec!["wjames@ualberta.ca".into(), "ksetzer@ualberta.ca".into()],
+            tools: vec![],
+        };
+        assert_eq!(team.member_index("ksetzer@ualberta.ca"), Some(1));
+        // Emails scraped from the site can differ in case and carry stray spaces
+        assert_eq!(team.member_index(" WJames@ualberta.ca "), Some(0));
+        assert_eq!(team.member_index("dmildenb@ualberta.ca"), None);
+        assert_eq!(team.member_index(""), None);
+    }
+}
@@ -0,0 +1,29 @@
+use std::{fs::OpenOptions, io::Write, path::Path, sync::Mutex};
+
+use crate::html::Element;
+
+static LOG: Mutex<Option<std::fs::File>> = Mutex::new(None);
+
+/// Start appending scraper extractions and dropped errors to the file at `path`
+pub fn enable(path: impl AsRef<Path>) -> std::io::Result<()> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    *LOG.lock().unwrap() = Some(file);
+    Ok(())
+}
+
+/// Record a field scraped from the server's markup along with the element it came from,
+/// so extractions can be compared against expectations when the markup drifts
+pub fn extraction(field: &str, value: &str, source: &Element) {
+    let mut log = LOG.lock().unwrap();
+    let Some(file) = log.as_mut() else { return };
+    let now = chrono::Local::now().format("%F %T");
+    writeln!(file, "{now} {field} = {value:?} from {}", source.open_tag()).ok();
+}
+
+/// Record an error that is dropped rather than shown, like a lookup only used for display
+pub fn failure(what: &str, err: &anyhow::Error) {
+    let mut log = LOG.lock().unwrap();
+    let Some(file) = log.as_mut() else { return };
+    let now = chrono::Local::now().format("%F %T");
+    writeln!(file, "{now} {what} failed: {err:#}").ok();
+}
@@ -0,0 +1,102 @@
+use itertools::Itertools;
+
+const MATCH_SCORE: i64 = 1;
+const CONSECUTIVE_BONUS: i64 = 5;
+const WORD_START_BONUS: i64 = 3;
+const MAX_GAP_PENALTY: i64 = 3;
+
+/// Lowercased alphanumeric characters of `s`, each flagged with whether it starts a word
+fn normalize(s: &str) -> Vec<(char, bool)> {
+    let mut prev_alnum = false;
+    let mut chars = vec![];
+    for c in s.chars() {
+        if c.is_alphanumeric() {
+            chars.extend(c.to_lowercase().map(|lc| (lc, !prev_alnum)));
+        }
+        prev_alnum = c.is_alphanumeric();
+    }
+    chars
+}
+
+fn score_from(query: &[(char, bool)], candidate: &[(char, bool)], start: usize) -> Option<i64> {
+    let mut score = 0;
+    let mut prev: Option<usize> = None;
+    let mut pos = start;
+    for &(qc, _) in query {
+        let i = pos + candidate[pos..].iter().position(|&(cc, _)| cc == qc)?;
+        score += MATCH_SCORE;
+        if candidate[i].1 {
+            score += WORD_START_BONUS;
+        }
+        match prev {
+            Some(p) if p + 1 == i => score += CONSECUTIVE_BONUS,
+            Some(p) => score -= ((i - p - 1) as i64).min(MAX_GAP_PENALTY),
+            None => {}
+        }
+        prev = Some(i);
+        pos = i + 1;
+    }
+    Some(score)
+}
+
+/// Score how well `query` matches `candidate` as a case-insensitive subsequence,
+/// ignoring whitespace and punctuation. Consecutive runs and matches at the start
+/// of words score higher, gaps score lower. `None` if `query` is not a subsequence.
+pub fn score(query: &str, candidate: &str) -> Option<i64> {
+    let query = normalize(query);
+    let candidate = normalize(candidate);
+    let Some(&(first, _)) = query.first() else {
+        return Some(0);
+    };
+    candidate
+        .iter()
+        .positions(|&(c, _)| c == first)
+        .filter_map(|start| score_from(&query, &candidate, start))
+        .max()
+}
+
+/// `score` relative to a single consecutive run starting at a word, in `0.0..=1.0`
+pub fn confidence(query: &str, candidate: &str) -> Option<f64> {
+    let len = normalize(query).len() as i64;
+    if len == 0 {
+        return Some(1.0);
+    }
+    let perfect = len * MATCH_SCORE + WORD_START_BONUS + (len - 1) * CONSECUTIVE_BONUS;
+    score(query, candidate).map(|s| (s.max(0) as f64 / perfect as f64).min(1.0))
+}
+
+/// Items that match `query`, best first, paired with their `confidence`
+pub fn rank<T>(
+    query: &str,
+    items: impl IntoIterator<Item = T>,
+    key: impl Fn(&T) -> &str,
+) -> Vec<(T, f64)> {
+    items
+        .into_iter()
+        .filter_map(|item| confidence(query, key(&item)).map(|c| (item, c)))
+        .sorted_by(|(_, a), (_, b)| b.total_cmp(a))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_confidence() {
+        assert_eq!(confidence("MLA 150", "Heidelberg MLA150"), Some(1.0));
+        assert_eq!(confidence("mla150", "Heidelberg MLA150"), Some(1.0));
+        assert!(confidence("heidel mla", "Heidelberg MLA150").unwrap() > 0.8);
+        assert!(confidence("mla", "Multi Laser Anneal").unwrap() < 0.5);
+        assert_eq!(confidence("mla", "Mask Aligner"), None);
+        assert_eq!(confidence("xyz", "Heidelberg MLA150"), None);
+    }
+
+    #[test]
+    fn test_rank() {
+        let labels = ["Mask Aligner", "Heidelberg MLA150", "Plasmalab 150"];
+        let ranked = rank("mla150", labels, |label| label);
+        assert_eq!(ranked[0].0, "Heidelberg MLA150");
+        assert!(ranked.iter().all(|(label, _)| *label != "Mask Aligner"));
+    }
+}
@@ -1,80 +1,203 @@
 use anyhow::anyhow;
 use nom::{
     branch::alt,
-    bytes::complete::{is_not, tag, take_until},
+    bytes::complete::{is_not, tag, tag_no_case, take_until},
     character::complete::{alpha1, alphanumeric1, char, multispace1},
-    combinator::{map, opt, recognize, verify},
-    error::{ContextError, ParseError, VerboseError},
+    combinator::{map, not, opt, recognize, verify},
+    error::{ContextError, ParseError, VerboseError, VerboseErrorKind},
     multi::{many0, many1, separated_list0},
-    sequence::{delimited, pair, terminated},
+    sequence::{delimited, pair, preceded, terminated},
     IResult,
 };
-use std::{collections::BTreeMap, fmt::Display, option::Option, ptr::addr_of, str::FromStr};
-
-#[derive(Debug, Clone)]
-enum MaybeParsed<S, T> {
-    NotParsed(S),
-    Parsed(T),
-}
-impl<S, T> MaybeParsed<S, T> {
-    fn as_parsed(self) -> Option<T> {
-        match self {
-            MaybeParsed::NotParsed(_) => None,
-            MaybeParsed::Parsed(inner) => Some(inner),
-        }
-    }
-    fn as_ref_parsed(&self) -> Option<&T> {
-        match self {
-            MaybeParsed::NotParsed(_) => None,
-            MaybeParsed::Parsed(inner) => Some(inner),
-        }
-    }
-    fn as_ref_unparsed(&self) -> Option<&S> {
-        match self {
-            MaybeParsed::NotParsed(s) => Some(s),
-            MaybeParsed::Parsed(_) => None,
-        }
-    }
-}
+use std::{collections::BTreeMap, fmt::Display, option::Option, str::FromStr, sync::OnceLock};
 
 #[derive(Debug, Clone)]
 pub struct Element {
     name: String,
     attrs: BTreeMap<String, String>,
-    contents: MaybeParsed<String, Vec<Content>>,
+    /// The unparsed markup between the open and close tags
+    source: String,
+    /// `source` parsed on first use, so untouched subtrees never get parsed
+    contents: OnceLock<Vec<Content>>,
+    /// How `source` is to be parsed, the same as for the element itself
+    options: ParseOptions,
 }
 impl Element {
     pub fn get_attr(&self, key: &str) -> Option<&str> {
         self.attrs.get(key).map(|s| s.as_str())
     }
+    /// Every attribute as a key-value pair, sorted by key
+    ///
+    /// ```
+    /// # use nanofab_cli::html::Element;
+    /// let input = r#"<input type="date" name="start_date">"#.parse::<Element>()?;
+    /// let attrs: Vec<_> = input.attrs().collect();
+    /// assert_eq!(attrs, [("name", "start_date"), ("type", "date")]);
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn attrs(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.attrs.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+    /// Like `get_attr`, but matches `key` in any case. Parsed attribute names are already
+    /// lowercase, this is for keys the caller can't guarantee are.
+    pub fn get_attr_ci(&self, key: &str) -> Option<&str> {
+        self.attrs.iter().find(|(k, _)| k.eq_ignore_ascii_case(key)).map(|(_, v)| v.as_str())
+    }
+    /// The element's opening tag, e.g. `<div class="columns" id="main">`
+    pub fn open_tag(&self) -> String {
+        let attrs = self.attrs.iter().map(|(k, v)| format!(" {k}=\"{v}\""));
+        format!("<{}{}>", self.name, attrs.collect::<String>())
+    }
+    /// Render the element back to markup, re-encoding entities and self-closing void elements.
+    /// Unlike `Display`, this is meant to be parsed again.
+    pub fn to_html(&self) -> String {
+        let mut html = String::new();
+        self.write_html(&mut html);
+        html
+    }
+    fn write_html(&self, html: &mut String) {
+        html.push('<');
+        html.push_str(&self.name);
+        for (key, value) in &self.attrs {
+            html.push_str(&format!(" {key}=\"{}\"", encode_entities(value)));
+        }
+        if VOID_ELEMENTS.contains(&self.name.as_str()) {
+            html.push_str("/>");
+            return;
+        }
+        html.push('>');
+        let is_raw_text = RAW_TEXT_ELEMENTS.contains(&self.name.as_str());
+        for content in self.iter_contents() {
+            match content {
+                Content::Text(text) if is_raw_text => html.push_str(text),
+                Content::Text(text) => html.push_str(&encode_entities(text)),
+                Content::Element(elem) => elem.write_html(html),
+            }
+        }
+        html.push_str(&format!("</{}>", self.name));
+    }
+    /// Descendants with tag `name`, if given, and the exact attribute `(key, value)`, if given
+    pub fn select<'a>(
+        &'a self,
+        name: Option<&'a str>,
+        attr: Option<(&'a str, &'a str)>,
+    ) -> impl Iterator<Item = &'a Element> + 'a {
+        self.iter_decendents()
+            .filter(move |elem| name.is_none_or(|name| elem.name.eq_ignore_ascii_case(name)))
+            .filter(move |elem| attr.is_none_or(|(key, value)| elem.get_attr(key) == Some(value)))
+    }
+    /// The first descendant whose `id` attribute is exactly `id`
+    ///
+    /// ```
+    /// # use nanofab_cli::html::Element;
+    /// let root = r#"<form><select id="sel_project_id"></select></form>"#.parse::<Element>()?;
+    /// assert!(root.find_by_id("sel_project_id").is_some());
+    /// assert!(root.find_by_id("sel_project").is_none());
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn find_by_id(&self, id: &str) -> Option<&Element> {
+        self.iter_decendents().find_attr("id", |v| v == id)
+    }
+    /// The first descendant whose `name` attribute is exactly `name`
+    ///
+    /// ```
+    /// # use nanofab_cli::html::Element;
+    /// let html = r#"<form><input name="nonce" value="a"/><input name="nonce_key" value="b"/></form>"#;
+    /// let root = html.parse::<Element>()?;
+    /// assert_eq!(root.find_by_name("nonce_key").and_then(|e| e.get_attr("value")), Some("b"));
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn find_by_name(&self, name: &str) -> Option<&Element> {
+        self.iter_decendents().find_attr("name", |v| v == name)
+    }
+    /// All text inside the element in document order, joined with single spaces
+    pub fn text_content(&self) -> String {
+        let mut texts = vec![];
+        self.collect_text(&mut texts);
+        texts.join(" ")
+    }
+    fn collect_text<'a>(&'a self, texts: &mut Vec<&'a str>) {
+        for content in self.iter_contents() {
+            match content {
+                Content::Text(text) if !text.trim().is_empty() => texts.push(text.trim()),
+                Content::Text(_) => {}
+                Content::Element(elem) => elem.collect_text(texts),
+            }
+        }
+    }
     pub fn iter_contents(&self) -> impl Iterator<Item = &Content> {
-        unsafe { self.force_parse() };
-        self.contents.as_ref_parsed().expect("Just parsed").iter()
+        self.contents().iter()
     }
     pub fn iter_children(&self) -> impl Iterator<Item = &Element> {
         self.iter_contents().filter_map(|c| c.as_ref_element())
     }
-    pub fn iter_decendents<'a>(&'a self) -> Box<dyn Iterator<Item = &Element> + 'a> {
-        Box::new(
-            self.iter_children()
-                .map(|elem| Some(elem).into_iter().chain(elem.iter_decendents()))
-                .flatten(),
-        )
+    /// All elements below this one in document order
+    pub fn iter_decendents<'a>(&'a self) -> Box<dyn Iterator<Item = &'a Element> + 'a> {
+        Box::new(Decendents::new(self, None))
+    }
+    /// Like `iter_decendents`, but only `max_depth` levels down, so `1` is just the children
+    pub fn iter_decendents_depth(&self, max_depth: usize) -> impl Iterator<Item = &Element> {
+        Decendents::new(self, Some(max_depth))
     }
     pub fn into_iter_contents(self) -> impl Iterator<Item = Content> {
-        unsafe { self.force_parse() };
-        self.contents.as_parsed().expect("Just parsed").into_iter()
+        self.contents();
+        self.contents.into_inner().expect("Just parsed").into_iter()
     }
     pub fn into_iter_children(self) -> impl Iterator<Item = Element> {
-        self.into_iter_contents().filter_map(|c| c.as_element())
+        self.into_iter_contents().filter_map(|c| c.into_element())
+    }
+    fn contents(&self) -> &Vec<Content> {
+        self.contents.get_or_init(|| {
+            many0(|i| xml_content_with::<()>(i, self.options))(&self.source).unwrap().1
+        })
     }
-    unsafe fn force_parse(&self) {
-        let Some(i) = self.contents.as_ref_unparsed()else{return};
-        let (_, contents) = many0(xml_content::<()>)(i).unwrap();
-        let contents_ptr = addr_of!(self.contents) as *mut MaybeParsed<String, _>;
-        *contents_ptr = MaybeParsed::Parsed(contents);
+    fn with_contents(
+        name: String,
+        attrs: BTreeMap<String, String>,
+        contents: Vec<Content>,
+    ) -> Self {
+        let contents = OnceLock::from(contents);
+        Self { name, attrs, source: String::new(), contents, options: ParseOptions::default() }
     }
 }
+/// Depth-first walk over an element's decendents, keeping its own stack of
+/// partly visited levels so deep trees can't overflow the call stack
+struct Decendents<'a> {
+    stack: Vec<(std::slice::Iter<'a, Content>, usize)>,
+    max_depth: Option<usize>,
+}
+impl<'a> Decendents<'a> {
+    fn new(root: &'a Element, max_depth: Option<usize>) -> Self {
+        let mut stack = vec![];
+        if max_depth != Some(0) {
+            stack.push((root.contents().iter(), 1));
+        }
+        Self { stack, max_depth }
+    }
+}
+impl<'a> Iterator for Decendents<'a> {
+    type Item = &'a Element;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (contents, depth) = self.stack.last_mut()?;
+            match contents.next() {
+                Some(Content::Element(elem)) => {
+                    let depth = *depth;
+                    if self.max_depth.is_none_or(|max_depth| depth < max_depth) {
+                        self.stack.push((elem.contents().iter(), depth + 1));
+                    }
+                    return Some(elem);
+                }
+                Some(Content::Text(_)) => {}
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+    }
+}
+
 impl Display for Element {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Element").field("name", &self.name).field("attrs", &self.attrs).finish()?;
@@ -93,23 +216,84 @@ impl FromStr for Element {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match xml_element::<VerboseError<&str>>(s.trim()) {
-            Ok((_, elem)) => Ok(elem),
-            Err(nom::Err::Error(e) | nom::Err::Failure(e)) => Err(anyhow!(e.to_string())),
-            _ => unimplemented!(),
+        parse_with(s, ParseOptions::default())
+    }
+}
+
+/// Like `Element::from_str`, but text keeps its surrounding whitespace, so
+/// `<b> x </b>` holds `" x "` rather than `"x"`
+pub fn parse_preserving_ws(s: &str) -> anyhow::Result<Element> {
+    parse_with(s, ParseOptions { preserve_ws: true, ..Default::default() })
+}
+
+fn parse_with(s: &str, options: ParseOptions) -> anyhow::Result<Element> {
+    let element_p = |i| xml_element_with::<VerboseError<&str>>(i, options);
+    match preceded(xml_multispace0, element_p)(s.trim()) {
+        Ok((_, elem)) => Ok(elem),
+        Err(e) => Err(parse_error(s, e)),
+    }
+}
+
+/// Parse a run of top-level sibling elements, for responses with no single wrapping element
+pub fn parse_fragment(s: &str) -> anyhow::Result<Vec<Element>> {
+    let element_p = delimited(xml_multispace0, xml_element, xml_multispace0);
+    match many0(element_p)(s) {
+        Ok(("", elements)) => Ok(elements),
+        Ok((rest, _)) => {
+            let rest = VerboseError { errors: vec![(rest, VerboseErrorKind::Context("element"))] };
+            Err(parse_error(s, nom::Err::Error(rest)))
+        }
+        Err(e) => Err(parse_error(s, e)),
+    }
+}
+
+/// Like `parse_fragment`, but for markup that leaves elements unclosed. An unclosed
+/// element ends at its parent's close tag or the next open tag of the same name, so
+/// `<p>a<p>b` gives two sibling paragraphs. `Element::from_str` stays strict.
+pub fn parse_lenient(s: &str) -> anyhow::Result<Vec<Element>> {
+    let options = ParseOptions { lenient: true, ..Default::default() };
+    let element_p = delimited(
+        xml_multispace0,
+        move |i| xml_element_with::<VerboseError<&str>>(i, options),
+        xml_multispace0,
+    );
+    match many0(element_p)(s) {
+        Ok(("", elements)) => Ok(elements),
+        Ok((rest, _)) => {
+            let rest = VerboseError { errors: vec![(rest, VerboseErrorKind::Context("element"))] };
+            Err(parse_error(s, nom::Err::Error(rest)))
         }
+        Err(e) => Err(parse_error(s, e)),
     }
 }
+
+fn parse_error(s: &str, e: nom::Err<VerboseError<&str>>) -> anyhow::Error {
+    let e = match e {
+        nom::Err::Error(e) | nom::Err::Failure(e) => e,
+        nom::Err::Incomplete(_) => return anyhow!("parse error: input ended early"),
+    };
+    let Some((rest, kind)) = e.errors.first() else {
+        return anyhow!("parse error: {e}");
+    };
+    // `rest` is a suffix of `s`, so the pointers give how far parsing got
+    let offset = rest.as_ptr() as usize - s.as_ptr() as usize;
+    let snippet = rest.chars().take(40).collect::<String>();
+    anyhow!("parse error at byte {offset}: {snippet:?} ({kind:?})")
+}
 pub trait ElementIter<'e>: Iterator<Item = &'e Element> + Sized + 'e {
     fn filter_attr(
         self,
         key: &'e str,
         value_predicate: impl Fn(&str) -> bool + 'e,
-    ) -> Box<dyn Iterator<Item = &Element> + 'e> {
+    ) -> Box<dyn Iterator<Item = &'e Element> + 'e> {
         Box::new(self.filter(
             move |elem| matches!(elem.get_attr(key), Some(value) if value_predicate(value)),
         ))
     }
+    /// Elements whose `class` attribute contains `class` as a whole token
+    fn filter_class(self, class: &'e str) -> Box<dyn Iterator<Item = &'e Element> + 'e> {
+        self.filter_attr("class", move |value| value.split_whitespace().any(|t| t == class))
+    }
     fn find_attr(
         &mut self,
         key: &'e str,
@@ -126,13 +310,13 @@ pub enum Content {
     Element(Element),
 }
 impl Content {
-    pub fn as_text(self) -> Option<String> {
+    pub fn into_text(self) -> Option<String> {
         match self {
             Content::Text(t) => Some(t),
             Content::Element(_) => None,
         }
     }
-    pub fn as_element(self) -> Option<Element> {
+    pub fn into_element(self) -> Option<Element> {
         match self {
             Content::Text(_) => None,
             Content::Element(elem) => Some(elem),
@@ -160,6 +344,12 @@ impl Display for Content {
     }
 }
 
+/// Elements that never have contents or a close tag, like `<br>`
+const VOID_ELEMENTS: &[&str] = &["br", "img", "input", "hr", "meta", "link"];
+
+/// Elements whose contents are kept as raw text rather than parsed, like `<script>`
+const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style"];
+
 #[derive(Debug, PartialEq, Eq)]
 struct Tag {
     name: String,
@@ -167,33 +357,91 @@ struct Tag {
     is_close: bool,
 }
 
-fn xml_element<'i, E>(i: &'i str) -> IResult<&str, Element, E>
+#[derive(Debug, Clone, Copy, Default)]
+struct ParseOptions {
+    /// Keep text exactly as written instead of trimming it
+    preserve_ws: bool,
+    /// Implicitly close elements that are missing a close tag, see `parse_lenient`
+    lenient: bool,
+}
+
+fn xml_element<'i, E>(i: &'i str) -> IResult<&'i str, Element, E>
+where
+    E: ContextError<&'i str> + ParseError<&'i str>,
+{
+    xml_element_with(i, ParseOptions::default())
+}
+
+fn xml_element_with<'i, E>(i: &'i str, options: ParseOptions) -> IResult<&'i str, Element, E>
 where
     E: ContextError<&'i str> + ParseError<&'i str>,
 {
     let (i, Tag { name, attrs, .. }) = verify(xml_tag, |t| !t.is_close)(i)?;
-    let close_tag_p = verify(xml_tag, |t| t.is_close && t.name == name);
+    if VOID_ELEMENTS.iter().any(|void| void.eq_ignore_ascii_case(&name)) {
+        return Ok((i, Element::with_contents(name, attrs, vec![])));
+    }
+    if RAW_TEXT_ELEMENTS.iter().any(|raw| raw.eq_ignore_ascii_case(&name)) {
+        // Lowercasing ASCII keeps byte offsets, so `end` indexes into `i` too
+        let close = format!("</{}", name.to_ascii_lowercase());
+        if let Some(end) = i.to_ascii_lowercase().find(&close) {
+            let (body, rest) = i.split_at(end);
+            let (rest, _) = verify(xml_tag, |t| t.is_close)(rest)?;
+            let contents = match body.is_empty() {
+                true => vec![],
+                false => vec![Content::Text(body.to_string())],
+            };
+            return Ok((rest, Element::with_contents(name, attrs, contents)));
+        }
+    }
+    let close_tag_p = verify(xml_tag, |t| t.is_close && t.name.eq_ignore_ascii_case(&name));
     let i_before = i;
-    let (i_after, maybe_contents) = opt(terminated(recognize(many0(xml_content)), close_tag_p))(i)?;
-    let (i, contents) = match maybe_contents {
-        Some(contents) => (i_after, MaybeParsed::NotParsed(contents.into())),
-        None => (i_before, MaybeParsed::Parsed(vec![])),
-    };
-    Ok((i, Element { name, attrs, contents }))
+    let contents_p = recognize(many0(|i| xml_content_with(i, options)));
+    let (i_after, maybe_contents) = opt(terminated(contents_p, close_tag_p))(i)?;
+    match maybe_contents {
+        Some(source) => {
+            let source = source.to_string();
+            Ok((i_after, Element { name, attrs, source, contents: OnceLock::new(), options }))
+        }
+        None if options.lenient => {
+            // With no close tag, the element ends where its parent does or where
+            // another element of the same name opens, like `<p>` does in HTML
+            let same_name_open_p = verify(xml_tag, |t| !t.is_close && t.name == name);
+            let content_p = preceded(not(same_name_open_p), |i| xml_content_with(i, options));
+            let (i, source) = recognize(many0(content_p))(i_before)?;
+            let source = source.to_string();
+            Ok((i, Element { name, attrs, source, contents: OnceLock::new(), options }))
+        }
+        None => Ok((i_before, Element::with_contents(name, attrs, vec![]))),
+    }
 }
 
-fn xml_content<'i, E>(i: &'i str) -> IResult<&str, Content, E>
+fn xml_content_with<'i, E>(i: &'i str, options: ParseOptions) -> IResult<&'i str, Content, E>
 where
     E: ContextError<&'i str> + ParseError<&'i str>,
 {
-    let to_trim_string = |s: &str| s.trim().to_string();
+    let to_text = |s: &str| match options.preserve_ws {
+        true => decode_entities(s),
+        false => decode_entities(s.trim()),
+    };
     let not_empty = |s: &str| !s.is_empty();
-    let trim_text_p = verify(map(is_not("<"), to_trim_string), not_empty);
-    let element_p = delimited(xml_multispace0, xml_element, xml_multispace0);
-    alt((map(element_p, Content::Element), map(trim_text_p, Content::Text)))(i)
+    let text_p = verify(map(is_not("<"), to_text), not_empty);
+    // Whitespace around elements is only skipped when text is trimmed anyway
+    let element_p = |i| match options.preserve_ws {
+        true => xml_element_with(i, options),
+        false => delimited(xml_multispace0, |i| xml_element_with(i, options), xml_multispace0)(i),
+    };
+    let cdata_p = map(xml_cdata, |s: &str| Content::Text(s.to_string()));
+    alt((map(element_p, Content::Element), cdata_p, map(text_p, Content::Text)))(i)
+}
+
+/// The literal inside of a `<![CDATA[ ... ]]>` section, with no entity decoding
+fn xml_cdata<'i, E: ParseError<&'i str>>(i: &'i str) -> IResult<&'i str, &'i str, E> {
+    let start = "<![CDATA[";
+    let end = "]]>";
+    delimited(tag(start), take_until(end), tag(end))(i)
 }
 
-fn xml_tag<'i, E: ParseError<&'i str>>(i: &'i str) -> IResult<&str, Tag, E> {
+fn xml_tag<'i, E: ParseError<&'i str>>(i: &'i str) -> IResult<&'i str, Tag, E> {
     let attrs_p = separated_list0(xml_multispace1, xml_attr);
     let (i, _) = char('<')(i)?;
     let (i, start_slash) = opt(char('/'))(i)?;
@@ -203,45 +451,101 @@ fn xml_tag<'i, E: ParseError<&'i str>>(i: &'i str) -> IResult<&str, Tag, E> {
     let (i, _) = xml_multispace0(i)?;
     let (i, _) = opt(char('/'))(i)?;
     let (i, _) = char('>')(i)?;
-    let name = name_str.to_string();
+    // Tag names are case-insensitive, so store them lowercased for predictable comparisons
+    let name = name_str.to_ascii_lowercase();
     let attrs = maybe_attrs_vec.unwrap_or_default().into_iter().collect();
     let is_close = start_slash.is_some();
     Ok((i, Tag { name, attrs, is_close }))
 }
 
-fn xml_attr<'i, E: ParseError<&'i str>>(i: &'i str) -> IResult<&str, (String, String), E> {
-    let value_p = delimited(tag("=\""), take_until("\""), tag("\""));
+fn xml_attr<'i, E: ParseError<&'i str>>(i: &'i str) -> IResult<&'i str, (String, String), E> {
+    let double_quoted_p = delimited(tag("\""), take_until("\""), tag("\""));
+    let single_quoted_p = delimited(tag("'"), take_until("'"), tag("'"));
+    let unquoted_p = is_not(" \t\r\n>");
+    let value_p = preceded(char('='), alt((double_quoted_p, single_quoted_p, unquoted_p)));
     let (i, name_str) = xml_name(i)?;
     let (i, maybe_value_str) = opt(value_p)(i)?;
-    let name = name_str.to_string();
-    let value = maybe_value_str.unwrap_or_default().to_string();
+    let name = name_str.to_ascii_lowercase();
+    let value = decode_entities(maybe_value_str.unwrap_or_default());
     Ok((i, (name, value)))
 }
 
-fn xml_name<'i, E: ParseError<&'i str>>(i: &'i str) -> IResult<&str, &str, E> {
+/// The reverse of `decode_entities` for the characters that can't appear as-is in markup
+fn encode_entities(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Replace character references like `&amp;`, `&#39;` and `&#x27;` with the
+/// characters they stand for. Unknown or malformed ones are kept verbatim.
+fn decode_entities(s: &str) -> String {
+    let mut decoded = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(amp) = rest.find('&') {
+        decoded.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let entity = rest[1..].find(';').map(|semi| &rest[1..semi + 1]);
+        let c = entity.and_then(|entity| match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some('\u{a0}'),
+            _ => match entity.strip_prefix('#')? {
+                hex if hex.starts_with(['x', 'X']) => u32::from_str_radix(&hex[1..], 16).ok(),
+                dec => dec.parse::<u32>().ok(),
+            }
+            .and_then(char::from_u32),
+        });
+        match (c, entity) {
+            (Some(c), Some(entity)) => {
+                decoded.push(c);
+                rest = &rest[entity.len() + 2..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+fn xml_name<'i, E: ParseError<&'i str>>(i: &'i str) -> IResult<&'i str, &'i str, E> {
     let start_p = alt((alpha1, tag("_")));
     let rest_p = alt((alphanumeric1, tag("-"), tag("_"), tag(".")));
     recognize(pair(start_p, many0(rest_p)))(i)
 }
 
-fn xml_multispace1<'i, E: ParseError<&'i str>>(i: &'i str) -> IResult<&str, &str, E> {
-    recognize(many1(alt((multispace1, xml_comment))))(i)
+fn xml_multispace1<'i, E: ParseError<&'i str>>(i: &'i str) -> IResult<&'i str, &'i str, E> {
+    recognize(many1(alt((multispace1, xml_comment, xml_doctype, xml_processing_instruction))))(i)
 }
 
-fn xml_multispace0<'i, E: ParseError<&'i str>>(i: &'i str) -> IResult<&str, &str, E> {
-    recognize(many0(alt((multispace1, xml_comment))))(i)
+fn xml_multispace0<'i, E: ParseError<&'i str>>(i: &'i str) -> IResult<&'i str, &'i str, E> {
+    recognize(many0(alt((multispace1, xml_comment, xml_doctype, xml_processing_instruction))))(i)
 }
 
-fn xml_comment<'i, E: ParseError<&'i str>>(i: &'i str) -> IResult<&str, &str, E> {
+fn xml_comment<'i, E: ParseError<&'i str>>(i: &'i str) -> IResult<&'i str, &'i str, E> {
     let start = "<!--";
     let end = "-->";
     delimited(tag(start), take_until(end), tag(end))(i)
 }
 
-#[cfg(test)]
-mod tests {
-    use std::mem::size_of_val;
+fn xml_doctype<'i, E: ParseError<&'i str>>(i: &'i str) -> IResult<&'i str, &'i str, E> {
+    recognize(delimited(tag_no_case("<!doctype"), opt(is_not(">")), tag(">")))(i)
+}
 
+fn xml_processing_instruction<'i, E: ParseError<&'i str>>(
+    i: &'i str,
+) -> IResult<&'i str, &'i str, E> {
+    let start = "<?";
+    let end = "?>";
+    delimited(tag(start), take_until(end), tag(end))(i)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
     use itertools::Itertools;
     use nom::error::VerboseError;
 
@@ -249,7 +553,7 @@ mod tests {
 
     #[test]
     fn test_parse_xml() {
-        let (rest, mut root) = xml_element::<VerboseError<&str>>(TEST2).unwrap();
+        let (rest, root) = xml_element::<VerboseError<&str>>(TEST2).unwrap();
         root.iter_children().for_each(|elem| elem.iter_children().for_each(|_| ()));
         println!("{root}");
         println!("Rest: `{rest}`");
@@ -267,9 +571,290 @@ mod tests {
         // assert_eq!(pretty, RESULT1);
     }
 
+    #[test]
+    fn test_decode_entities() {
+        assert_eq!(decode_entities("Wyatt James &amp; Co"), "Wyatt James & Co");
+        assert_eq!(decode_entities("it&#39;s &lt;b&gt;&quot;ok&quot;&#x27;"), "it's <b>\"ok\"'");
+        assert_eq!(decode_entities("&apos;&#X41;&#66;"), "'AB");
+        assert_eq!(decode_entities("&bogus; & &amp &#xZZ; &;"), "&bogus; & &amp &#xZZ; &;");
+        assert_eq!(decode_entities("no entities"), "no entities");
+    }
+
+    #[test]
+    fn test_decoded_text_and_attrs() {
+        let root = "<span title=\"6:00am &amp; later\">Wyatt James &amp; Co</span>"
+            .parse::<Element>()
+            .unwrap();
+        assert_eq!(root.get_attr("title"), Some("6:00am & later"));
+        let text = root.iter_contents().find_map(|c| c.as_ref_text());
+        assert_eq!(text, Some("Wyatt James & Co"));
+    }
+
+    #[test]
+    fn test_void_elements() {
+        let root = "<div><br><span>hi</span><input type=\"hidden\" value=\"1\"></div>"
+            .parse::<Element>()
+            .unwrap();
+        let children = root.iter_children().map(|elem| elem.name.as_str()).collect_vec();
+        assert_eq!(children, ["br", "span", "input"]);
+        assert_eq!(root.iter_children().next().unwrap().iter_contents().count(), 0);
+    }
+
+    #[test]
+    fn test_text_content() {
+        let root = TEST2.parse::<Element>().unwrap();
+        let booking = root.iter_decendents().find_attr("id", |v| v == "booking-191730").unwrap();
+        assert_eq!(booking.text_content(), "6:00 9:00 wjames");
+        let start_cell = booking.iter_decendents().find_attr("class", |v| v == "columns small-4");
+        assert_eq!(start_cell.unwrap().text_content(), "6:00");
+        let head = root.iter_decendents().find_attr("class", |v| v == "table-head").unwrap();
+        assert_eq!(head.text_content(), "start stop user");
+    }
+
+    #[test]
+    fn test_filter_class() {
+        let root =
+            "<div><div class=\"columns small-4\">a</div><div class=\"columns-inner\">b</div></div>"
+                .parse::<Element>()
+                .unwrap();
+        let texts = |class| root.iter_children().filter_class(class).map(|e| e.text_content());
+        assert_eq!(texts("columns").collect_vec(), ["a"]);
+        assert_eq!(texts("small-4").collect_vec(), ["a"]);
+        assert_eq!(texts("columns-inner").collect_vec(), ["b"]);
+        assert_eq!(texts("small").count(), 0);
+    }
+
+    #[test]
+    fn test_parse_error_offset() {
+        let err = "  <div class=\"row\" <span>hi</span></div>".parse::<Element>().unwrap_err();
+        let msg = err.to_string();
+        assert!(msg.starts_with("parse error at byte 19: \"<span>hi</span></div>\""), "{msg}");
+    }
+
+    #[test]
+    fn test_attr_quoting() {
+        let root = "<td title='it\"s' width=100 class=\"a b\" data-tooltip>x</td>"
+            .parse::<Element>()
+            .unwrap();
+        assert_eq!(root.get_attr("title"), Some("it\"s"));
+        assert_eq!(root.get_attr("width"), Some("100"));
+        assert_eq!(root.get_attr("class"), Some("a b"));
+        assert_eq!(root.get_attr("data-tooltip"), Some(""));
+        assert_eq!(root.text_content(), "x");
+    }
+
+    #[test]
+    fn test_raw_text_elements() {
+        let script = "if (a < b && c > d) { el.innerHTML = '<div>'; }";
+        let html =
+            format!("<div><script type=\"text/javascript\">{script}</SCRIPT><p>after</p></div>");
+        let root = html.parse::<Element>().unwrap();
+        let children = root.iter_children().collect_vec();
+        assert_eq!(children.iter().map(|e| e.name.as_str()).collect_vec(), ["script", "p"]);
+        let contents = children[0].iter_contents().collect_vec();
+        assert_eq!(contents.len(), 1);
+        assert_eq!(contents[0].as_ref_text(), Some(script));
+    }
+
+    #[test]
+    fn test_close_tag_case() {
+        let root = "<Section class=\"active\"><P>hi</p></SECTION>".parse::<Element>().unwrap();
+        assert_eq!(root.name, "section");
+        let child = root.iter_children().exactly_one().ok().unwrap();
+        assert_eq!(child.name, "p");
+        assert_eq!(child.text_content(), "hi");
+    }
+
+    #[test]
+    fn test_parse_fragment() {
+        let elements =
+            parse_fragment("\n <div id=\"a\">one</div>\n<div id=\"b\"><br>two</div> ").unwrap();
+        let ids = elements.iter().map(|e| e.get_attr("id").unwrap()).collect_vec();
+        assert_eq!(ids, ["a", "b"]);
+        assert_eq!(elements[1].text_content(), "two");
+        assert!(parse_fragment("").unwrap().is_empty());
+        let err = parse_fragment("<div>one</div> stray").unwrap_err();
+        assert!(err.to_string().starts_with("parse error at byte 15: \"stray\""), "{err}");
+    }
+
+    #[test]
+    fn test_to_html() {
+        let html = "<div class=\"a&amp;b\"><br><span>x &lt; y</span><script>a < b</script></div>";
+        let root = html.parse::<Element>().unwrap();
+        assert_eq!(
+            root.to_html(),
+            "<div class=\"a&amp;b\"><br/><span>x &lt; y</span><script>a < b</script></div>"
+        );
+    }
+
+    #[test]
+    fn test_to_html_round_trip() {
+        for fixture in [TEST1, TEST2] {
+            let root = fixture.parse::<Element>().unwrap();
+            let reparsed = root.to_html().parse::<Element>().unwrap();
+            let tree = |root: &Element| {
+                let mut elems = vec![(root.name.clone(), root.attrs.clone(), root.text_content())];
+                elems.extend(
+                    root.iter_decendents()
+                        .map(|e| (e.name.clone(), e.attrs.clone(), e.text_content())),
+                );
+                elems
+            };
+            assert_eq!(tree(&root), tree(&reparsed));
+        }
+    }
+
+    #[test]
+    fn test_lazy_contents_reentrant() {
+        let root = TEST2.parse::<Element>().unwrap();
+        let first = root.iter_contents().count();
+        // Holding one iterator while starting others must not disturb either
+        for child in root.iter_children() {
+            assert_eq!(root.iter_contents().count(), first);
+            let grandchildren = child.iter_children().collect_vec();
+            assert_eq!(child.iter_children().count(), grandchildren.len());
+        }
+        // Elements can be shared with other threads and parsed from them
+        std::thread::scope(|scope| {
+            let handles =
+                (0..4).map(|_| scope.spawn(|| root.iter_decendents().count())).collect_vec();
+            let counts = handles.into_iter().map(|h| h.join().unwrap()).collect_vec();
+            assert!(counts.iter().all_equal());
+        });
+    }
+
+    #[test]
+    fn test_attr_name_case() {
+        let root = "<DIV CLASS=\"row\" Data-Tooltip Title=\"t\"></DIV>".parse::<Element>().unwrap();
+        assert_eq!(root.get_attr("class"), Some("row"));
+        assert_eq!(root.get_attr("data-tooltip"), Some(""));
+        assert_eq!(root.get_attr("Title"), None);
+        assert_eq!(root.get_attr_ci("Title"), Some("t"));
+        assert_eq!(root.get_attr_ci("CLASS"), Some("row"));
+    }
+
+    #[test]
+    fn test_skip_doctype_and_processing_instructions() {
+        let html = "<?xml version=\"1.0\"?>\n<!DOCTYPE html>\n<!-- c --><div id=\"a\"><!doctype x><p>hi</p></div>";
+        let root = html.parse::<Element>().unwrap();
+        assert_eq!(root.get_attr("id"), Some("a"));
+        assert_eq!(root.iter_children().map(|e| e.name.as_str()).collect_vec(), ["p"]);
+        let elements = parse_fragment("<!DOCTYPE html><div></div><div></div>").unwrap();
+        assert_eq!(elements.len(), 2);
+    }
+
+    #[test]
+    fn test_select() {
+        let root = TEST2.parse::<Element>().unwrap();
+        let has_tip = root.select(Some("span"), Some(("class", "has-tip"))).count();
+        assert_eq!(has_tip, 37 * 3);
+        let rows = root
+            .select(Some("div"), None)
+            .filter(|e| e.get_attr("id").is_some_and(|id| id.starts_with("booking-")));
+        assert_eq!(rows.count(), 37);
+        assert_eq!(root.select(None, Some(("id", "booking-191730"))).count(), 1);
+        assert_eq!(root.select(Some("span"), Some(("id", "booking-191730"))).count(), 0);
+    }
+
+    #[test]
+    fn test_cdata() {
+        let root = "<div>before <![CDATA[if (a < b && c) { x = '&amp;'; }]]><b>after</b></div>"
+            .parse::<Element>()
+            .unwrap();
+        let texts = root.iter_contents().filter_map(|c| c.as_ref_text()).collect_vec();
+        assert_eq!(texts, ["before", "if (a < b && c) { x = '&amp;'; }"]);
+        assert_eq!(root.iter_children().map(|e| e.name.as_str()).collect_vec(), ["b"]);
+    }
+
+    #[test]
+    fn test_iter_decendents_depth() {
+        let root = "<a><b><c><d></d></c></b><b2></b2></a>".parse::<Element>().unwrap();
+        let names = |iter: Box<dyn Iterator<Item = &Element> + '_>| {
+            iter.map(|e| e.name.clone()).collect_vec()
+        };
+        assert_eq!(names(root.iter_decendents()), ["b", "c", "d", "b2"]);
+        assert_eq!(names(Box::new(root.iter_decendents_depth(1))), ["b", "b2"]);
+        assert_eq!(names(Box::new(root.iter_decendents_depth(2))), ["b", "c", "b2"]);
+        assert!(names(Box::new(root.iter_decendents_depth(0))).is_empty());
+    }
+
+    #[test]
+    fn test_iter_decendents_deep() {
+        // Built directly, since the recursive parser itself can't take a tree this deep
+        let depth = 1000;
+        let mut root = Element::with_contents(
+            "span".to_string(),
+            BTreeMap::new(),
+            vec![Content::Text("bottom".to_string())],
+        );
+        for _ in 0..depth {
+            root = Element::with_contents(
+                "div".to_string(),
+                BTreeMap::new(),
+                vec![Content::Element(root)],
+            );
+        }
+        assert_eq!(root.iter_decendents().count(), depth);
+        assert_eq!(root.iter_decendents().last().unwrap().name, "span");
+        assert_eq!(root.iter_decendents_depth(10).count(), 10);
+    }
+
+    #[test]
+    fn test_parse_preserving_ws() {
+        let html = "<p><b> x </b><span> 6:00</span></p>";
+        let texts = |root: &Element| {
+            root.iter_decendents()
+                .flat_map(|e| e.iter_contents().filter_map(|c| c.as_ref_text()))
+                .map(String::from)
+                .collect_vec()
+        };
+        assert_eq!(texts(&parse_preserving_ws(html).unwrap()), [" x ", " 6:00"]);
+        assert_eq!(texts(&html.parse::<Element>().unwrap()), ["x", "6:00"]);
+
+        let root = parse_preserving_ws("<p>a <b>x</b> c</p>").unwrap();
+        let own_texts = root.iter_contents().filter_map(|c| c.as_ref_text()).collect_vec();
+        assert_eq!(own_texts, ["a ", " c"]);
+        assert_eq!(texts(&root), ["x"]);
+    }
+
+    #[test]
+    fn test_parse_lenient_siblings() {
+        let elements = parse_lenient("<p>a<p>b").unwrap();
+        let summary = elements.iter().map(|e| (e.name.as_str(), e.text_content())).collect_vec();
+        assert_eq!(summary, [("p", "a".to_string()), ("p", "b".to_string())]);
+        assert!(elements.iter().all(|e| e.iter_children().count() == 0));
+    }
+
+    #[test]
+    fn test_parse_lenient_closes_at_parent() {
+        let elements = parse_lenient("<div><li>one<li>two</div><span>after</span>").unwrap();
+        assert_eq!(elements.iter().map(|e| e.name.as_str()).collect_vec(), ["div", "span"]);
+        let items = elements[0].iter_children().map(|e| e.text_content()).collect_vec();
+        assert_eq!(items, ["one", "two"]);
+        // Well-formed nesting of the same name is left alone
+        let nested = parse_lenient("<div><div>inner</div></div>").unwrap();
+        assert_eq!(nested[0].iter_decendents().count(), 1);
+    }
+
+    #[test]
+    fn test_strict_parse_unchanged() {
+        // Strictly, unclosed elements stay empty and what follows them belongs to the parent
+        let root = "<div><li>one<li>two</div>".parse::<Element>().unwrap();
+        assert_eq!(root.iter_children().count(), 2);
+        assert!(root.iter_children().all(|li| li.iter_contents().count() == 0));
+        assert_eq!(root.text_content(), "one two");
+    }
+
+    #[test]
+    fn test_open_tag() {
+        let root =
+            "<input value=\"abc\" name=\"nonce\" type=\"hidden\"/>".parse::<Element>().unwrap();
+        assert_eq!(root.open_tag(), "<input name=\"nonce\" type=\"hidden\" value=\"abc\">");
+    }
+
     #[test]
     fn test_iter_decendents() {
-        let (_, mut root) = xml_element::<VerboseError<&str>>(TEST1).unwrap();
+        let (_, root) = xml_element::<VerboseError<&str>>(TEST1).unwrap();
         let children = root.iter_decendents().map(|elem| (&elem.name, &elem.attrs)).collect_vec();
         for child in children {
             println!("{child:?}");
@@ -277,6 +862,7 @@ mod tests {
     }
 
     const TEST1: &str = "<div class=\"row\">\n\t<div class=\"columns\">\n\t\t<div class=\"row\"><div class=\"columns\">\n\t \n\t <form data-abide id=\"frm-update-date-range\" name=\"frm-update-date-range\" class=\"custom\" target=\"#ajax-booking-view-tool-1669869926\" action=\"ajax.get-bookings.php\">\n\t \t\n\t \t<div class=\"row \">\n            <div class=\"columns\">\n                <label>Select Tool(s)</label>\n                <input type=\"hidden\" name=\"tool_id[]\" multiple=\"multiple\" class=\"select2-ajax\" source=\"ajax.get-tools.php\" hide_inactive=\"1\" data-placeholder=\"Select Tools.. (leave blank for all tools)\" value=\"\"/>\n                <small class=\"error\">Pick some tools</small> \n            </div>\n        </div>\n        \n        <div class=\"row collapse\">\n            <div class=\"columns small-6\">\n                <label for=\"select-tool\">Start</label>\n                <input type=\"date\" name=\"start_date\" value=\"2022-11-23\" required>\n                <small class=\"error\">Select Date</small>\n            </div>\n            \n            <div class=\"columns small-6\">\n\t\t\t\t<label for=\"select-tool\" class=\"right\">End</label>\n                <input type=\"date\" name=\"end_date\" value=\"2022-11-30\" required>\n                <small class=\"error\">Select Date</small>\n            </div>\n        </div>\n\n       \n        <div class=\"row\">\n            <div class=\"columns\">\n            \t<button type=\"submit\" class=\"small right secondary radius\" id=\"btn-view-schedule\" >Check</button>\n            \t<span class=\"has-tooltip\" title=\"subscribe to this schedule\" ></span>\n                <input type=\"hidden\" name=\"nonce\" value=\"9xNGZda%lDKYFbV7zxFS\">\n                <input type=\"hidden\" name=\"nonce_key\" value=\"booking-view-tool-1669869926\">\n                \n            </div>\n        </div> \n\t</form>\n<div id=\"ajax-booking-view-tool-1669869926\" name=\"results\" data-alert></div>\n</div></div>\t</div>\n</div>";
+    #[allow(dead_code)]
     const RESULT1: &str = "Element { name: \"div\", attrs: {\"class\": \"row\"} }\n    Element { name: \"div\", attrs: {\"class\": \"columns\"} }\n        Element { name: \"div\", attrs: {\"class\": \"row\"} }\n            Element { name: \"div\", attrs: {\"class\": \"columns\"} }\n                Element { name: \"form\", attrs: {\"action\": \"ajax.get-bookings.php\", \"class\": \"custom\", \"data-abide\": \"\", \"id\": \"frm-update-date-range\", \"name\": \"frm-update-date-range\", \"target\": \"#ajax-booking-view-tool-1669869926\"} }\n                    Element { name: \"div\", attrs: {\"class\": \"row \"} }\n                        Element { name: \"div\", attrs: {\"class\": \"columns\"} }\n                            Element { name: \"label\", attrs: {} }\n                                Select Tool(s)\n                            Element { name: \"input\", attrs: {\"class\": \"select2-ajax\", \"data-placeholder\": \"Select Tools.. (leave blank for all tools)\", \"hide_inactive\": \"1\", \"multiple\": \"multiple\", \"name\": \"tool_id[]\", \"source\": \"ajax.get-tools.php\", \"type\": \"hidden\", \"value\": \"\"} }\n                            Element { name: \"small\", attrs: {\"class\": \"error\"} }\n                                Pick some tools\n                    Element { name: \"div\", attrs: {\"class\": \"row collapse\"} }\n                        Element { name: \"div\", attrs: {\"class\": \"columns small-6\"} }\n                            Element { name: \"label\", attrs: {\"for\": \"select-tool\"} }\n                                Start\n                            Element { name: \"input\", attrs: {\"name\": \"start_date\", \"required\": \"\", \"type\": \"date\", \"value\": \"2022-11-23\"} }\n                            Element { name: \"small\", attrs: {\"class\": \"error\"} }\n                                Select Date\n                        Element { name: \"div\", attrs: {\"class\": \"columns small-6\"} }\n                            Element { name: \"label\", attrs: {\"class\": \"right\", \"for\": \"select-tool\"} }\n                                End\n                            Element { name: \"input\", attrs: {\"name\": \"end_date\", \"required\": \"\", \"type\": \"date\", \"value\": \"2022-11-30\"} }\n                            Element { name: \"small\", attrs: {\"class\": \"error\"} }\n                                Select Date\n                    Element { name: \"div\", attrs: {\"class\": \"row\"} }\n                        Element { name: \"div\", attrs: {\"class\": \"columns\"} }\n                            Element { name: \"button\", attrs: {\"class\": \"small right secondary radius\", \"id\": \"btn-view-schedule\", \"type\": \"submit\"} }\n                                Check\n                            Element { name: \"span\", attrs: {\"class\": \"has-tooltip\", \"title\": \"subscribe to this schedule\"} }\n                            Element { name: \"input\", attrs: {\"name\": \"nonce\", \"type\": \"hidden\", \"value\": \"9xNGZda%lDKYFbV7zxFS\"} }\n                            Element { name: \"input\", attrs: {\"name\": \"nonce_key\", \"type\": \"hidden\", \"value\": \"booking-view-tool-1669869926\"} }\n                Element { name: \"div\", attrs: {\"data-alert\": \"\", \"id\": \"ajax-booking-view-tool-1669869926\", \"name\": \"results\"} }";
-    const TEST2: &str = "<div class=\"section-container accordion\" data-section=\"accordion\">	<section class=\"active\">		<p class=\"title\" data-section-title><a href=\"\">Thu, Nov 24</a> </p> <div class=\"content\"					data-section-content>					<h4><small><a href=\"equipment-detail.php?tool_id=427\">Heidelberg MLA150</a></small></h4><div class=\"row\"><div class=\"columns\"><div class=\"table\">\r\n\t\t\t\t\t\t\t\t<div class=\"table-head\">\r\n\t\t\t\t\t\t\t\t\t<div class=\"row table-row\">\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">start</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">stop</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 left\">user</div>\r\n\t\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t<div class=\"table-body\" ><div id=\"booking-191730\" class=\"table-row group-8ae908785e3a1bb237ea2641a043a4b0\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"6:00am Thu Nov 24th\" data-tooltip> 6:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"9:00am Thu Nov 24th\" data-tooltip> 9:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Wyatt James <br/> wyatt@norcada.com\" data-tooltip >wjames</span></div></div></div><div id=\"booking-190677\" class=\"table-row group-76f1e7763eaa4d882813a63cf5d51f8d\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"9:00am Thu Nov 24th\" data-tooltip> 9:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"11:10am Thu Nov 24th\" data-tooltip> 11:10 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Min Wu <br/> wu2@ualberta.ca\" data-tooltip >min-wu</span></div></div></div><div id=\"booking-191536\" class=\"table-row group-293df35ed63cb7ccbe156a3d79908973\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"11:30am Thu Nov 24th\" data-tooltip> 11:30</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"1:00pm Thu Nov 24th\" data-tooltip> 13:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Daksh Malhotra <br/> dmalhot2@ualberta.ca\" data-tooltip >dmalhot2</span></div></div></div><div id=\"booking-191132\" class=\"table-row group-41cee0e4055e0ac8184ae497014c49be\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"2:00pm Thu Nov 24th\" data-tooltip> 14:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"4:00pm Thu Nov 24th\" data-tooltip> 16:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Kevin Setzer <br/> kevin@appliednt.com\" data-tooltip >ksetzer</span></div></div></div><div id=\"booking-191677\" class=\"table-row group-5b68a4ec1e52b15f75b7bcd6f4224872\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"4:00pm Thu Nov 24th\" data-tooltip> 16:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"5:00pm Thu Nov 24th\" data-tooltip> 17:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Daniel Mildenberger <br/> dmildenb@ualberta.ca\" data-tooltip >dmildenb</span></div></div></div><div id=\"booking-191678\" class=\"table-row group-4b8a09b6d673022ecf5de68c575fe717\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"5:00pm Thu Nov 24th\" data-tooltip> 17:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"6:00pm Thu Nov 24th\" data-tooltip> 18:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Daniel Mildenberger <br/> dmildenb@ualberta.ca\" data-tooltip >dmildenb</span></div></div></div></div></div><div id=\"ajax-rb-427\" name=\"results\" data-fade-out ></div></section><section ><p class=\"title\" data-section-title><a href=\"\">Fri, Nov 25</a></p><div class=\"content\" data-section-content><h4><small><a href=\"equipment-detail.php?tool_id=427\">Heidelberg MLA150</a></small></h4><div class=\"row\"><div class=\"columns\"><div class=\"table\">\r\n\t\t\t\t\t\t\t\t<div class=\"table-head\">\r\n\t\t\t\t\t\t\t\t\t<div class=\"row table-row\">\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">start</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">stop</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 left\">user</div>\r\n\t\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t<div class=\"table-body\" ><div id=\"booking-191701\" class=\"table-row group-761fa754ca075f0663e471d6b98141f4\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"6:00am Fri Nov 25th\" data-tooltip> 6:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"7:00am Fri Nov 25th\" data-tooltip> 7:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Wyatt James <br/> wyatt@norcada.com\" data-tooltip >wjames</span></div></div></div><div id=\"booking-191542\" class=\"table-row group-3da11a1ebb5bccceb4f9a6fa3ee5a9f2\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"7:00am Fri Nov 25th\" data-tooltip> 7:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"9:00am Fri Nov 25th\" data-tooltip> 9:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Pedro Duarte Riveros <br/> duarteri@ualberta.ca\" data-tooltip >Duarteri</span></div></div></div><div id=\"booking-190689\" class=\"table-row group-ac7627ab3efd68204626b1133a7b9f06\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"9:00am Fri Nov 25th\" data-tooltip> 9:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"11:00am Fri Nov 25th\" data-tooltip> 11:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Eric Milburn <br/> eric@zinite.com\" data-tooltip >emilburn</span></div></div></div><div id=\"booking-191571\" class=\"table-row group-1bd94fc24bc77a9c28737553cbbdde5c\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"11:00am Fri Nov 25th\" data-tooltip> 11:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"1:30pm Fri Nov 25th\" data-tooltip> 13:30 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Abbie Gottert <br/> abbie@norcada.com\" data-tooltip >agottert</span></div></div></div><div id=\"booking-191134\" class=\"table-row group-378f68e0c942509b067cf55bad7a404e\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"1:30pm Fri Nov 25th\" data-tooltip> 13:30</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"3:30pm Fri Nov 25th\" data-tooltip> 15:30 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Kevin Setzer <br/> kevin@appliednt.com\" data-tooltip >ksetzer</span></div></div></div><div id=\"booking-191602\" class=\"table-row group-5ed742c71f7260f261bd931d56e18f51\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"4:00pm Fri Nov 25th\" data-tooltip> 16:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"10:00pm Fri Nov 25th\" data-tooltip> 22:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Ahmed Elsherbiny <br/> ahmed.elsherbiny@shebamicrosystems.com\" data-tooltip >elsherbi</span></div></div></div></div></div><div id=\"ajax-rb-427\" name=\"results\" data-fade-out ></div></section><section ><p class=\"title\" data-section-title><a href=\"\">Sat, Nov 26</a></p><div class=\"content\" data-section-content><h4><small><a href=\"equipment-detail.php?tool_id=427\">Heidelberg MLA150</a></small></h4><div class=\"row\"><div class=\"columns\"><div class=\"table\">\r\n\t\t\t\t\t\t\t\t<div class=\"table-head\">\r\n\t\t\t\t\t\t\t\t\t<div class=\"row table-row\">\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">start</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">stop</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 left\">user</div>\r\n\t\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t<div class=\"table-body\" ><div id=\"booking-191702\" class=\"table-row group-402d78fd8e4fcee5e4d53a5ba6a39a92\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"6:00am Sat Nov 26th\" data-tooltip> 6:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"8:30am Sat Nov 26th\" data-tooltip> 8:30 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Wyatt James <br/> wyatt@norcada.com\" data-tooltip >wjames</span></div></div></div><div id=\"booking-191653\" class=\"table-row group-b357212ef36db734ec877d14d45575f7\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"8:30am Sat Nov 26th\" data-tooltip> 8:30</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"10:00am Sat Nov 26th\" data-tooltip> 10:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Alexandria McKinlay <br/> amckinlay@appliednt.com\" data-tooltip >amckinlay</span></div></div></div><div id=\"booking-191703\" class=\"table-row group-89de0efb3491a3f70030d030e05442d4\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"10:00am Sat Nov 26th\" data-tooltip> 10:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"12:00pm Sat Nov 26th\" data-tooltip> 12:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Wyatt James <br/> wyatt@norcada.com\" data-tooltip >wjames</span></div></div></div><div id=\"booking-191426\" class=\"table-row group-0d62f43375be21685f2c82f24972cd6c\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"2:00pm Sat Nov 26th\" data-tooltip> 14:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"4:00pm Sat Nov 26th\" data-tooltip> 16:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Eric Milburn <br/> eric@zinite.com\" data-tooltip >emilburn</span></div></div></div></div></div><div id=\"ajax-rb-427\" name=\"results\" data-fade-out ></div></section><section ><p class=\"title\" data-section-title><a href=\"\">Sun, Nov 27</a></p><div class=\"content\" data-section-content><h4><small><a href=\"equipment-detail.php?tool_id=427\">Heidelberg MLA150</a></small></h4><div class=\"row\"><div class=\"columns\"><div class=\"table\">\r\n\t\t\t\t\t\t\t\t<div class=\"table-head\">\r\n\t\t\t\t\t\t\t\t\t<div class=\"row table-row\">\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">start</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">stop</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 left\">user</div>\r\n\t\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t<div class=\"table-body\" ><div id=\"booking-191603\" class=\"table-row group-b125d24de84d1f18fe5abbf674121684\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"9:00am Sun Nov 27th\" data-tooltip> 9:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"1:00pm Sun Nov 27th\" data-tooltip> 13:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Ahmed Elsherbiny <br/> ahmed.elsherbiny@shebamicrosystems.com\" data-tooltip >elsherbi</span></div></div></div><div id=\"booking-191938\" class=\"table-row group-97e22a4d44845d631319369782426a13\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"4:30pm Sun Nov 27th\" data-tooltip> 16:30</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"6:30pm Sun Nov 27th\" data-tooltip> 18:30 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Eric Milburn <br/> eric@zinite.com\" data-tooltip >emilburn</span></div></div></div><div id=\"booking-191939\" class=\"table-row group-77f99f37c34a6997a85ebae7f31d46c8\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"6:30pm Sun Nov 27th\" data-tooltip> 18:30</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"8:30pm Sun Nov 27th\" data-tooltip> 20:30 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Eric Milburn <br/> eric@zinite.com\" data-tooltip >emilburn</span></div></div></div></div></div><div id=\"ajax-rb-427\" name=\"results\" data-fade-out ></div></section><section ><p class=\"title\" data-section-title><a href=\"\">Mon, Nov 28</a></p><div class=\"content\" data-section-content><h4><small><a href=\"equipment-detail.php?tool_id=427\">Heidelberg MLA150</a></small></h4><div class=\"row\"><div class=\"columns\"><div class=\"table\">\r\n\t\t\t\t\t\t\t\t<div class=\"table-head\">\r\n\t\t\t\t\t\t\t\t\t<div class=\"row table-row\">\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">start</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">stop</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 left\">user</div>\r\n\t\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t<div class=\"table-body\" ><div id=\"booking-191576\" class=\"table-row group-fe2b75dcb307e5f687ba5fc11c71d8e8\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"7:00am Mon Nov 28th\" data-tooltip> 7:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"9:00am Mon Nov 28th\" data-tooltip> 9:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Pedro Duarte Riveros <br/> duarteri@ualberta.ca\" data-tooltip >Duarteri</span></div></div></div><div id=\"booking-190707\" class=\"table-row group-e0b463a324cea71f7278407e04ab166d\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"12:00pm Mon Nov 28th\" data-tooltip> 12:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"2:00pm Mon Nov 28th\" data-tooltip> 14:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Min Wu <br/> wu2@ualberta.ca\" data-tooltip >min-wu</span></div></div></div><div id=\"booking-191809\" class=\"table-row group-5974cffcc961333e9d68e257282b0d98\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"2:00pm Mon Nov 28th\" data-tooltip> 14:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"4:00pm Mon Nov 28th\" data-tooltip> 16:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Daniel Mildenberger <br/> dmildenb@ualberta.ca\" data-tooltip >dmildenb</span></div></div></div></div></div><div id=\"ajax-rb-427\" name=\"results\" data-fade-out ></div></section><section ><p class=\"title\" data-section-title><a href=\"\">Tue, Nov 29</a></p><div class=\"content\" data-section-content><h4><small><a href=\"equipment-detail.php?tool_id=427\">Heidelberg MLA150</a></small></h4><div class=\"row\"><div class=\"columns\"><div class=\"table\">\r\n\t\t\t\t\t\t\t\t<div class=\"table-head\">\r\n\t\t\t\t\t\t\t\t\t<div class=\"row table-row\">\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">start</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">stop</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 left\">user</div>\r\n\t\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t<div class=\"table-body\" ><div id=\"booking-191577\" class=\"table-row group-979074d9d3f8c82821da62b5eab85c5d\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"7:00am Tue Nov 29th\" data-tooltip> 7:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"9:00am Tue Nov 29th\" data-tooltip> 9:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Pedro Duarte Riveros <br/> duarteri@ualberta.ca\" data-tooltip >Duarteri</span></div></div></div><div id=\"booking-191257\" class=\"table-row group-f04015d3be04dba84953a3cafad6d5c6\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"9:00am Tue Nov 29th\" data-tooltip> 9:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"11:00am Tue Nov 29th\" data-tooltip> 11:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Eric Milburn <br/> eric@zinite.com\" data-tooltip >emilburn</span></div></div></div><div id=\"booking-191336\" class=\"table-row group-8a1c073486cb52d05cf9c73dcaccf090\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"11:00am Tue Nov 29th\" data-tooltip> 11:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"1:00pm Tue Nov 29th\" data-tooltip> 13:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Cory Rewcastle <br/> cory@transeon.ca\" data-tooltip >crewcastle</span></div></div></div></div></div><div id=\"ajax-rb-427\" name=\"results\" data-fade-out ></div></section><section ><p class=\"title\" data-section-title><a href=\"\">Wed, Nov 30</a></p><div class=\"content\" data-section-content><h4><small><a href=\"equipment-detail.php?tool_id=427\">Heidelberg MLA150</a></small></h4><div class=\"row\"><div class=\"columns\"><div class=\"table\">\r\n\t\t\t\t\t\t\t\t<div class=\"table-head\">\r\n\t\t\t\t\t\t\t\t\t<div class=\"row table-row\">\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">start</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">stop</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 left\">user</div>\r\n\t\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t<div class=\"table-body\" ><div id=\"booking-191740\" class=\"table-row group-eae5dd63e9c0f87a89b3619277c86cf8\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"10:00am Wed Nov 30th\" data-tooltip> 10:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"12:00pm Wed Nov 30th\" data-tooltip> 12:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Cory Rewcastle <br/> cory@transeon.ca\" data-tooltip >crewcastle</span></div></div></div><div id=\"booking-190681\" class=\"table-row group-7f2cc60a903d4dbcd83bdc66cd4ce082\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"1:30pm Wed Nov 30th\" data-tooltip> 13:30</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"3:30pm Wed Nov 30th\" data-tooltip> 15:30 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Gustavo de Oliveira Luiz <br/> deolivei@ualberta.ca\" data-tooltip >gde-oliveira-luiz</span></div></div></div><div id=\"booking-191270\" class=\"table-row group-5542d9b02f97f3bd12e9e1762f90e86b\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"4:00pm Wed Nov 30th\" data-tooltip> 16:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"6:00pm Wed Nov 30th\" data-tooltip> 18:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Min Wu <br/> wu2@ualberta.ca\" data-tooltip >min-wu</span></div></div></div></div></div><div id=\"ajax-rb-427\" name=\"results\" data-fade-out ></div></section><section ><p class=\"title\" data-section-title><a href=\"\">Thu, Dec 1</a></p><div class=\"content\" data-section-content><h4><small><a href=\"equipment-detail.php?tool_id=427\">Heidelberg MLA150</a></small></h4><div class=\"row\"><div class=\"columns\"><div class=\"table\">\r\n\t\t\t\t\t\t\t\t<div class=\"table-head\">\r\n\t\t\t\t\t\t\t\t\t<div class=\"row table-row\">\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">start</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">stop</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 left\">user</div>\r\n\t\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t<div class=\"table-body\" ><div id=\"booking-190698\" class=\"table-row group-b51f070ebe4f5b78c63ceb66de19324f\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"9:00am Thu Dec 1st\" data-tooltip> 9:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"11:00am Thu Dec 1st\" data-tooltip> 11:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Gustavo de Oliveira Luiz <br/> deolivei@ualberta.ca\" data-tooltip >gde-oliveira-luiz</span></div></div></div><div id=\"booking-191346\" class=\"table-row group-213fa1f6f5c4d9309e1700676dcbe4cf\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"11:00am Thu Dec 1st\" data-tooltip> 11:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"1:00pm Thu Dec 1st\" data-tooltip> 13:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Cory Rewcastle <br/> cory@transeon.ca\" data-tooltip >crewcastle</span></div></div></div></div></div><div id=\"ajax-rb-427\" name=\"results\" data-fade-out ></div></section><section ><p class=\"title\" data-section-title><a href=\"\">Mon, Dec 5</a></p><div class=\"content\" data-section-content><h4><small><a href=\"equipment-detail.php?tool_id=427\">Heidelberg MLA150</a></small></h4><div class=\"row\"><div class=\"columns\"><div class=\"table\">\r\n\t\t\t\t\t\t\t\t<div class=\"table-head\">\r\n\t\t\t\t\t\t\t\t\t<div class=\"row table-row\">\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">start</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">stop</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 left\">user</div>\r\n\t\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t<div class=\"table-body\" ><div id=\"booking-191275\" class=\"table-row group-f52c97502eb34eebc15bc231ba4f2e33\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"2:00pm Mon Dec 5th\" data-tooltip> 14:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"4:00pm Mon Dec 5th\" data-tooltip> 16:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Eric Milburn <br/> eric@zinite.com\" data-tooltip >emilburn</span></div></div></div></div></div><div id=\"ajax-rb-427\" name=\"results\" data-fade-out ></div></section><section ><p class=\"title\" data-section-title><a href=\"\">Wed, Dec 7</a></p><div class=\"content\" data-section-content><h4><small><a href=\"equipment-detail.php?tool_id=427\">Heidelberg MLA150</a></small></h4><div class=\"row\"><div class=\"columns\"><div class=\"table\">\r\n\t\t\t\t\t\t\t\t<div class=\"table-head\">\r\n\t\t\t\t\t\t\t\t\t<div class=\"row table-row\">\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">start</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">stop</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 left\">user</div>\r\n\t\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t<div class=\"table-body\" ><div id=\"booking-191281\" class=\"table-row group-ecefe6746df8464aaf0af04bb919b72a\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"9:00am Wed Dec 7th\" data-tooltip> 9:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"11:00am Wed Dec 7th\" data-tooltip> 11:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Eric Milburn <br/> eric@zinite.com\" data-tooltip >emilburn</span></div></div></div><div id=\"booking-190685\" class=\"table-row group-85c33a95a664b0c7478022fd2ad4d837\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"1:30pm Wed Dec 7th\" data-tooltip> 13:30</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"3:30pm Wed Dec 7th\" data-tooltip> 15:30 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Gustavo de Oliveira Luiz <br/> deolivei@ualberta.ca\" data-tooltip >gde-oliveira-luiz</span></div></div></div></div></div><div id=\"ajax-rb-427\" name=\"results\" data-fade-out ></div></section><section ><p class=\"title\" data-section-title><a href=\"\">Thu, Dec 8</a></p><div class=\"content\" data-section-content><h4><small><a href=\"equipment-detail.php?tool_id=427\">Heidelberg MLA150</a></small></h4><div class=\"row\"><div class=\"columns\"><div class=\"table\">\r\n\t\t\t\t\t\t\t\t<div class=\"table-head\">\r\n\t\t\t\t\t\t\t\t\t<div class=\"row table-row\">\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">start</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">stop</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 left\">user</div>\r\n\t\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t<div class=\"table-body\" ><div id=\"booking-190699\" class=\"table-row group-e0b5728e888dc7948946dcd3fcca1caf\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"9:00am Thu Dec 8th\" data-tooltip> 9:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"11:00am Thu Dec 8th\" data-tooltip> 11:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Gustavo de Oliveira Luiz <br/> deolivei@ualberta.ca\" data-tooltip >gde-oliveira-luiz</span></div></div></div><div id=\"booking-191286\" class=\"table-row group-89fbf79710e8645968f969278e718db2\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"12:00pm Thu Dec 8th\" data-tooltip> 12:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"2:00pm Thu Dec 8th\" data-tooltip> 14:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Eric Milburn <br/> eric@zinite.com\" data-tooltip >emilburn</span></div></div></div></div></div><div id=\"ajax-rb-427\" name=\"results\" data-fade-out ></div></section><section ><p class=\"title\" data-section-title><a href=\"\">Wed, Dec 14</a></p><div class=\"content\" data-section-content><h4><small><a href=\"equipment-detail.php?tool_id=427\">Heidelberg MLA150</a></small></h4><div class=\"row\"><div class=\"columns\"><div class=\"table\">\r\n\t\t\t\t\t\t\t\t<div class=\"table-head\">\r\n\t\t\t\t\t\t\t\t\t<div class=\"row table-row\">\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">start</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">stop</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 left\">user</div>\r\n\t\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t<div class=\"table-body\" ><div id=\"booking-190686\" class=\"table-row group-c0fa0d01811c2d074a34a1cb7ebfc137\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"1:30pm Wed Dec 14th\" data-tooltip> 13:30</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"3:30pm Wed Dec 14th\" data-tooltip> 15:30 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Gustavo de Oliveira Luiz <br/> deolivei@ualberta.ca\" data-tooltip >gde-oliveira-luiz</span></div></div></div></div></div><div id=\"ajax-rb-427\" name=\"results\" data-fade-out ></div></section><section ><p class=\"title\" data-section-title><a href=\"\">Thu, Dec 15</a></p><div class=\"content\" data-section-content><h4><small><a href=\"equipment-detail.php?tool_id=427\">Heidelberg MLA150</a></small></h4><div class=\"row\"><div class=\"columns\"><div class=\"table\">\r\n\t\t\t\t\t\t\t\t<div class=\"table-head\">\r\n\t\t\t\t\t\t\t\t\t<div class=\"row table-row\">\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">start</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">stop</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 left\">user</div>\r\n\t\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t<div class=\"table-body\" ><div id=\"booking-190700\" class=\"table-row group-032074d744988726d6e4e90acf98f994\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"9:00am Thu Dec 15th\" data-tooltip> 9:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"11:00am Thu Dec 15th\" data-tooltip> 11:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Gustavo de Oliveira Luiz <br/> deolivei@ualberta.ca\" data-tooltip >gde-oliveira-luiz</span></div></div></div></div></div><div id=\"ajax-rb-427\" name=\"results\" data-fade-out ></div></section></div>";
+    pub(crate) const TEST2: &str = "<div class=\"section-container accordion\" data-section=\"accordion\">	<section class=\"active\">		<p class=\"title\" data-section-title><a href=\"\">Thu, Nov 24</a> </p> <div class=\"content\"					data-section-content>					<h4><small><a href=\"equipment-detail.php?tool_id=427\">Heidelberg MLA150</a></small></h4><div class=\"row\"><div class=\"columns\"><div class=\"table\">\r\n\t\t\t\t\t\t\t\t<div class=\"table-head\">\r\n\t\t\t\t\t\t\t\t\t<div class=\"row table-row\">\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">start</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">stop</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 left\">user</div>\r\n\t\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t<div class=\"table-body\" ><div id=\"booking-191730\" class=\"table-row group-8ae908785e3a1bb237ea2641a043a4b0\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"6:00am Thu Nov 24th\" data-tooltip> 6:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"9:00am Thu Nov 24th\" data-tooltip> 9:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Wyatt James <br/> wyatt@norcada.com\" data-tooltip >wjames</span></div></div></div><div id=\"booking-190677\" class=\"table-row group-76f1e7763eaa4d882813a63cf5d51f8d\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"9:00am Thu Nov 24th\" data-tooltip> 9:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"11:10am Thu Nov 24th\" data-tooltip> 11:10 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Min Wu <br/> wu2@ualberta.ca\" data-tooltip >min-wu</span></div></div></div><div id=\"booking-191536\" class=\"table-row group-293df35ed63cb7ccbe156a3d79908973\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"11:30am Thu Nov 24th\" data-tooltip> 11:30</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"1:00pm Thu Nov 24th\" data-tooltip> 13:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Daksh Malhotra <br/> dmalhot2@ualberta.ca\" data-tooltip >dmalhot2</span></div></div></div><div id=\"booking-191132\" class=\"table-row group-41cee0e4055e0ac8184ae497014c49be\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"2:00pm Thu Nov 24th\" data-tooltip> 14:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"4:00pm Thu Nov 24th\" data-tooltip> 16:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Kevin Setzer <br/> kevin@appliednt.com\" data-tooltip >ksetzer</span></div></div></div><div id=\"booking-191677\" class=\"table-row group-5b68a4ec1e52b15f75b7bcd6f4224872\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"4:00pm Thu Nov 24th\" data-tooltip> 16:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"5:00pm Thu Nov 24th\" data-tooltip> 17:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Daniel Mildenberger <br/> dmildenb@ualberta.ca\" data-tooltip >dmildenb</span></div></div></div><div id=\"booking-191678\" class=\"table-row group-4b8a09b6d673022ecf5de68c575fe717\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"5:00pm Thu Nov 24th\" data-tooltip> 17:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"6:00pm Thu Nov 24th\" data-tooltip> 18:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Daniel Mildenberger <br/> dmildenb@ualberta.ca\" data-tooltip >dmildenb</span></div></div></div></div></div><div id=\"ajax-rb-427\" name=\"results\" data-fade-out ></div></section><section ><p class=\"title\" data-section-title><a href=\"\">Fri, Nov 25</a></p><div class=\"content\" data-section-content><h4><small><a href=\"equipment-detail.php?tool_id=427\">Heidelberg MLA150</a></small></h4><div class=\"row\"><div class=\"columns\"><div class=\"table\">\r\n\t\t\t\t\t\t\t\t<div class=\"table-head\">\r\n\t\t\t\t\t\t\t\t\t<div class=\"row table-row\">\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">start</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">stop</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 left\">user</div>\r\n\t\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t<div class=\"table-body\" ><div id=\"booking-191701\" class=\"table-row group-761fa754ca075f0663e471d6b98141f4\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"6:00am Fri Nov 25th\" data-tooltip> 6:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"7:00am Fri Nov 25th\" data-tooltip> 7:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Wyatt James <br/> wyatt@norcada.com\" data-tooltip >wjames</span></div></div></div><div id=\"booking-191542\" class=\"table-row group-3da11a1ebb5bccceb4f9a6fa3ee5a9f2\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"7:00am Fri Nov 25th\" data-tooltip> 7:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"9:00am Fri Nov 25th\" data-tooltip> 9:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Pedro Duarte Riveros <br/> duarteri@ualberta.ca\" data-tooltip >Duarteri</span></div></div></div><div id=\"booking-190689\" class=\"table-row group-ac7627ab3efd68204626b1133a7b9f06\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"9:00am Fri Nov 25th\" data-tooltip> 9:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"11:00am Fri Nov 25th\" data-tooltip> 11:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Eric Milburn <br/> eric@zinite.com\" data-tooltip >emilburn</span></div></div></div><div id=\"booking-191571\" class=\"table-row group-1bd94fc24bc77a9c28737553cbbdde5c\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"11:00am Fri Nov 25th\" data-tooltip> 11:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"1:30pm Fri Nov 25th\" data-tooltip> 13:30 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Abbie Gottert <br/> abbie@norcada.com\" data-tooltip >agottert</span></div></div></div><div id=\"booking-191134\" class=\"table-row group-378f68e0c942509b067cf55bad7a404e\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"1:30pm Fri Nov 25th\" data-tooltip> 13:30</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"3:30pm Fri Nov 25th\" data-tooltip> 15:30 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Kevin Setzer <br/> kevin@appliednt.com\" data-tooltip >ksetzer</span></div></div></div><div id=\"booking-191602\" class=\"table-row group-5ed742c71f7260f261bd931d56e18f51\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"4:00pm Fri Nov 25th\" data-tooltip> 16:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"10:00pm Fri Nov 25th\" data-tooltip> 22:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Ahmed Elsherbiny <br/> ahmed.elsherbiny@shebamicrosystems.com\" data-tooltip >elsherbi</span></div></div></div></div></div><div id=\"ajax-rb-427\" name=\"results\" data-fade-out ></div></section><section ><p class=\"title\" data-section-title><a href=\"\">Sat, Nov 26</a></p><div class=\"content\" data-section-content><h4><small><a href=\"equipment-detail.php?tool_id=427\">Heidelberg MLA150</a></small></h4><div class=\"row\"><div class=\"columns\"><div class=\"table\">\r\n\t\t\t\t\t\t\t\t<div class=\"table-head\">\r\n\t\t\t\t\t\t\t\t\t<div class=\"row table-row\">\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">start</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">stop</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 left\">user</div>\r\n\t\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t<div class=\"table-body\" ><div id=\"booking-191702\" class=\"table-row group-402d78fd8e4fcee5e4d53a5ba6a39a92\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"6:00am Sat Nov 26th\" data-tooltip> 6:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"8:30am Sat Nov 26th\" data-tooltip> 8:30 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Wyatt James <br/> wyatt@norcada.com\" data-tooltip >wjames</span></div></div></div><div id=\"booking-191653\" class=\"table-row group-b357212ef36db734ec877d14d45575f7\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"8:30am Sat Nov 26th\" data-tooltip> 8:30</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"10:00am Sat Nov 26th\" data-tooltip> 10:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Alexandria McKinlay <br/> amckinlay@appliednt.com\" data-tooltip >amckinlay</span></div></div></div><div id=\"booking-191703\" class=\"table-row group-89de0efb3491a3f70030d030e05442d4\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"10:00am Sat Nov 26th\" data-tooltip> 10:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"12:00pm Sat Nov 26th\" data-tooltip> 12:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Wyatt James <br/> wyatt@norcada.com\" data-tooltip >wjames</span></div></div></div><div id=\"booking-191426\" class=\"table-row group-0d62f43375be21685f2c82f24972cd6c\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"2:00pm Sat Nov 26th\" data-tooltip> 14:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"4:00pm Sat Nov 26th\" data-tooltip> 16:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Eric Milburn <br/> eric@zinite.com\" data-tooltip >emilburn</span></div></div></div></div></div><div id=\"ajax-rb-427\" name=\"results\" data-fade-out ></div></section><section ><p class=\"title\" data-section-title><a href=\"\">Sun, Nov 27</a></p><div class=\"content\" data-section-content><h4><small><a href=\"equipment-detail.php?tool_id=427\">Heidelberg MLA150</a></small></h4><div class=\"row\"><div class=\"columns\"><div class=\"table\">\r\n\t\t\t\t\t\t\t\t<div class=\"table-head\">\r\n\t\t\t\t\t\t\t\t\t<div class=\"row table-row\">\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">start</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">stop</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 left\">user</div>\r\n\t\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t<div class=\"table-body\" ><div id=\"booking-191603\" class=\"table-row group-b125d24de84d1f18fe5abbf674121684\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"9:00am Sun Nov 27th\" data-tooltip> 9:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"1:00pm Sun Nov 27th\" data-tooltip> 13:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Ahmed Elsherbiny <br/> ahmed.elsherbiny@shebamicrosystems.com\" data-tooltip >elsherbi</span></div></div></div><div id=\"booking-191938\" class=\"table-row group-97e22a4d44845d631319369782426a13\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"4:30pm Sun Nov 27th\" data-tooltip> 16:30</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"6:30pm Sun Nov 27th\" data-tooltip> 18:30 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Eric Milburn <br/> eric@zinite.com\" data-tooltip >emilburn</span></div></div></div><div id=\"booking-191939\" class=\"table-row group-77f99f37c34a6997a85ebae7f31d46c8\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"6:30pm Sun Nov 27th\" data-tooltip> 18:30</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"8:30pm Sun Nov 27th\" data-tooltip> 20:30 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Eric Milburn <br/> eric@zinite.com\" data-tooltip >emilburn</span></div></div></div></div></div><div id=\"ajax-rb-427\" name=\"results\" data-fade-out ></div></section><section ><p class=\"title\" data-section-title><a href=\"\">Mon, Nov 28</a></p><div class=\"content\" data-section-content><h4><small><a href=\"equipment-detail.php?tool_id=427\">Heidelberg MLA150</a></small></h4><div class=\"row\"><div class=\"columns\"><div class=\"table\">\r\n\t\t\t\t\t\t\t\t<div class=\"table-head\">\r\n\t\t\t\t\t\t\t\t\t<div class=\"row table-row\">\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">start</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">stop</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 left\">user</div>\r\n\t\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t<div class=\"table-body\" ><div id=\"booking-191576\" class=\"table-row group-fe2b75dcb307e5f687ba5fc11c71d8e8\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"7:00am Mon Nov 28th\" data-tooltip> 7:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"9:00am Mon Nov 28th\" data-tooltip> 9:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Pedro Duarte Riveros <br/> duarteri@ualberta.ca\" data-tooltip >Duarteri</span></div></div></div><div id=\"booking-190707\" class=\"table-row group-e0b463a324cea71f7278407e04ab166d\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"12:00pm Mon Nov 28th\" data-tooltip> 12:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"2:00pm Mon Nov 28th\" data-tooltip> 14:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Min Wu <br/> wu2@ualberta.ca\" data-tooltip >min-wu</span></div></div></div><div id=\"booking-191809\" class=\"table-row group-5974cffcc961333e9d68e257282b0d98\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"2:00pm Mon Nov 28th\" data-tooltip> 14:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"4:00pm Mon Nov 28th\" data-tooltip> 16:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Daniel Mildenberger <br/> dmildenb@ualberta.ca\" data-tooltip >dmildenb</span></div></div></div></div></div><div id=\"ajax-rb-427\" name=\"results\" data-fade-out ></div></section><section ><p class=\"title\" data-section-title><a href=\"\">Tue, Nov 29</a></p><div class=\"content\" data-section-content><h4><small><a href=\"equipment-detail.php?tool_id=427\">Heidelberg MLA150</a></small></h4><div class=\"row\"><div class=\"columns\"><div class=\"table\">\r\n\t\t\t\t\t\t\t\t<div class=\"table-head\">\r\n\t\t\t\t\t\t\t\t\t<div class=\"row table-row\">\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">start</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">stop</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 left\">user</div>\r\n\t\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t<div class=\"table-body\" ><div id=\"booking-191577\" class=\"table-row group-979074d9d3f8c82821da62b5eab85c5d\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"7:00am Tue Nov 29th\" data-tooltip> 7:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"9:00am Tue Nov 29th\" data-tooltip> 9:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Pedro Duarte Riveros <br/> duarteri@ualberta.ca\" data-tooltip >Duarteri</span></div></div></div><div id=\"booking-191257\" class=\"table-row group-f04015d3be04dba84953a3cafad6d5c6\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"9:00am Tue Nov 29th\" data-tooltip> 9:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"11:00am Tue Nov 29th\" data-tooltip> 11:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Eric Milburn <br/> eric@zinite.com\" data-tooltip >emilburn</span></div></div></div><div id=\"booking-191336\" class=\"table-row group-8a1c073486cb52d05cf9c73dcaccf090\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"11:00am Tue Nov 29th\" data-tooltip> 11:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"1:00pm Tue Nov 29th\" data-tooltip> 13:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Cory Rewcastle <br/> cory@transeon.ca\" data-tooltip >crewcastle</span></div></div></div></div></div><div id=\"ajax-rb-427\" name=\"results\" data-fade-out ></div></section><section ><p class=\"title\" data-section-title><a href=\"\">Wed, Nov 30</a></p><div class=\"content\" data-section-content><h4><small><a href=\"equipment-detail.php?tool_id=427\">Heidelberg MLA150</a></small></h4><div class=\"row\"><div class=\"columns\"><div class=\"table\">\r\n\t\t\t\t\t\t\t\t<div class=\"table-head\">\r\n\t\t\t\t\t\t\t\t\t<div class=\"row table-row\">\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">start</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">stop</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 left\">user</div>\r\n\t\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t<div class=\"table-body\" ><div id=\"booking-191740\" class=\"table-row group-eae5dd63e9c0f87a89b3619277c86cf8\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"10:00am Wed Nov 30th\" data-tooltip> 10:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"12:00pm Wed Nov 30th\" data-tooltip> 12:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Cory Rewcastle <br/> cory@transeon.ca\" data-tooltip >crewcastle</span></div></div></div><div id=\"booking-190681\" class=\"table-row group-7f2cc60a903d4dbcd83bdc66cd4ce082\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"1:30pm Wed Nov 30th\" data-tooltip> 13:30</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"3:30pm Wed Nov 30th\" data-tooltip> 15:30 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Gustavo de Oliveira Luiz <br/> deolivei@ualberta.ca\" data-tooltip >gde-oliveira-luiz</span></div></div></div><div id=\"booking-191270\" class=\"table-row group-5542d9b02f97f3bd12e9e1762f90e86b\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"4:00pm Wed Nov 30th\" data-tooltip> 16:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"6:00pm Wed Nov 30th\" data-tooltip> 18:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Min Wu <br/> wu2@ualberta.ca\" data-tooltip >min-wu</span></div></div></div></div></div><div id=\"ajax-rb-427\" name=\"results\" data-fade-out ></div></section><section ><p class=\"title\" data-section-title><a href=\"\">Thu, Dec 1</a></p><div class=\"content\" data-section-content><h4><small><a href=\"equipment-detail.php?tool_id=427\">Heidelberg MLA150</a></small></h4><div class=\"row\"><div class=\"columns\"><div class=\"table\">\r\n\t\t\t\t\t\t\t\t<div class=\"table-head\">\r\n\t\t\t\t\t\t\t\t\t<div class=\"row table-row\">\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">start</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">stop</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 left\">user</div>\r\n\t\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t<div class=\"table-body\" ><div id=\"booking-190698\" class=\"table-row group-b51f070ebe4f5b78c63ceb66de19324f\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"9:00am Thu Dec 1st\" data-tooltip> 9:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"11:00am Thu Dec 1st\" data-tooltip> 11:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Gustavo de Oliveira Luiz <br/> deolivei@ualberta.ca\" data-tooltip >gde-oliveira-luiz</span></div></div></div><div id=\"booking-191346\" class=\"table-row group-213fa1f6f5c4d9309e1700676dcbe4cf\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"11:00am Thu Dec 1st\" data-tooltip> 11:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"1:00pm Thu Dec 1st\" data-tooltip> 13:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Cory Rewcastle <br/> cory@transeon.ca\" data-tooltip >crewcastle</span></div></div></div></div></div><div id=\"ajax-rb-427\" name=\"results\" data-fade-out ></div></section><section ><p class=\"title\" data-section-title><a href=\"\">Mon, Dec 5</a></p><div class=\"content\" data-section-content><h4><small><a href=\"equipment-detail.php?tool_id=427\">Heidelberg MLA150</a></small></h4><div class=\"row\"><div class=\"columns\"><div class=\"table\">\r\n\t\t\t\t\t\t\t\t<div class=\"table-head\">\r\n\t\t\t\t\t\t\t\t\t<div class=\"row table-row\">\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">start</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">stop</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 left\">user</div>\r\n\t\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t<div class=\"table-body\" ><div id=\"booking-191275\" class=\"table-row group-f52c97502eb34eebc15bc231ba4f2e33\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"2:00pm Mon Dec 5th\" data-tooltip> 14:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"4:00pm Mon Dec 5th\" data-tooltip> 16:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Eric Milburn <br/> eric@zinite.com\" data-tooltip >emilburn</span></div></div></div></div></div><div id=\"ajax-rb-427\" name=\"results\" data-fade-out ></div></section><section ><p class=\"title\" data-section-title><a href=\"\">Wed, Dec 7</a></p><div class=\"content\" data-section-content><h4><small><a href=\"equipment-detail.php?tool_id=427\">Heidelberg MLA150</a></small></h4><div class=\"row\"><div class=\"columns\"><div class=\"table\">\r\n\t\t\t\t\t\t\t\t<div class=\"table-head\">\r\n\t\t\t\t\t\t\t\t\t<div class=\"row table-row\">\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">start</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">stop</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 left\">user</div>\r\n\t\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t<div class=\"table-body\" ><div id=\"booking-191281\" class=\"table-row group-ecefe6746df8464aaf0af04bb919b72a\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"9:00am Wed Dec 7th\" data-tooltip> 9:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"11:00am Wed Dec 7th\" data-tooltip> 11:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Eric Milburn <br/> eric@zinite.com\" data-tooltip >emilburn</span></div></div></div><div id=\"booking-190685\" class=\"table-row group-85c33a95a664b0c7478022fd2ad4d837\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"1:30pm Wed Dec 7th\" data-tooltip> 13:30</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"3:30pm Wed Dec 7th\" data-tooltip> 15:30 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Gustavo de Oliveira Luiz <br/> deolivei@ualberta.ca\" data-tooltip >gde-oliveira-luiz</span></div></div></div></div></div><div id=\"ajax-rb-427\" name=\"results\" data-fade-out ></div></section><section ><p class=\"title\" data-section-title><a href=\"\">Thu, Dec 8</a></p><div class=\"content\" data-section-content><h4><small><a href=\"equipment-detail.php?tool_id=427\">Heidelberg MLA150</a></small></h4><div class=\"row\"><div class=\"columns\"><div class=\"table\">\r\n\t\t\t\t\t\t\t\t<div class=\"table-head\">\r\n\t\t\t\t\t\t\t\t\t<div class=\"row table-row\">\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">start</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">stop</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 left\">user</div>\r\n\t\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t<div class=\"table-body\" ><div id=\"booking-190699\" class=\"table-row group-e0b5728e888dc7948946dcd3fcca1caf\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"9:00am Thu Dec 8th\" data-tooltip> 9:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"11:00am Thu Dec 8th\" data-tooltip> 11:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Gustavo de Oliveira Luiz <br/> deolivei@ualberta.ca\" data-tooltip >gde-oliveira-luiz</span></div></div></div><div id=\"booking-191286\" class=\"table-row group-89fbf79710e8645968f969278e718db2\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"12:00pm Thu Dec 8th\" data-tooltip> 12:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"2:00pm Thu Dec 8th\" data-tooltip> 14:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Eric Milburn <br/> eric@zinite.com\" data-tooltip >emilburn</span></div></div></div></div></div><div id=\"ajax-rb-427\" name=\"results\" data-fade-out ></div></section><section ><p class=\"title\" data-section-title><a href=\"\">Wed, Dec 14</a></p><div class=\"content\" data-section-content><h4><small><a href=\"equipment-detail.php?tool_id=427\">Heidelberg MLA150</a></small></h4><div class=\"row\"><div class=\"columns\"><div class=\"table\">\r\n\t\t\t\t\t\t\t\t<div class=\"table-head\">\r\n\t\t\t\t\t\t\t\t\t<div class=\"row table-row\">\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">start</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">stop</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 left\">user</div>\r\n\t\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t<div class=\"table-body\" ><div id=\"booking-190686\" class=\"table-row group-c0fa0d01811c2d074a34a1cb7ebfc137\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"1:30pm Wed Dec 14th\" data-tooltip> 13:30</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"3:30pm Wed Dec 14th\" data-tooltip> 15:30 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Gustavo de Oliveira Luiz <br/> deolivei@ualberta.ca\" data-tooltip >gde-oliveira-luiz</span></div></div></div></div></div><div id=\"ajax-rb-427\" name=\"results\" data-fade-out ></div></section><section ><p class=\"title\" data-section-title><a href=\"\">Thu, Dec 15</a></p><div class=\"content\" data-section-content><h4><small><a href=\"equipment-detail.php?tool_id=427\">Heidelberg MLA150</a></small></h4><div class=\"row\"><div class=\"columns\"><div class=\"table\">\r\n\t\t\t\t\t\t\t\t<div class=\"table-head\">\r\n\t\t\t\t\t\t\t\t\t<div class=\"row table-row\">\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">start</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 \">stop</div>\r\n\t\t\t\t\t\t\t\t\t\t<div class=\"columns small-4 left\">user</div>\r\n\t\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t</div>\r\n\t\t\t\t\t\t\t\t<div class=\"table-body\" ><div id=\"booking-190700\" class=\"table-row group-032074d744988726d6e4e90acf98f994\"><div class=\"row\"><div class=\"columns small-4\"><span class=\"has-tip\" title=\"9:00am Thu Dec 15th\" data-tooltip> 9:00</span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"11:00am Thu Dec 15th\" data-tooltip> 11:00 </span></div><div class=\"columns small-4\"><span class=\"has-tip\" title=\"Gustavo de Oliveira Luiz <br/> deolivei@ualberta.ca\" data-tooltip >gde-oliveira-luiz</span></div></div></div></div></div><div id=\"ajax-rb-427\" name=\"results\" data-fade-out ></div></section></div>";
 }
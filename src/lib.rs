@@ -0,0 +1,12 @@
+pub mod cli;
+pub mod config;
+pub mod debug_log;
+pub mod fuzzy;
+pub mod html;
+pub mod logins;
+pub mod nanofab;
+pub mod schedule;
+#[cfg(feature = "svg")]
+pub mod svg;
+pub mod term_ui;
+pub mod transport;
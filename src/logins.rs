@@ -0,0 +1,356 @@
+use std::{collections::BTreeMap, path::Path};
+
+use anyhow::{anyhow, Context, Result};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use chacha20poly1305::{
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
+    ChaCha20Poly1305, Key, Nonce,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::nanofab::{write_private, Login};
+
+pub const LOGINS_FILENAME: &str = "logins.ron";
+/// Where the one saved login was kept before there were profiles
+pub const OLD_LOGIN_FILENAME: &str = "login.ron";
+/// Where the key for saved passwords is kept in the OS keyring
+const KEYRING_SERVICE: &str = "nanofab-cli";
+const KEYRING_USER: &str = "saved-logins";
+
+/// A saved login's password couldn't be read back, from the OS keyring being unavailable
+/// or the password not decrypting, so it has to be typed in instead
+#[derive(Debug)]
+pub struct SavedLoginLocked;
+impl std::fmt::Display for SavedLoginLocked {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "The saved login can't be unlocked, please log in")
+    }
+}
+impl std::error::Error for SavedLoginLocked {}
+
+/// The key saved passwords are encrypted with, so `logins.ron` alone doesn't give them away
+pub struct LoginKey(Key);
+impl LoginKey {
+    /// The key kept in the OS keyring, made and stored there the first time
+    pub fn from_keyring() -> Result<Self> {
+        let entry = keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER)
+            .context("Failed to open the OS keyring")?;
+        match entry.get_password() {
+            Ok(encoded) => {
+                let bytes = BASE64.decode(encoded.trim()).ok().filter(|bytes| bytes.len() == 32);
+                let bytes =
+                    bytes.context("The key for saved logins in the OS keyring is invalid")?;
+                Ok(Self(*Key::from_slice(&bytes)))
+            }
+            Err(keyring::Error::NoEntry) => {
+                let key = Self::generate();
+                entry
+                    .set_password(&BASE64.encode(key.0))
+                    .context("Failed to store the key for saved logins in the OS keyring")?;
+                Ok(key)
+            }
+            Err(err) => {
+                Err(err).context("Failed to read the key for saved logins from the OS keyring")
+            }
+        }
+    }
+    pub fn generate() -> Self {
+        Self(ChaCha20Poly1305::generate_key(&mut OsRng))
+    }
+}
+
+/// A login as kept in `logins.ron`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedLogin {
+    pub username: String,
+    password: SavedPassword,
+}
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum SavedPassword {
+    /// Moved over from a `login.ron`, until the profile is next used
+    Plain(String),
+    /// Sealed with a `LoginKey`, tied to the username so it can't be moved to another profile
+    Encrypted { nonce: String, ciphertext: String },
+}
+impl SavedLogin {
+    fn encrypt(login: &Login, key: &LoginKey) -> Self {
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let payload = Payload { msg: login.password.as_bytes(), aad: login.username.as_bytes() };
+        let ciphertext = ChaCha20Poly1305::new(&key.0)
+            .encrypt(&nonce, payload)
+            .expect("Encrypting a password should not fail");
+        let password = SavedPassword::Encrypted {
+            nonce: BASE64.encode(nonce),
+            ciphertext: BASE64.encode(ciphertext),
+        };
+        Self { username: login.username.clone(), password }
+    }
+    /// The login with its password decrypted. Fails if the key is wrong or the saved login
+    /// was changed.
+    pub fn decrypt(&self, key: &LoginKey) -> Result<Login> {
+        let password = match &self.password {
+            SavedPassword::Plain(password) => password.clone(),
+            SavedPassword::Encrypted { nonce, ciphertext } => {
+                let failed =
+                    || anyhow!("Could not decrypt the saved password for `{}`", self.username);
+                let nonce = BASE64.decode(nonce).ok().filter(|nonce| nonce.len() == 12);
+                let nonce = nonce.ok_or_else(failed)?;
+                let ciphertext = BASE64.decode(ciphertext).map_err(|_| failed())?;
+                let payload = Payload { msg: &ciphertext, aad: self.username.as_bytes() };
+                let password = ChaCha20Poly1305::new(&key.0)
+                    .decrypt(Nonce::from_slice(&nonce), payload)
+                    .map_err(|_| failed())?;
+                String::from_utf8(password).map_err(|_| failed())?
+            }
+        };
+        Ok(Login { username: self.username.clone(), password })
+    }
+    pub fn is_encrypted(&self) -> bool {
+        matches!(self.password, SavedPassword::Encrypted { .. })
+    }
+}
+
+/// Saved logins by profile name, like a personal and a shared-project account
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SavedLogins {
+    profiles: BTreeMap<String, SavedLogin>,
+    /// The profile logged in with last, which the saved session cookies belong to
+    last_used: Option<String>,
+}
+
+/// How to log in at startup with the saved profiles
+#[derive(Debug, PartialEq, Eq)]
+pub enum ProfileChoice<'a> {
+    /// Nothing is saved, so ask for a new login
+    New,
+    /// Use the only saved profile without asking
+    Use(&'a str),
+    /// Let the user pick one of several
+    Ask,
+}
+
+impl SavedLogins {
+    /// The logins saved in `dir`. A `login.ron` from before profiles is first moved into
+    /// `logins.ron`, as a profile named after its username. Its password stays readable until
+    /// `insert` saves it again.
+    pub fn load(dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = dir.as_ref();
+        let path = dir.join(LOGINS_FILENAME);
+        if path.exists() {
+            let raw = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read `{}`", path.display()))?;
+            return ron::from_str(&raw)
+                .with_context(|| format!("Failed to parse `{}`", path.display()));
+        }
+        let old_path = dir.join(OLD_LOGIN_FILENAME);
+        let mut logins = Self::default();
+        let Ok(raw) = std::fs::read_to_string(&old_path) else { return Ok(logins) };
+        let login = ron::from_str::<Login>(&raw)
+            .with_context(|| format!("Failed to parse `{}`", old_path.display()))?;
+        let name = login.username.clone();
+        let password = SavedPassword::Plain(login.password);
+        logins.profiles.insert(name.clone(), SavedLogin { username: login.username, password });
+        // The saved cookies were that login's, so it stays the last used
+        logins.last_used = Some(name);
+        logins.save(dir)?;
+        std::fs::remove_file(&old_path)
+            .with_context(|| format!("Failed to remove `{}`", old_path.display()))?;
+        Ok(logins)
+    }
+    pub fn save(&self, dir: impl AsRef<Path>) -> Result<()> {
+        let path = dir.as_ref().join(LOGINS_FILENAME);
+        let raw = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?;
+        write_private(&path, &raw).with_context(|| format!("Failed to write `{}`", path.display()))
+    }
+    pub fn is_empty(&self) -> bool {
+        self.profiles.is_empty()
+    }
+    /// Profile names in alphabetical order
+    pub fn names(&self) -> Vec<&str> {
+        self.profiles.keys().map(String::as_str).collect()
+    }
+    pub fn get(&self, name: &str) -> Option<&SavedLogin> {
+        self.profiles.get(name)
+    }
+    pub fn last_used(&self) -> Option<&str> {
+        self.last_used.as_deref().filter(|name| self.profiles.contains_key(*name))
+    }
+    /// Mark `name` as the profile the saved session cookies belong to
+    pub fn set_last_used(&mut self, name: &str) {
+        self.last_used = Some(name.to_string());
+    }
+    /// The last used profile, if it is the one logged in as `username`. The saved session
+    /// cookies belong to it.
+    pub fn session_profile(&self, username: &str) -> Option<&str> {
+        self.last_used().filter(|name| self.profiles[*name].username == username)
+    }
+    /// Save `login` as `name` with its password encrypted by `key`, replacing any login
+    /// already under it, and mark it last used
+    pub fn insert(&mut self, name: impl Into<String>, login: &Login, key: &LoginKey) {
+        let name = name.into();
+        self.profiles.insert(name.clone(), SavedLogin::encrypt(login, key));
+        self.last_used = Some(name);
+    }
+    pub fn remove(&mut self, name: &str) -> Option<SavedLogin> {
+        if self.last_used.as_deref() == Some(name) {
+            self.last_used = None;
+        }
+        self.profiles.remove(name)
+    }
+    pub fn startup_choice(&self) -> ProfileChoice<'_> {
+        match self.names()[..] {
+            [] => ProfileChoice::New,
+            [name] => ProfileChoice::Use(name),
+            _ => ProfileChoice::Ask,
+        }
+    }
+    /// The profile to use when there is no one to ask, like on the command line: the only
+    /// one, or else the last used
+    pub fn default_profile(&self) -> Option<&str> {
+        match self.startup_choice() {
+            ProfileChoice::Use(name) => Some(name),
+            _ => self.last_used(),
+        }
+    }
+    /// Where the profile picker starts, on the last used profile in `names`
+    pub fn picker_start(&self) -> usize {
+        let last_used = self.last_used();
+        self.profiles.keys().position(|name| Some(name.as_str()) == last_used).unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn login(username: &str) -> Login {
+        Login { username: username.to_string(), password: format!("{username}-pass") }
+    }
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("nanofab-cli-{name}-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_migrate_old_login() {
+        let dir = temp_dir("migrate");
+        let old = Login { username: "wjames".into(), password: "hunter2".into() };
+        std::fs::write(dir.join(OLD_LOGIN_FILENAME), ron::to_string(&old).unwrap()).unwrap();
+        let mut logins = SavedLogins::load(&dir).unwrap();
+        assert_eq!(logins.names(), ["wjames"]);
+        let key = LoginKey::generate();
+        let saved = logins.get("wjames").unwrap();
+        assert!(!saved.is_encrypted());
+        assert_eq!(saved.decrypt(&key).unwrap().password, "hunter2");
+        assert_eq!(logins.last_used(), Some("wjames"));
+        assert!(!dir.join(OLD_LOGIN_FILENAME).exists());
+        // Saving it again seals the password
+        logins.insert("wjames", &old, &key);
+        logins.save(&dir).unwrap();
+        let raw = std::fs::read_to_string(dir.join(LOGINS_FILENAME)).unwrap();
+        assert!(!raw.contains("hunter2"));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(dir.join(LOGINS_FILENAME)).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        let logins = SavedLogins::load(&dir).unwrap();
+        assert_eq!(logins.names(), ["wjames"]);
+        assert!(logins.get("wjames").unwrap().is_encrypted());
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(SavedLogins::load(&dir).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = temp_dir("logins");
+        let key = LoginKey::generate();
+        let mut logins = SavedLogins::default();
+        logins.insert("personal", &login("wjames"), &key);
+        logins.insert("shared", &login("project7"), &key);
+        logins.set_last_used("personal");
+        logins.save(&dir).unwrap();
+        let loaded = SavedLogins::load(&dir).unwrap();
+        assert_eq!(loaded.names(), ["personal", "shared"]);
+        let shared = loaded.get("shared").unwrap().decrypt(&key).unwrap();
+        assert_eq!(
+            (shared.username.as_str(), shared.password.as_str()),
+            ("project7", "project7-pass")
+        );
+        assert_eq!(loaded.last_used(), Some("personal"));
+        assert_eq!(loaded.session_profile("wjames"), Some("personal"));
+        // Logged in some other way than the last used profile
+        assert_eq!(loaded.session_profile("project7"), None);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_profile_choice() {
+        let key = LoginKey::generate();
+        let mut logins = SavedLogins::default();
+        assert_eq!(logins.startup_choice(), ProfileChoice::New);
+        assert_eq!(logins.default_profile(), None);
+        logins.insert("shared", &login("project7"), &key);
+        assert_eq!(logins.startup_choice(), ProfileChoice::Use("shared"));
+        logins.insert("personal", &login("wjames"), &key);
+        assert_eq!(logins.startup_choice(), ProfileChoice::Ask);
+        assert_eq!(logins.default_profile(), Some("personal"));
+        assert_eq!(logins.picker_start(), 0);
+        logins.set_last_used("shared");
+        assert_eq!(logins.picker_start(), 1);
+        // Removing the last used profile leaves nothing to fall back on with several left
+        logins.insert("other", &login("other"), &key);
+        logins.remove("other");
+        assert_eq!(logins.last_used(), None);
+        assert_eq!(logins.default_profile(), None);
+        assert_eq!(logins.picker_start(), 0);
+        logins.remove("personal");
+        assert_eq!(logins.default_profile(), Some("shared"));
+    }
+
+    #[test]
+    fn test_decrypt_round_trip() {
+        let key = LoginKey::generate();
+        let saved = SavedLogin::encrypt(&login("wjames"), &key);
+        assert!(saved.is_encrypted());
+        let decrypted = saved.decrypt(&key).unwrap();
+        assert_eq!(decrypted.password, "wjames-pass");
+        // A fresh nonce each time, so the same password doesn't save the same twice
+        let again = SavedLogin::encrypt(&login("wjames"), &key);
+        assert_ne!(format!("{:?}", again.password), format!("{:?}", saved.password));
+        assert!(saved.decrypt(&LoginKey::generate()).is_err());
+    }
+
+    #[test]
+    fn test_decrypt_tampered_file() {
+        let dir = temp_dir("tampered");
+        let key = LoginKey::generate();
+        let mut logins = SavedLogins::default();
+        logins.insert("personal", &login("wjames"), &key);
+        logins.insert("shared", &login("project7"), &key);
+        logins.save(&dir).unwrap();
+        let path = dir.join(LOGINS_FILENAME);
+        let raw = std::fs::read_to_string(&path).unwrap();
+        let SavedPassword::Encrypted { ciphertext, .. } = &logins.get("personal").unwrap().password
+        else {
+            panic!("Not encrypted")
+        };
+        // Flip one byte of the ciphertext
+        let mut bytes = BASE64.decode(ciphertext).unwrap();
+        bytes[0] ^= 1;
+        std::fs::write(&path, raw.replace(ciphertext.as_str(), &BASE64.encode(bytes))).unwrap();
+        let tampered = SavedLogins::load(&dir).unwrap();
+        let err = tampered.get("personal").unwrap().decrypt(&key).unwrap_err();
+        assert_eq!(err.to_string(), "Could not decrypt the saved password for `wjames`");
+        assert!(tampered.get("shared").unwrap().decrypt(&key).is_ok());
+        // A password moved to another username doesn't decrypt either
+        let mut moved = tampered.get("shared").unwrap().clone();
+        moved.username = "wjames".to_string();
+        assert!(moved.decrypt(&key).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
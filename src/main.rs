@@ -1,30 +1,138 @@
-mod html;
-mod nanofab;
-mod schedule;
-mod term_ui;
-
-use anyhow::{bail, Result};
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{Duration, NaiveDate, NaiveTime};
 use crossterm::{
     cursor,
     event::{self, KeyCode},
-    style, terminal, ExecutableCommand, QueueableCommand,
+    style::{self, Color},
+    terminal, ExecutableCommand, QueueableCommand,
 };
 use itertools::Itertools;
 use std::{
-    io::{stdout, Write},
+    io::{stdout, IsTerminal, Write},
     path::Path,
     vec,
 };
-use term_ui::display_error_msg;
 
-use crate::nanofab::{Login, NanoFab, Tool};
-use crate::term_ui::{EventObject, QueueableCommand as _};
+use clap::Parser;
+use nanofab_cli::cli::{self, Cli, Command, OpeningsArgs};
+use nanofab_cli::config::{self, Config, Team};
+use nanofab_cli::debug_log;
+use nanofab_cli::logins::{LoginKey, ProfileChoice, SavedLoginLocked, SavedLogins};
+use nanofab_cli::nanofab::{
+    lab_now, search_tools, Booking, Login, NanoFab, SessionExpired, Tool, LAB_TIMEZONE,
+};
+use nanofab_cli::schedule::{self, ExportMeta, TimeSlot, TimeTable};
+use nanofab_cli::term_ui::{
+    self, display_error_msg, display_view_help, read_event, selected_option, Binding,
+    DateRangeInput, EventObject, Pager, PagerExit, QueueableCommand as _, QuitRequested, Spinner,
+    TextInput, ToggleSet, CONFIRM_KEYS, DATE_RANGE_KEYS, UPDOWN_KEYS,
+};
 
 const CONFIG_DIR: &str = ".nanofab-cli";
-const LOGIN_FILENAME: &str = "login.ron";
+const COOKIES_FILENAME: &str = "cookies.txt";
+const CONFIG_FILENAME: &str = "config.ron";
+const DEBUG_LOG_FILENAME: &str = "debug.log";
+
+const USERNAME_PROMPT: &str = "Enter username: ";
+const PASSWORD_PROMPT: &str = "Enter password: ";
+const CLIENT_RETRIES: u32 = 3;
+const LONG_OPENING_HOURS: i64 = 2;
+/// How far ahead the openings view looks unless another end date is picked
+const OPENINGS_VIEW_DAYS: i64 = 30;
+/// What the keys of a view do besides its drivers' keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ViewAction {
+    Choose,
+    Back,
+    Sort,
+}
+const MAIN_MENU_KEYS: &[Binding<ViewAction>] = &[
+    Binding {
+        codes: &[KeyCode::Enter],
+        help: ("Enter/Click", "Choose option"),
+        action: ViewAction::Choose,
+    },
+    Binding { codes: &[KeyCode::Esc], help: ("Esc", "Exit"), action: ViewAction::Back },
+];
+const PROFILE_SELECT_KEYS: &[Binding<ViewAction>] = &[
+    Binding {
+        codes: &[KeyCode::Enter],
+        help: ("Enter/Click", "Choose login"),
+        action: ViewAction::Choose,
+    },
+    Binding { codes: &[KeyCode::Esc], help: ("Esc", "Back"), action: ViewAction::Back },
+];
+const PROJECTS_VIEW_KEYS: &[Binding<ViewAction>] = &[
+    Binding {
+        codes: &[KeyCode::Char('s')],
+        help: ("s", "Change sort order"),
+        action: ViewAction::Sort,
+    },
+    Binding {
+        codes: &[KeyCode::Enter, KeyCode::Esc],
+        help: ("Enter/Esc", "Back"),
+        action: ViewAction::Back,
+    },
+];
+const DATE_RANGE_VIEW_KEYS: &[Binding<ViewAction>] = &[
+    Binding {
+        codes: &[KeyCode::Enter],
+        help: ("Enter", "Look at these days"),
+        action: ViewAction::Choose,
+    },
+    Binding { codes: &[KeyCode::Esc], help: ("Esc", "Go back"), action: ViewAction::Back },
+];
+/// What the openings view's keys do on top of the `Pager` ones
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OpeningsAction {
+    Cap,
+    EarliestStart,
+    LongOpening,
+    MinLength,
+    Export,
+}
+const OPENINGS_VIEW_KEYS: &[Binding<OpeningsAction>] = &[
+    Binding {
+        codes: &[KeyCode::Char('n')],
+        help: ("n", "Show all, the first 10 or the first 25 openings, when not searching"),
+        action: OpeningsAction::Cap,
+    },
+    Binding {
+        codes: &[KeyCode::Char('e')],
+        help: ("e", "Set the earliest start time each day"),
+        action: OpeningsAction::EarliestStart,
+    },
+    Binding {
+        codes: &[KeyCode::Char('g')],
+        help: ("g", "Jump to the first opening of at least 2 hours"),
+        action: OpeningsAction::LongOpening,
+    },
+    Binding {
+        codes: &[KeyCode::Char('m')],
+        help: ("m", "Hide openings shorter than a length of time"),
+        action: OpeningsAction::MinLength,
+    },
+    export_binding(OpeningsAction::Export),
+];
+const USER_BOOKINGS_KEYS: &[Binding<()>] = &[export_binding(())];
+
+const fn export_binding<A>(action: A) -> Binding<A> {
+    Binding { codes: &[KeyCode::Char('x')], help: ("x", "Export to a CSV or JSON file"), action }
+}
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    let cli = Cli::parse();
+    if cli.verbose {
+        let mut log_filepath = dirs::home_dir().unwrap();
+        log_filepath.push(CONFIG_DIR);
+        std::fs::create_dir(&log_filepath).ok();
+        log_filepath.push(DEBUG_LOG_FILENAME);
+        debug_log::enable(&log_filepath)?;
+    }
+    if let Some(command) = &cli.command {
+        return run_cli(&cli, command).await;
+    }
     crossterm::terminal::enable_raw_mode()?;
     stdout()
         .execute(crossterm::terminal::EnterAlternateScreen)?
@@ -35,69 +143,305 @@ async fn main() -> Result<()> {
     res
 }
 
+async fn run_cli(cli: &Cli, command: &Command) -> Result<()> {
+    let mut config_dir = dirs::home_dir().unwrap();
+    config_dir.push(CONFIG_DIR);
+    let config = Config::load(config_dir.join(CONFIG_FILENAME))?;
+    let client = new_client();
+    let ask_password = || {
+        if !std::io::stdin().is_terminal() {
+            bail!("No password for `--username`, pass `--password` or set NANOFAB_PASSWORD");
+        }
+        term_ui::read_hidden_line("Password: ")
+    };
+    match cli.login(ask_password)? {
+        Some(login) => client.authenticate(&login).await?,
+        None => {
+            let logins = SavedLogins::load(&config_dir)?;
+            let name = logins
+                .default_profile()
+                .context("No saved login to use, run `nanofab-cli` to save or pick one")?;
+            saved_login(&client, &config_dir, name).await?;
+        }
+    }
+    match command {
+        Command::Openings(args) => cli_openings(&client, config, args).await,
+        Command::Bookings => {
+            let mut bookings = client.get_user_bookings().await?.to_local(&LAB_TIMEZONE);
+            bookings.coalesce_adjacent_by(Booking::same_reservation);
+            print!("{}", cli::format_bookings(&bookings));
+            Ok(())
+        }
+        Command::Projects => {
+            let mut projects = client.get_user_projects().await?;
+            config.projects.sort.sort(&mut projects);
+            print!("{}", cli::format_projects(&projects));
+            Ok(())
+        }
+        Command::ExportIcs { file, tools } => export_ics(&client, &config, file, tools).await,
+        #[cfg(feature = "svg")]
+        Command::ExportSvg { tool, file } => export_svg(&client, tool, file).await,
+    }
+}
+
+async fn cli_openings(client: &NanoFab, mut config: Config, args: &OpeningsArgs) -> Result<()> {
+    let settings = &mut config.openings;
+    settings.lead_minutes = args.lead.unwrap_or(settings.lead_minutes);
+    settings.trail_minutes = args.trail.unwrap_or(settings.trail_minutes);
+    settings.min_minutes = args.min.unwrap_or(settings.min_minutes);
+    let tool = client.get_tool_from_label(&args.tool).await?;
+    let today = lab_now().date();
+    let ahead = args.days.map(|days| Duration::days(days as i64));
+    let too_far = || anyhow!("`--days` is too far ahead");
+    let end = ahead.map(|ahead| today.checked_add_signed(ahead).ok_or_else(too_far)).transpose()?;
+    let bookings = client.get_tool_bookings(&tool, Some(today), end).await?;
+    let mut openings = filter_openings(bookings, settings);
+    if let Some(ahead) = ahead {
+        let now = lab_now();
+        openings.clip_to(now, now.checked_add_signed(ahead).ok_or_else(too_far)?);
+    }
+    print!("{}", cli::format_openings(&openings.to_local(&LAB_TIMEZONE), args.format));
+    Ok(())
+}
+
+/// Write the openings when every one of `labels` is free to an iCalendar file,
+/// one event per window, so they can be overlaid on a shared calendar
+async fn export_ics(
+    client: &NanoFab,
+    config: &Config,
+    path: impl AsRef<Path>,
+    labels: &[String],
+) -> Result<()> {
+    let today = Some(lab_now().date());
+    let mut tools = vec![];
+    // Keep the working hours and days, but leave trimming until the common openings are known
+    let untrimmed = config::Openings {
+        hours: config.openings.hours,
+        closed_days: config.openings.closed_days.clone(),
+        holidays: config.openings.holidays.clone(),
+        ..Default::default()
+    };
+    let mut openings: Option<TimeTable<()>> = None;
+    for label in labels {
+        let tool = client.get_tool_from_label(label).await?;
+        let bookings = client.get_tool_bookings(&tool, today, None).await?;
+        let tool_openings = filter_openings(bookings, &untrimmed);
+        openings = Some(match openings {
+            Some(openings) => openings.intersect(&tool_openings),
+            None => tool_openings,
+        });
+        tools.push(tool.label);
+    }
+    // Trim and drop short windows only once the common openings are known
+    let mut openings = openings.expect("At least one tool label is given");
+    trim_openings(&mut openings, &config.openings);
+    std::fs::write(
+        path,
+        openings.to_ical(&LAB_TIMEZONE, &format!("All free: {}", tools.join(", "))),
+    )?;
+    Ok(())
+}
+
+#[cfg(feature = "svg")]
+async fn export_svg(client: &NanoFab, label: &str, path: impl AsRef<Path>) -> Result<()> {
+    let tool = client.get_tool_from_label(label).await?;
+    let today = lab_now().date();
+    let end = today.checked_add_days(chrono::Days::new(6));
+    let bookings = client.get_tool_bookings(&tool, Some(today), end).await?.to_local(&LAB_TIMEZONE);
+    let svg =
+        nanofab_cli::svg::render_timeline(&bookings, today, 7, |booking| booking.user_name.clone());
+    std::fs::write(path, svg)?;
+    Ok(())
+}
+
+/// A client that rides out brief network drops, like on campus wifi
+fn new_client() -> NanoFab {
+    NanoFab::new().with_retries(CLIENT_RETRIES, std::time::Duration::from_millis(500))
+}
+
+/// Log in with the saved profile `name`, resuming the saved session if it is that profile's
+async fn saved_login(client: &NanoFab, config_dir: &Path, name: &str) -> Result<Login> {
+    let mut logins = SavedLogins::load(config_dir)?;
+    let saved = logins.get(name).with_context(|| format!("No saved login `{name}`"))?;
+    let key = LoginKey::from_keyring().context(SavedLoginLocked)?;
+    let login = saved.decrypt(&key).context(SavedLoginLocked)?;
+    let was_encrypted = saved.is_encrypted();
+    let is_last_used = logins.last_used() == Some(name);
+    // The client logs in with `login` by itself if the server has dropped the saved session
+    match is_last_used && client.load_cookies(config_dir.join(COOKIES_FILENAME))? {
+        true => client.resume(&login),
+        false => client.authenticate(&login).await?,
+    }
+    if !was_encrypted {
+        // Moved over from `login.ron`, so seal the password now that it is known to work
+        logins.insert(name, &login, &key);
+        logins.save(config_dir)?;
+    } else if !is_last_used {
+        logins.set_last_used(name);
+        logins.save(config_dir)?;
+    }
+    Ok(login)
+}
+
 async fn run_ui() -> Result<()> {
     // Create the config dir if it doesn't exist
     let mut config_dir = dirs::home_dir().unwrap();
     config_dir.push(CONFIG_DIR);
-    let mut login_filepath = config_dir.clone();
-    login_filepath.push(LOGIN_FILENAME);
+    let mut config_filepath = config_dir.clone();
+    config_filepath.push(CONFIG_FILENAME);
     std::fs::create_dir(&config_dir).ok();
+    let config = Config::load(&config_filepath)
+        .or_else(|err| display_error_msg(err).map(|_| Config::default()))?;
+    term_ui::set_theme(config.theme);
 
-    // Create the client struct
-    let client = NanoFab::new();
+    let idle_timeout = config
+        .session
+        .idle_timeout_minutes
+        .map(|minutes| std::time::Duration::from_secs(minutes * 60));
+    let mut use_saved_login = true;
+    'session: loop {
+        // Create the client struct, fresh for every session so only saved cookies carry over
+        let client = new_client();
 
-    // Login the user
-    loop {
-        let err = match user_login(&client).await {
-            Ok(Some(_)) => break,
-            Ok(None) => return Ok(()),
-            Err(e) => e,
+        // Login the user
+        let login = loop {
+            let err = match user_login(&client, &config_dir, use_saved_login).await {
+                Ok(Some(login)) => break login,
+                Ok(None) => return Ok(()),
+                Err(e) => e,
+            };
+            // Only a saved login that can't be unlocked is typed in, other errors like a
+            // dropped connection are worth retrying with it
+            if err.downcast_ref::<SavedLoginLocked>().is_some() {
+                use_saved_login = false;
+            }
+            display_error_msg(err)?;
         };
-        display_error_msg(err)?;
-    }
+        save_session(&client, &config_dir, &login).or_else(display_error_msg)?;
+        // Only shown as a reminder of who is logged in, so the menu goes without it rather
+        // than opening every session on an error
+        let account = client
+            .get_account()
+            .await
+            .map_err(|err| debug_log::failure("Account lookup", &err))
+            .ok();
 
-    // Main menu
-    let mut selector = Some(0);
-    loop {
-        let mut options = vec![];
-        options.push("List Tool Openings");
-        options.push("List User Bookings");
-        options.push("List User Projects");
-        if login_filepath.exists() {
-            options.push("Delete Saved Login");
-        }
-        options.push("Exit");
-        stdout()
-            .queue(cursor::Hide)?
-            .queue(cursor::MoveTo(0, 0))?
-            .queue_ver_selector(&options, selector)?
-            .queue(terminal::Clear(terminal::ClearType::FromCursorDown))?
-            .flush()?;
-        let event = event::read()?;
-        if event.updown_driver(&mut selector, options.len() - 1) {
-        } else if event.is_key(KeyCode::Esc) {
-            break;
-        } else if event.is_key(KeyCode::Enter) {
-            let res = match options[selector.unwrap()] {
-                "Exit" => break,
-                "List Tool Openings" => list_tool_openings(&client).await,
-                "List User Bookings" => list_user_bookings(&client).await,
-                "Delete Saved Login" => delete_saved_login(&login_filepath),
-                "List User Projects" => list_user_projects(&client).await,
-                selection => bail!("`{selection}` is not implemented"),
+        // Main menu
+        let has_saved_logins = || match SavedLogins::load(&config_dir) {
+            Ok(logins) => Ok(!logins.is_empty()),
+            Err(err) => display_error_msg(err).map(|_| false),
+        };
+        let mut show_delete_login = has_saved_logins()?;
+        let mut selector = Some(0);
+        loop {
+            let mut options = vec![
+                "List Tool Openings",
+                "Combined Tool Openings",
+                "List User Bookings",
+                "List User Projects",
+            ];
+            if !config.team.members.is_empty() {
+                options.push("Team Schedule");
+            }
+            options.push("Tool History");
+            if show_delete_login {
+                options.push("Delete Saved Login");
+            }
+            options.push("Log Out");
+            options.push("Exit");
+            let first_row = match account {
+                Some(_) => 2,
+                None => 0,
             };
-            if let Err(err) = res {
-                display_error_msg(err)?;
+            stdout().queue(cursor::Hide)?.queue(cursor::MoveTo(0, 0))?;
+            if let Some(account) = &account {
+                stdout()
+                    .queue(style::Print(format!("Logged in as {account}")))?
+                    .queue(terminal::Clear(terminal::ClearType::UntilNewLine))?
+                    .queue(cursor::MoveTo(0, 1))?
+                    .queue(terminal::Clear(terminal::ClearType::CurrentLine))?
+                    .queue(cursor::MoveTo(0, first_row))?;
             }
-        };
+            stdout()
+                .queue_ver_selector(&options, selector)?
+                .queue(terminal::Clear(terminal::ClearType::FromCursorDown))?
+                .flush()?;
+            let Some(event) = read_event(idle_timeout)? else {
+                // Idle for too long, so clear the screen and make the next user log in
+                stdout().execute(terminal::Clear(terminal::ClearType::All))?;
+                log_out(&client, &config_dir, &login).await?;
+                use_saved_login = false;
+                continue 'session;
+            };
+            // Clicking an option chooses it, like moving to it and pressing Enter
+            let clicked = event.clicked_row(first_row).filter(|&row| row < options.len());
+            if clicked.is_some() {
+                selector = clicked;
+            }
+            #[allow(clippy::if_same_then_else)]
+            if event.updown_driver(&mut selector, options.len() - 1) {
+            } else if event.vim_driver(&mut selector, options.len() - 1) {
+            } else if event.is_help() {
+                display_view_help(&[UPDOWN_KEYS], MAIN_MENU_KEYS)?;
+            } else if event.binding(MAIN_MENU_KEYS) == Some(ViewAction::Back) {
+                break 'session;
+            } else if event.binding(MAIN_MENU_KEYS) == Some(ViewAction::Choose) || clicked.is_some()
+            {
+                // Enter without a (valid) selection is a no-op rather than a panic
+                let Some(&option) = selected_option(&options, selector) else { continue };
+                let res = match option {
+                    "Exit" => break 'session,
+                    "Log Out" => {
+                        log_out(&client, &config_dir, &login).await?;
+                        use_saved_login = false;
+                        continue 'session;
+                    }
+                    "List Tool Openings" => list_tool_openings(&client, &config).await,
+                    "Combined Tool Openings" => list_combined_openings(&client, &config).await,
+                    "List User Bookings" => list_user_bookings(&client).await,
+                    "Delete Saved Login" => {
+                        let res = delete_saved_login(&config_dir);
+                        show_delete_login = has_saved_logins()?;
+                        res
+                    }
+                    "List User Projects" => list_user_projects(&client, &config).await,
+                    "Tool History" => list_tool_history(&client, &config).await,
+                    "Team Schedule" => list_team_schedule(&client, &config.team).await,
+                    selection => bail!("`{selection}` is not implemented"),
+                };
+                // Re-authenticating along the way replaces the session cookie
+                save_session(&client, &config_dir, &login).or_else(display_error_msg)?;
+                if let Err(err) = res {
+                    if err.downcast_ref::<QuitRequested>().is_some() {
+                        break 'session;
+                    }
+                    let expired = err.downcast_ref::<SessionExpired>().is_some();
+                    display_error_msg(err)?;
+                    if expired {
+                        // The client already tried logging back in with this login, so ask for a new one
+                        use_saved_login = false;
+                        continue 'session;
+                    }
+                }
+            };
+        }
     }
     Ok(())
 }
 
-async fn list_user_projects(client: &NanoFab) -> Result<()> {
-    let projects = client.get_user_projects().await?;
+async fn list_user_projects(client: &NanoFab, config: &Config) -> Result<()> {
+    let server_order = client.get_user_projects().await?;
+    let mut sort = config.projects.sort;
     loop {
-        stdout().queue(cursor::Hide)?.queue(cursor::MoveTo(0, 0))?;
+        let mut projects = server_order.iter().collect_vec();
+        sort.sort(&mut projects);
+        stdout()
+            .queue(cursor::Hide)?
+            .queue(cursor::MoveTo(0, 0))?
+            .queue(style::Print(format!("Projects sorted by {sort}")))?
+            .queue(terminal::Clear(terminal::ClearType::UntilNewLine))?
+            .queue(cursor::MoveDown(1))?
+            .queue(cursor::MoveToColumn(0))?;
         for project in &projects {
             stdout()
                 .queue(style::Print(format!("{:?}", project)))?
@@ -108,231 +452,731 @@ async fn list_user_projects(client: &NanoFab) -> Result<()> {
         stdout().queue(terminal::Clear(terminal::ClearType::FromCursorDown))?.flush()?;
         let event = event::read()?;
         #[allow(clippy::if_same_then_else)]
-        if event.is_key(KeyCode::Enter) {
-            break;
-        } else if event.is_key(KeyCode::Esc) {
+        if event.resize_driver()? {
+        } else if event.is_help() {
+            display_view_help(&[], PROJECTS_VIEW_KEYS)?;
+        } else if event.binding(PROJECTS_VIEW_KEYS) == Some(ViewAction::Sort) {
+            sort = sort.next();
+        } else if event.binding(PROJECTS_VIEW_KEYS) == Some(ViewAction::Back) {
             break;
         }
     }
     Ok(())
 }
 
-fn delete_saved_login(path: impl AsRef<Path>) -> Result<()> {
-    if user_confirm()? {
-        std::fs::remove_file(path).ok();
+/// Pick a saved profile, unless there is only one, and delete it
+fn delete_saved_login(config_dir: &Path) -> Result<()> {
+    let mut logins = SavedLogins::load(config_dir)?;
+    let name = match logins.startup_choice() {
+        ProfileChoice::Use(name) => name,
+        ProfileChoice::Ask => match user_profile_select(&logins, "Delete which login?", false)? {
+            Some(ProfileChoice::Use(name)) => name,
+            _ => return Ok(()),
+        },
+        ProfileChoice::New => return Ok(()),
+    }
+    .to_string();
+    if user_confirm("Are you sure? ", false)? {
+        if logins.last_used() == Some(name.as_str()) {
+            std::fs::remove_file(config_dir.join(COOKIES_FILENAME)).ok();
+        }
+        logins.remove(&name);
+        logins.save(config_dir)?;
     }
     Ok(())
 }
 
+/// End the session on the server, then delete its cookies and saved profile so nothing
+/// left on this terminal can resume it
+async fn log_out(client: &NanoFab, config_dir: &Path, login: &Login) -> Result<()> {
+    client.logout().await.or_else(display_error_msg)?;
+    forget_session_login(config_dir, login).or_else(display_error_msg)?;
+    std::fs::remove_file(config_dir.join(COOKIES_FILENAME)).ok();
+    Ok(())
+}
+
+/// Delete the saved profile the session logged in with, if it was saved
+fn forget_session_login(config_dir: &Path, login: &Login) -> Result<()> {
+    let mut logins = SavedLogins::load(config_dir)?;
+    if let Some(name) = logins.session_profile(&login.username).map(String::from) {
+        logins.remove(&name);
+        logins.save(config_dir)?;
+    }
+    Ok(())
+}
+
+/// Keep the session cookie for the next run, but only for the saved profile it belongs to
+fn save_session(client: &NanoFab, config_dir: &Path, login: &Login) -> Result<()> {
+    match SavedLogins::load(config_dir)?.session_profile(&login.username) {
+        Some(_) => client.save_cookies(config_dir.join(COOKIES_FILENAME)),
+        None => Ok(()),
+    }
+}
+
 async fn list_user_bookings(client: &NanoFab) -> Result<()> {
-    let bookings = client.get_user_bookings().await?;
-    let mut scroll = Some(0);
-    let buffer = format!("{bookings}");
-    let lines = buffer.lines().collect_vec();
-    let bottom_gap = 0;
-    let mut max_lines = (terminal::size()?.1 as usize).saturating_sub(bottom_gap);
+    let bookings = Spinner::new("Loading bookings").run(client.get_user_bookings()).await?;
+    let mut bookings = bookings.to_local(&LAB_TIMEZONE);
+    // Each hour of a reservation is its own booking with its own id
+    bookings.coalesce_adjacent_by(Booking::same_reservation);
+    let lines = format!("{bookings}").lines().map(String::from).collect();
+    let mut pager = Pager::new("User Bookings", lines).with_keys(USER_BOOKINGS_KEYS);
     loop {
-        stdout().queue(cursor::Hide)?.queue(cursor::MoveTo(0, 0))?;
-        for line in lines.iter().skip(scroll.unwrap()).take(max_lines) {
-            stdout()
-                .queue(style::Print(line))?
-                .queue(terminal::Clear(terminal::ClearType::UntilNewLine))?
-                .queue(cursor::MoveDown(1))?
-                .queue(cursor::MoveToColumn(0))?;
+        match pager.run()? {
+            PagerExit::Back => return Ok(()),
+            PagerExit::Quit => return Err(QuitRequested.into()),
+            PagerExit::Key(event) if event.binding(USER_BOOKINGS_KEYS).is_some() => {
+                user_export(&bookings, "bookings.csv").or_else(display_error_msg)?
+            }
+            PagerExit::Key(_) => {}
         }
-        stdout().queue(terminal::Clear(terminal::ClearType::FromCursorDown))?.flush()?;
+    }
+}
+
+async fn list_team_schedule(client: &NanoFab, team: &Team) -> Result<()> {
+    let palette =
+        [Color::Cyan, Color::Green, Color::Yellow, Color::Magenta, Color::Blue, Color::Red];
+    let bookings =
+        client.get_team_bookings(team, Some(lab_now().date())).await?.to_local(&LAB_TIMEZONE);
+    let mut lines = bookings
+        .labeled_lines(|booking| format!("{} ({})", booking.user_name, booking.tool_label))
+        .into_iter()
+        .map(|(line, meta)| {
+            let color = meta
+                .and_then(|booking| team.member_index(&booking.user_email))
+                .map(|i| palette[i % palette.len()]);
+            (line, color)
+        })
+        .collect_vec();
+    if lines.is_empty() {
+        lines.push(("No team bookings".to_string(), None));
+    }
+    run_pager(Pager::colored("Team Schedule", lines))
+}
+
+async fn list_tool_history(client: &NanoFab, config: &Config) -> Result<()> {
+    let Some(tool) = user_tool_select(client).await? else {
+        return Ok(());
+    };
+    let today = lab_now().date();
+    let start = today.checked_sub_days(chrono::Days::new(config.history.days));
+    let bookings = Spinner::new("Loading history")
+        .run(client.get_tool_bookings(&tool, start, Some(today)))
+        .await?;
+    let window =
+        TimeSlot::new(start.and_then(|date| date.and_hms_opt(0, 0, 0)), Some(lab_now()), ());
+    let utilization = bookings.utilization(&window);
+    let bookings = bookings.to_local(&LAB_TIMEZONE);
+    // Rows continuing the previous row's reservation are dimmed so each reservation reads as one
+    let mut prev_booking = None;
+    let mut lines = bookings
+        .labeled_lines(|booking| format!("{} <{}>", booking.user_name, booking.user_email))
+        .into_iter()
+        .map(|(line, booking)| {
+            let Some(booking) = booking else {
+                return (line, None);
+            };
+            let continues = prev_booking.is_some_and(|prev: &Booking| prev.same_group(booking));
+            prev_booking = Some(booking);
+            match continues {
+                true => (format!("{line} (cont.)"), Some(Color::DarkGrey)),
+                false => (line, None),
+            }
+        })
+        .collect_vec();
+    if lines.is_empty() {
+        lines.push(("No bookings".to_string(), None));
+    }
+    let title = format!(
+        "History for `{}` over the last {} days ({:.0}% booked)",
+        tool.label,
+        config.history.days,
+        utilization * 100.0
+    );
+    run_pager(Pager::colored(title, lines))
+}
+
+/// Show `pager` until the user leaves it, with `q` quitting the program
+fn run_pager(mut pager: Pager) -> Result<()> {
+    loop {
+        match pager.run()? {
+            PagerExit::Back => return Ok(()),
+            PagerExit::Quit => return Err(QuitRequested.into()),
+            PagerExit::Key(_) => {}
+        }
+    }
+}
+
+/// The usable openings around `bookings`, shared by the TUI and `nanofab-cli openings`
+fn filter_openings<M>(mut bookings: TimeTable<M>, settings: &config::Openings) -> TimeTable<()> {
+    // Overlapping bookings from the server would otherwise throw off `inverted`
+    bookings.merge_overlaps(|first, _| first);
+    let mut openings = bookings.inverted();
+    openings.subtract_before(lab_now());
+    openings.subtract_weekends(settings.closed_days.as_deref().unwrap_or(&schedule::WEEKEND));
+    openings.subtract_holidays(&settings.holidays);
+    match settings.hours {
+        Some((open, close)) => openings.subtract_after_hours(open, close),
+        None => openings.subtract_default_after_hours(),
+    }
+    trim_openings(&mut openings, settings);
+    openings
+}
+
+/// Trim the lead and trail time off `openings`, clip them to the earliest start and drop
+/// the short ones. Clipping after trimming keeps `earliest_start` the time a booking can
+/// start, rather than that time plus the lead.
+fn trim_openings(openings: &mut TimeTable<()>, settings: &config::Openings) {
+    openings.shrink_slots(
+        Duration::minutes(settings.lead_minutes),
+        Duration::minutes(settings.trail_minutes),
+    );
+    if let Some(time) = settings.earliest_start {
+        openings.subtract_before_time_of_day(time);
+    }
+    openings.subtract_less_duration(Duration::minutes(settings.min_minutes));
+}
+
+/// The times when every one of a few tools is free, for work that needs them all
+async fn list_combined_openings(client: &NanoFab, config: &Config) -> Result<()> {
+    let Some(tools) = user_tools_select(client).await? else {
+        return Ok(());
+    };
+    let bookings = Spinner::new("Loading bookings")
+        .run(client.get_tools_bookings(&tools, Some(lab_now().date()), None))
+        .await?;
+    let mut openings = filter_openings(bookings, &config.openings);
+    let now = lab_now();
+    openings.clip_to(now, now + Duration::days(OPENINGS_VIEW_DAYS));
+    let lines = format!("{}", openings.to_local(&LAB_TIMEZONE)).lines().map(String::from).collect();
+    let labels = tools.iter().map(|tool| format!("`{}`", tool.label)).join(", ");
+    run_pager(Pager::new(format!("Openings when all of {labels} are free"), lines))
+}
+
+async fn list_tool_openings(client: &NanoFab, config: &Config) -> Result<()> {
+    let Some(tool) = user_tool_select(client).await? else {
+        return Ok(());
+    };
+    let today = lab_now().date();
+    let default_end = today + Duration::days(OPENINGS_VIEW_DAYS);
+    let Some((start_date, end_date)) = user_date_range(today, default_end)? else {
+        return Ok(());
+    };
+    let (bookings, blocker) = Spinner::new("Loading bookings")
+        .run(async {
+            let (bookings, blocker) = tokio::join!(
+                client.get_tool_bookings(&tool, Some(start_date), Some(end_date)),
+                client.booking_blocker()
+            );
+            // Not specific to the tool and only a warning over the openings, so the view
+            // goes without it if the check fails
+            Ok((bookings?, blocker.ok().flatten()))
+        })
+        .await?;
+    let mut settings = config.openings.clone();
+    // Bounded so the view always has an end to scroll to
+    let range_start = start_date.and_time(NaiveTime::MIN);
+    let range_end = (end_date + Duration::days(1)).and_time(NaiveTime::MIN);
+    let bounded_openings = |settings: &config::Openings| {
+        let mut openings = filter_openings(bookings.clone(), settings);
+        openings.clip_to(lab_now().max(range_start), range_end);
+        openings
+    };
+    let mut openings = bounded_openings(&settings);
+    let caps = [None, Some(10), Some(25)];
+    let mut cap_index = 0;
+
+    let mut pager = Pager::new("", vec![]).with_keys(OPENINGS_VIEW_KEYS);
+    loop {
+        let cap = caps[cap_index];
+        let mut shown = openings.clone().to_local(&LAB_TIMEZONE);
+        let hidden = cap.map_or(0, |cap| shown.truncate(cap));
+        let mut lines = format!("{shown}").lines().map(String::from).collect_vec();
+        if hidden > 0 {
+            lines.push(format!("…and {hidden} more"));
+        }
+        let mut title = format!(
+            "Openings for `{}`, {} to {}",
+            tool.label,
+            start_date.format("%b %-d"),
+            end_date.format("%b %-d")
+        );
+        if let Some(time) = settings.earliest_start {
+            title += &format!(" from {}", time.format("%-I:%M%P"));
+        }
+        if settings.min_minutes > 0 {
+            let min = schedule::hours_minutes(Duration::minutes(settings.min_minutes));
+            title += &format!(", at least {min}");
+        }
+        if let Some(cap) = cap {
+            title += &format!(" (first {cap})");
+        }
+        if let Some(reason) = &blocker {
+            title += &format!(" - can't book, {reason}");
+        }
+        pager.set_title(title);
+        pager.set_lines(lines);
+        // The pager's search takes `n` over from the openings cap while there is a query
+        let event = match pager.run()? {
+            PagerExit::Back => break,
+            PagerExit::Quit => return Err(QuitRequested.into()),
+            PagerExit::Key(event) => event,
+        };
+        match event.binding(OPENINGS_VIEW_KEYS) {
+            Some(OpeningsAction::Cap) => {
+                cap_index = (cap_index + 1) % caps.len();
+                pager.scroll_to(0);
+            }
+            Some(OpeningsAction::EarliestStart) => {
+                match user_time_input(
+                    "Earliest start (HH:MM, empty for none): ",
+                    settings.earliest_start,
+                ) {
+                    Ok(time) => {
+                        settings.earliest_start = time;
+                        openings = bounded_openings(&settings);
+                        pager.scroll_to(0);
+                    }
+                    Err(err) => display_error_msg(err)?,
+                }
+            }
+            Some(OpeningsAction::MinLength) => {
+                match user_duration_input(
+                    "Shortest opening (like 1:30 or 45m, empty for any): ",
+                    settings.min_minutes,
+                ) {
+                    Ok(minutes) => {
+                        settings.min_minutes = minutes;
+                        openings = bounded_openings(&settings);
+                        pager.scroll_to(0);
+                    }
+                    Err(err) => display_error_msg(err)?,
+                }
+            }
+            Some(OpeningsAction::Export) => {
+                user_export(&shown, "openings.csv").or_else(display_error_msg)?;
+            }
+            Some(OpeningsAction::LongOpening) => {
+                let long = Duration::hours(LONG_OPENING_HOURS);
+                let local = openings.clone().to_local(&LAB_TIMEZONE);
+                match local.first_opening_at_least(long) {
+                    Some(slot) => {
+                        // The opening may be past the cap, so show everything before jumping
+                        cap_index = 0;
+                        pager.set_lines(format!("{local}").lines().map(String::from).collect());
+                        pager.scroll_to(opening_line(&local, slot));
+                    }
+                    None => display_error_msg(anyhow!(
+                        "No openings of at least {LONG_OPENING_HOURS} hours"
+                    ))?,
+                }
+            }
+            None => {}
+        }
+    }
+    Ok(())
+}
+
+/// The line of `format!("{openings}")` that `slot` starts on
+fn opening_line(openings: &TimeTable<()>, slot: &TimeSlot<()>) -> usize {
+    if slot.start().is_none() {
+        return 0;
+    }
+    // Everything printed before `slot` starts is the same as for the table cut off there
+    let before = openings.iter().take_while(|ts| ts.start() < slot.start());
+    let cut = TimeTable::new(before.cloned().chain([TimeSlot::new(*slot.start(), None, ())]));
+    format!("{cut}").lines().count().saturating_sub(1)
+}
+
+/// Ask for a `HH:MM` time on the bottom line. Empty input clears it and Esc keeps `current`.
+fn user_time_input(prompt: &str, current: Option<NaiveTime>) -> Result<Option<NaiveTime>> {
+    let initial = current.map(|time| time.format("%H:%M").to_string()).unwrap_or_default();
+    let Some(input) = user_text_input(prompt, &initial)? else {
+        return Ok(current);
+    };
+    let input = input.trim();
+    if input.is_empty() {
+        return Ok(None);
+    }
+    NaiveTime::parse_from_str(input, "%H:%M")
+        .map(Some)
+        .map_err(|_| anyhow!("`{input}` is not a time like 07:30"))
+}
+
+/// Ask for a length of time in minutes on the bottom line. Empty input clears it to `0` and
+/// Esc keeps `current`.
+fn user_duration_input(prompt: &str, current: i64) -> Result<i64> {
+    let initial = match current {
+        0 => String::new(),
+        minutes => format!("{}:{:02}", minutes / 60, minutes % 60),
+    };
+    let Some(input) = user_text_input(prompt, &initial)? else {
+        return Ok(current);
+    };
+    if input.trim().is_empty() {
+        return Ok(0);
+    }
+    schedule::parse_hours_minutes(&input)
+        .map(|duration| duration.num_minutes())
+        .ok_or_else(|| anyhow!("`{}` is not a length of time like 1:30", input.trim()))
+}
+
+/// Ask for a line of text on the bottom line, starting from `initial`. `None` on Esc.
+fn user_text_input(prompt: &str, initial: &str) -> Result<Option<String>> {
+    let mut input = TextInput::new(initial);
+    loop {
+        let row = terminal::size()?.1.saturating_sub(1);
+        stdout()
+            .queue(cursor::Show)?
+            .queue(cursor::MoveTo(0, row))?
+            .queue(style::Print(prompt))?
+            .queue(style::Print(input.as_str()))?
+            .queue(terminal::Clear(terminal::ClearType::UntilNewLine))?
+            .queue(cursor::MoveToColumn((prompt.chars().count() + input.cursor()) as u16))?
+            .flush()?;
         let event = event::read()?;
         #[allow(clippy::if_same_then_else)]
-        if event.updown_driver(&mut scroll, lines.len().saturating_sub(max_lines)) {
-        } else if event.scroll_driver(&mut scroll, lines.len().saturating_sub(max_lines)) {
-        } else if event.is_key(KeyCode::Enter) {
-            break;
+        if event.string_driver(&mut input) {
+        } else if event.resize_driver()? {
         } else if event.is_key(KeyCode::Esc) {
-            break;
-        } else if let Some((_, rows)) = event.is_resize() {
-            max_lines = (rows as usize).saturating_sub(bottom_gap);
+            return Ok(None);
+        } else if event.is_key(KeyCode::Enter) {
+            return Ok(Some(input.into_string()));
         }
     }
-    Ok(())
 }
 
-async fn list_tool_openings(client: &NanoFab) -> Result<()> {
-    let Some(tool) = user_tool_select(client).await?else{
+/// Ask for the first and last days to look at, starting from `start` and `end`. `None` on Esc.
+fn user_date_range(start: NaiveDate, end: NaiveDate) -> Result<Option<(NaiveDate, NaiveDate)>> {
+    let mut input = DateRangeInput::new(start, end);
+    let labels = ["From: ", "  To: "];
+    loop {
+        let mut column = 0;
+        stdout().queue(cursor::Show)?.queue(cursor::MoveTo(0, 0))?;
+        for (i, (label, field)) in labels.iter().zip(input.fields()).enumerate() {
+            if i == input.focus() {
+                column += label.len() + field.cursor();
+            } else if i < input.focus() {
+                column += label.len() + field.as_str().chars().count();
+            }
+            stdout()
+                .queue(style::Print(label))?
+                .queue_maybe_highlighted(field.as_str(), i == input.focus())?;
+        }
+        stdout()
+            .queue(terminal::Clear(terminal::ClearType::FromCursorDown))?
+            .queue(cursor::MoveToColumn(column as u16))?
+            .flush()?;
+        let event = event::read()?;
+        #[allow(clippy::if_same_then_else)]
+        if event.resize_driver()? {
+        } else if event.is_help() {
+            display_view_help(DATE_RANGE_KEYS, DATE_RANGE_VIEW_KEYS)?;
+        } else if input.driver(&event) {
+        } else if event.binding(DATE_RANGE_VIEW_KEYS) == Some(ViewAction::Back) {
+            return Ok(None);
+        } else if event.binding(DATE_RANGE_VIEW_KEYS) == Some(ViewAction::Choose) {
+            match input.range() {
+                Ok(range) => return Ok(Some(range)),
+                Err(err) => display_error_msg(err)?,
+            }
+        }
+    }
+}
+
+/// Ask for a file and write `table` to it as CSV or JSON, going by its extension
+fn user_export<M: ExportMeta>(table: &TimeTable<M>, default_path: &str) -> Result<()> {
+    let Some(path) = user_text_input("Export to (.csv or .json): ", default_path)? else {
         return Ok(());
     };
-    let bookings =
-        client.get_tool_bookings(&tool, Some(chrono::Local::now().date_naive()), None).await?;
-    let mut openings = bookings.inverted();
-    openings.subtract_before_now();
-    openings.subtract_weekends();
-    openings.subtract_after_hours();
-
-    let mut scroll = Some(0);
-    let buffer = format!("{openings}");
-    let lines = buffer.lines().collect_vec();
-    let bottom_gap = 1;
-    let mut max_lines = (terminal::size()?.1 as usize).saturating_sub(bottom_gap);
+    let path = Path::new(path.trim());
+    let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or_default();
+    let create = || std::fs::File::create(path).map(std::io::BufWriter::new);
+    let overwrite = format!("`{}` already exists, overwrite it? ", path.display());
+    if path.exists() && !user_confirm(&overwrite, false)? {
+        return Ok(());
+    }
+    match extension.to_lowercase().as_str() {
+        "csv" => schedule::export_csv(table, create()?)?,
+        "json" => schedule::export_json(table, create()?)?,
+        _ => bail!("`{}` does not end in .csv or .json", path.display()),
+    }
+    Ok(())
+}
+
+/// Ask `prompt` with a Yes/No choice, starting on Yes if `default` is true
+fn user_confirm(prompt: &str, default: bool) -> Result<bool> {
+    let mut selector = match default {
+        true => Some(0),
+        false => Some(1),
+    };
     loop {
         stdout()
             .queue(cursor::Hide)?
             .queue(cursor::MoveTo(0, 0))?
-            .queue(style::Print(format!("Openings for `{}`", tool.label)))?;
-        for line in lines.iter().skip(scroll.unwrap()).take(max_lines) {
-            stdout()
-                .queue(cursor::MoveDown(1))?
-                .queue(cursor::MoveToColumn(0))?
-                .queue(style::Print(line))?
-                .queue(terminal::Clear(terminal::ClearType::UntilNewLine))?;
-        }
-        stdout().queue(terminal::Clear(terminal::ClearType::FromCursorDown))?.flush()?;
+            .queue(style::Print(prompt))?
+            .queue_hor_selector(&["[Yes]", "[No]"], selector)?
+            .queue(terminal::Clear(terminal::ClearType::FromCursorDown))?
+            .flush()?;
         let event = event::read()?;
         #[allow(clippy::if_same_then_else)]
-        if event.updown_driver(&mut scroll, lines.len().saturating_sub(max_lines)) {
-        } else if event.scroll_driver(&mut scroll, lines.len().saturating_sub(max_lines)) {
-        } else if event.is_key(KeyCode::Enter) {
-            break;
-        } else if event.is_key(KeyCode::Esc) {
-            break;
-        } else if let Some((_, rows)) = event.is_resize() {
-            max_lines = (rows as usize).saturating_sub(bottom_gap);
+        if event.resize_driver()? {
+        } else if event.is_help() {
+            display_view_help::<()>(CONFIRM_KEYS, &[])?;
+        } else if let Some(answer) = event.confirm_driver(&mut selector) {
+            return Ok(answer);
         }
     }
-    Ok(())
 }
 
-fn user_confirm() -> Result<bool> {
-    let mut selector = Some(1);
+/// Save `login` as the profile `name`, or under its username if `name` is empty
+fn save_profile(config_dir: &Path, name: &str, login: &Login) -> Result<()> {
+    let key = LoginKey::from_keyring()?;
+    let mut logins = SavedLogins::load(config_dir)?;
+    match name {
+        "" => logins.insert(login.username.clone(), login, &key),
+        name => logins.insert(name, login, &key),
+    }
+    logins.save(config_dir)
+}
+
+/// Pick one of the saved profiles, or with `offer_new` a new login. `None` on Esc.
+fn user_profile_select<'a>(
+    logins: &'a SavedLogins,
+    title: &str,
+    offer_new: bool,
+) -> Result<Option<ProfileChoice<'a>>> {
+    let names = logins.names();
+    let mut options = names.clone();
+    if offer_new {
+        options.push("New Login");
+    }
+    let mut selector = Some(logins.picker_start());
+    let first_row = 2;
     loop {
         stdout()
             .queue(cursor::Hide)?
             .queue(cursor::MoveTo(0, 0))?
-            .queue(style::Print("Are you sure? "))?
-            .queue_hor_selector(&["[Yes]", "[No]"], selector)?
+            .queue(style::Print(title))?
+            .queue(terminal::Clear(terminal::ClearType::UntilNewLine))?
+            .queue(cursor::MoveTo(0, 1))?
+            .queue(terminal::Clear(terminal::ClearType::CurrentLine))?
+            .queue(cursor::MoveTo(0, first_row))?
+            .queue_ver_selector(&options, selector)?
             .queue(terminal::Clear(terminal::ClearType::FromCursorDown))?
             .flush()?;
         let event = event::read()?;
-        if event.leftright_driver(&mut selector, 1) {
-        } else if event.is_key(KeyCode::Enter) {
-            break;
+        let clicked = event.clicked_row(first_row).filter(|&row| row < options.len());
+        if clicked.is_some() {
+            selector = clicked;
+        }
+        #[allow(clippy::if_same_then_else)]
+        if event.updown_driver(&mut selector, options.len() - 1) {
+        } else if event.vim_driver(&mut selector, options.len() - 1) {
+        } else if event.resize_driver()? {
+        } else if event.is_help() {
+            display_view_help(&[UPDOWN_KEYS], PROFILE_SELECT_KEYS)?;
+        } else if event.binding(PROFILE_SELECT_KEYS) == Some(ViewAction::Back) {
+            return Ok(None);
+        } else if event.binding(PROFILE_SELECT_KEYS) == Some(ViewAction::Choose)
+            || clicked.is_some()
+        {
+            let Some(i) = selector else { continue };
+            return Ok(Some(match names.get(i) {
+                Some(name) => ProfileChoice::Use(name),
+                None => ProfileChoice::New,
+            }));
         }
     }
-    Ok(selector.unwrap() == 0)
 }
 
-async fn user_login(client: &NanoFab) -> Result<Option<Login>> {
-    let mut login_filepath = dirs::home_dir().unwrap();
-    login_filepath.push(CONFIG_DIR);
-    login_filepath.push(LOGIN_FILENAME);
-    if let Ok(login_raw) = std::fs::read_to_string(&login_filepath) {
-        let login = ron::from_str::<Login>(&login_raw)?;
-        client.authenticate(&login).await?;
-        return Ok(Some(login));
+async fn user_login(client: &NanoFab, config_dir: &Path, use_saved: bool) -> Result<Option<Login>> {
+    if use_saved {
+        let logins = SavedLogins::load(config_dir)?;
+        let choice = match logins.startup_choice() {
+            ProfileChoice::Ask => match user_profile_select(&logins, "Log in as", true)? {
+                Some(choice) => choice,
+                None => return Ok(None),
+            },
+            choice => choice,
+        };
+        if let ProfileChoice::Use(name) = choice {
+            return saved_login(client, config_dir, name).await.map(Some);
+        }
     }
-    let mut username = String::new();
+    let mut username = TextInput::default();
     loop {
         stdout()
             .queue(cursor::Show)?
             .queue(cursor::MoveTo(0, 0))?
-            .queue(style::Print("Enter username: "))?
-            .queue(style::Print(&username))?
+            .queue(style::Print(USERNAME_PROMPT))?
+            .queue(style::Print(username.as_str()))?
             .queue(terminal::Clear(terminal::ClearType::FromCursorDown))?
+            .queue(cursor::MoveToColumn((USERNAME_PROMPT.len() + username.cursor()) as u16))?
             .flush()?;
         let event = event::read()?;
+        #[allow(clippy::if_same_then_else)]
         if event.string_driver(&mut username) {
+        } else if event.resize_driver()? {
         } else if event.is_key(KeyCode::Esc) {
             return Ok(None);
         } else if event.is_key(KeyCode::Enter) {
             break;
         }
     }
-    let mut password = String::new();
+    let username = username.into_string();
+    let mut password = TextInput::default();
     loop {
-        let stars = (0..password.len()).map(|_| '*').collect::<String>();
+        let stars = password.as_str().chars().map(|_| '*').collect::<String>();
         stdout()
+            .queue(cursor::MoveTo(0, 0))?
+            .queue(style::Print(USERNAME_PROMPT))?
+            .queue(style::Print(&username))?
+            .queue(terminal::Clear(terminal::ClearType::UntilNewLine))?
             .queue(cursor::MoveTo(0, 1))?
-            .queue(style::Print("Enter password: "))?
+            .queue(style::Print(PASSWORD_PROMPT))?
             .queue(style::Print(stars))?
             .queue(terminal::Clear(terminal::ClearType::FromCursorDown))?
+            .queue(cursor::MoveToColumn((PASSWORD_PROMPT.len() + password.cursor()) as u16))?
             .flush()?;
         let event = event::read()?;
+        #[allow(clippy::if_same_then_else)]
         if event.string_driver(&mut password) {
+        } else if event.resize_driver()? {
         } else if event.is_key(KeyCode::Esc) {
             return Ok(None);
         } else if event.is_key(KeyCode::Enter) {
             break;
         }
     }
-    let login = Login { username, password };
+    let login = Login { username, password: password.into_string() };
     client.authenticate(&login).await?;
+    let stars = (0..login.password.len()).map(|_| '*').collect::<String>();
     let mut save_login = Some(1);
     loop {
         stdout()
             .queue(cursor::Hide)?
+            .queue(cursor::MoveTo(0, 0))?
+            .queue(style::Print(USERNAME_PROMPT))?
+            .queue(style::Print(&login.username))?
+            .queue(terminal::Clear(terminal::ClearType::UntilNewLine))?
+            .queue(cursor::MoveTo(0, 1))?
+            .queue(style::Print(PASSWORD_PROMPT))?
+            .queue(style::Print(&stars))?
+            .queue(terminal::Clear(terminal::ClearType::UntilNewLine))?
             .queue(cursor::MoveTo(0, 2))?
             .queue(style::Print("Save login? "))?
             .queue_hor_selector(&["[Yes]", "[No]"], save_login)?
             .queue(terminal::Clear(terminal::ClearType::FromCursorDown))?
             .flush()?;
         let event = event::read()?;
+        #[allow(clippy::if_same_then_else)]
         if event.leftright_driver(&mut save_login, 1) {
+        } else if event.resize_driver()? {
         } else if event.is_key(KeyCode::Enter) {
             break;
         }
     }
     if save_login == Some(0) {
-        std::fs::write(login_filepath, ron::to_string(&login)?)?;
+        if let Some(name) = user_text_input("Save as: ", &login.username)? {
+            // Failing to save still leaves the user logged in
+            save_profile(config_dir, name.trim(), &login).or_else(display_error_msg)?;
+        }
     }
     Ok(Some(login))
 }
 
 async fn user_tool_select(client: &NanoFab) -> Result<Option<Tool>> {
+    let tools = tool_picker(client, false).await?;
+    Ok(tools.and_then(|tools| tools.into_iter().next()))
+}
+
+async fn user_tools_select(client: &NanoFab) -> Result<Option<Vec<Tool>>> {
+    tool_picker(client, true).await
+}
+
+/// Search for a tool, or with `multi`, check any number of them with Space or a click
+async fn tool_picker(client: &NanoFab, multi: bool) -> Result<Option<Vec<Tool>>> {
     let bottom_gap = 2;
     let mut max_tools = (terminal::size()?.1 as usize).saturating_sub(bottom_gap);
-    let all_tools = client.get_tools().await?;
-    let mut search_str = String::new();
+    let all_tools = client.get_tools(false).await?;
+    let mut search = TextInput::default();
     let mut selection = None;
     let mut displayed_tools = all_tools.iter().take(max_tools).collect_vec();
+    let mut toggled = ToggleSet::default();
+    let title = match multi {
+        true => "Search for tools, Space to check, Enter when done:",
+        false => "Search for tool:",
+    };
 
     loop {
         let tool_names = displayed_tools.iter().map(|tool| tool.label.as_str()).collect_vec();
         stdout()
             .queue(cursor::Show)?
             .queue(cursor::MoveTo(0, 0))?
-            .queue(style::Print("Search for tool:"))?
+            .queue(style::Print(title))?
             .queue(terminal::Clear(terminal::ClearType::UntilNewLine))?
             .queue(cursor::MoveDown(1))?
             .queue(cursor::MoveToColumn(0))?
-            .queue(style::Print(&search_str))?
+            .queue(style::Print(search.as_str()))?
             .queue(terminal::Clear(terminal::ClearType::UntilNewLine))?
+            .queue(cursor::MoveToColumn(search.cursor() as u16))?
             .queue(cursor::SavePosition)?
             .queue(cursor::MoveDown(1))?
-            .queue(cursor::MoveToColumn(0))?
-            .queue_ver_selector(&tool_names, selection)?
+            .queue(cursor::MoveToColumn(0))?;
+        if displayed_tools.is_empty() {
+            stdout().queue(style::Print(format!("No tools match '{}'", search.as_str())))?;
+        } else if multi {
+            let checked =
+                displayed_tools.iter().map(|tool| toggled.contains(&tool.id)).collect_vec();
+            stdout().queue_ver_checklist(&tool_names, &checked, selection)?;
+        } else {
+            stdout().queue_ver_selector(&tool_names, selection)?;
+        }
+        stdout()
             .queue(terminal::Clear(terminal::ClearType::FromCursorDown))?
             .queue(cursor::RestorePosition)?
             .flush()?;
         let event = event::read()?;
+        // The list starts below the title and the search line
+        let clicked = event.clicked_row(2).filter(|&row| row < displayed_tools.len());
+        if clicked.is_some() {
+            selection = clicked;
+        }
         #[allow(clippy::if_same_then_else)]
-        if event.string_driver(&mut search_str) {
+        // j, k and Space only act on the selection once it is engaged with Down, and typing
+        // anything else drops it, so they can still be typed into the search
+        if selection.is_some()
+            && event.vim_driver(&mut selection, displayed_tools.len().saturating_sub(1))
+        {
+        } else if multi
+            && (clicked.is_some() || selection.is_some() && event.is_char() == Some(' '))
+        {
+            if let Some(tool) = selected_option(&displayed_tools, selection) {
+                toggled.toggle(tool.id.clone());
+            }
+        } else if event.string_driver(&mut search) {
             selection = None;
-            displayed_tools = all_tools
-                .iter()
-                .filter(|tool| tool.label.to_lowercase().contains(&search_str.to_lowercase()))
-                .take(max_tools)
-                .collect();
+            displayed_tools =
+                search_tools(&all_tools, search.as_str()).into_iter().take(max_tools).collect();
         } else if event.updown_driver(&mut selection, displayed_tools.len().saturating_sub(1)) {
         } else if event.scroll_driver(&mut selection, displayed_tools.len().saturating_sub(1)) {
         } else if event.is_key(KeyCode::Esc) {
             return Ok(None);
-        } else if event.is_key(KeyCode::Enter) & selection.is_some() {
-            return Ok(Some(displayed_tools[selection.unwrap()].clone()));
-        } else if let Some((_, rows)) = event.is_resize() {
-            max_tools = (rows as usize).saturating_sub(bottom_gap);
-            displayed_tools = all_tools
+        } else if event.is_key(KeyCode::Enter) && multi && !toggled.is_empty() {
+            let tools = toggled
+                .keys()
                 .iter()
-                .filter(|tool| tool.label.to_lowercase().contains(&search_str.to_lowercase()))
-                .take(max_tools)
+                .filter_map(|id| all_tools.iter().find(|tool| tool.id == *id).cloned())
                 .collect();
+            return Ok(Some(tools));
+        } else if event.is_key(KeyCode::Enter) || clicked.is_some() {
+            if let Some(tool) = selected_option(&displayed_tools, selection) {
+                return Ok(Some(vec![(*tool).clone()]));
+            }
+        } else if let Some((_, rows)) = event.is_resize() {
+            max_tools = rows.saturating_sub(bottom_gap);
+            displayed_tools =
+                search_tools(&all_tools, search.as_str()).into_iter().take(max_tools).collect();
             if let Some(s) = selection.as_mut() {
                 *s = (*s).min(displayed_tools.len().saturating_sub(1))
             }
@@ -1,103 +1,301 @@
 use crate::{
-    html::{Content, Element, ElementIter},
-    schedule::{TimeSlot, TimeTable},
+    config::Team,
+    debug_log, fuzzy,
+    html::{self, Content, Element, ElementIter},
+    schedule::{ExportMeta, RelTime, TimeSlot, TimeTable},
+    transport::{HttpTransport, ReqwestTransport},
 };
 
-use anyhow::{anyhow, Context, Result};
-use chrono::{format::ParseErrorKind, NaiveDate, NaiveDateTime};
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{format::ParseErrorKind, Datelike, Days, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use chrono_tz::Tz;
+use futures_util::{stream, StreamExt, TryStreamExt};
 use itertools::Itertools;
-use reqwest::Client;
+use reqwest::{cookie::CookieStore, Url};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    borrow::Borrow,
+    collections::HashSet,
+    fmt::Display,
+    path::Path,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
 use urlencoding::encode;
 
-pub struct NanoFab {
-    client: Client,
+/// Where `NanoFab::new` sends requests, unless `with_base_url` points it elsewhere
+const DEFAULT_BASE_URL: &str = "https://admin.nanofab.ualberta.ca";
+const LOGIN_PATH: &str = "ajax.login.php";
+const LOGOUT_PATH: &str = "logout.php";
+/// Timezone the NanoFab site writes its times in. Tables from the client hold wall-clock
+/// times in this zone, so use `TimeTable::to_local` before showing them.
+pub const LAB_TIMEZONE: Tz = chrono_tz::America::Edmonton;
+/// Modal with the logged in user's profile form. The name and its field names are guessed
+/// from the other modals, as no response from it has been captured yet.
+const ACCOUNT_MODAL: &str = "modal.user.profile.php";
+/// This and `CANCEL_BOOKING_PATH` are guessed from the other endpoints' names, so booking
+/// and cancelling stay behind the `booking` feature until real requests confirm them
+#[cfg(any(test, feature = "booking"))]
+const BOOK_TOOL_PATH: &str = "ajax.book-tool.php";
+#[cfg(any(test, feature = "booking"))]
+const CANCEL_BOOKING_PATH: &str = "ajax.cancel-booking.php";
+/// Most days of bookings asked for in one request
+const BOOKING_CHUNK_DAYS: u64 = 14;
+/// How long `get_tools` answers from its cache, unless `with_tool_cache_ttl` says otherwise
+const DEFAULT_TOOL_CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+/// Most user bookings looked up at once
+const USER_BOOKING_LOOKUPS: usize = 8;
+/// Fuzzy match confidence needed to stand in for an exact tool label
+const AUTO_SELECT_CONFIDENCE: f64 = 0.9;
+/// Fuzzy match confidence needed to be suggested when a tool label doesn't match
+const SUGGEST_CONFIDENCE: f64 = 0.5;
+
+pub struct NanoFab<H: HttpTransport = ReqwestTransport> {
+    transport: H,
+    base_url: String,
+    login: Mutex<Option<Login>>,
+    tool_cache: Mutex<Option<ToolCache>>,
+    tool_cache_ttl: Duration,
+}
+impl Default for NanoFab {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 impl NanoFab {
     pub fn new() -> Self {
+        Self::with_transport(ReqwestTransport::new())
+    }
+    /// Resend requests that time out, can't connect or get a 502, 503 or 504 up to
+    /// `retries` times, waiting `base_delay` and then twice as long after each failure
+    pub fn with_retries(mut self, retries: u32, base_delay: Duration) -> Self {
+        self.transport.retries = retries;
+        self.transport.retry_delay = base_delay;
+        self
+    }
+    /// Write the cookies for the base URL to `path`, one `name=value` per line, so a
+    /// later run can pick the session back up with `load_cookies`
+    pub fn save_cookies(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let cookies = match self.transport.cookies.cookies(&self.parsed_base_url()?) {
+            Some(header) => header.to_str().context("Cookie was not valid text")?.to_string(),
+            None => String::new(),
+        };
+        let lines = cookies.split("; ").filter(|cookie| !cookie.is_empty()).join("\n");
+        write_private(path, &lines).with_context(|| format!("Failed to write `{}`", path.display()))
+    }
+    /// Add the cookies `save_cookies` wrote to `path`, returning whether there were any
+    ///
+    /// Pair this with `resume` rather than `authenticate`, so the password is only sent
+    /// if the server has dropped the session.
+    pub fn load_cookies(&self, path: impl AsRef<Path>) -> Result<bool> {
+        let path = path.as_ref();
+        let Ok(raw) = std::fs::read_to_string(path) else { return Ok(false) };
+        let url = self.parsed_base_url()?;
+        let mut loaded = false;
+        for cookie in raw.lines().map(str::trim).filter(|cookie| cookie.contains('=')) {
+            self.transport.cookies.add_cookie_str(cookie, &url);
+            loaded = true;
+        }
+        Ok(loaded)
+    }
+    fn parsed_base_url(&self) -> Result<Url> {
+        Url::parse(&self.base_url).with_context(|| format!("`{}` is not a URL", self.base_url))
+    }
+}
+impl<H: HttpTransport> NanoFab<H> {
+    /// A client that sends its requests through `transport`
+    pub fn with_transport(transport: H) -> Self {
         Self {
-            client: reqwest::ClientBuilder::new()
-                .cookie_store(true)
-                .build()
-                .expect("Creating the client should not fail"),
+            transport,
+            base_url: DEFAULT_BASE_URL.to_string(),
+            login: Mutex::new(None),
+            tool_cache: Mutex::new(None),
+            tool_cache_ttl: DEFAULT_TOOL_CACHE_TTL,
         }
     }
+    /// Send requests to another server running the same booking software, like a mock
+    /// server in tests, given its root like `http://127.0.0.1:8080`
+    pub fn with_base_url(mut self, url: impl Into<String>) -> Self {
+        self.base_url = url.into().trim_end_matches('/').to_string();
+        self
+    }
+    fn url(&self, path: &str) -> String {
+        format!("{}/{path}", self.base_url)
+    }
+    /// Keep `login` to re-authenticate with when the server asks, without logging in now
+    pub fn resume(&self, login: &Login) {
+        *self.login.lock().unwrap() = Some(login.clone());
+    }
+    /// Keep the tool list from `get_tools` for `ttl` before fetching it again, or
+    /// not at all if `ttl` is zero
+    pub fn with_tool_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.tool_cache_ttl = ttl;
+        self
+    }
+    /// Make the next `get_tools` fetch the tool list, even if the cached one is fresh
+    pub fn clear_tool_cache(&self) {
+        *self.tool_cache.lock().unwrap() = None;
+    }
+    fn cached_tools(&self, include_inactive: bool) -> Option<Vec<Tool>> {
+        let cache = self.tool_cache.lock().unwrap();
+        let cache = cache.as_ref().filter(|cache| cache.include_inactive == include_inactive)?;
+        (cache.fetched.elapsed() < self.tool_cache_ttl).then(|| cache.tools.clone())
+    }
     pub async fn authenticate(&self, login: &Login) -> Result<()> {
-        self.post(
-            "https://admin.nanofab.ualberta.ca/ajax.login.php",
-            [
-                ("uname", login.username.as_str()),
-                ("password", login.password.as_str()),
-                ("eaaa42a1464aa2b40a3ecfd68e2105d7", "1"),
-            ],
-        )
-        .await
-        .context("Failed to authenticate")?;
+        self.post(LOGIN_PATH, login.form()).await.context("Failed to authenticate")?;
+        *self.login.lock().unwrap() = Some(login.clone());
         Ok(())
     }
-    pub async fn get_tools(&self) -> Result<Vec<Tool>> {
-        self.get::<Vec<Tool>>(
-            "https://admin.nanofab.ualberta.ca/ajax.get-tools.php?term=&hide_inactive=1",
-        )
-        .await
-        .context("Failed to get tool list from server")
+    /// End the session on the server, then forget its cookies and the login, so requests
+    /// after this fail with `SessionExpired` until the next `authenticate`
+    pub async fn logout(&self) -> Result<()> {
+        self.send_get(&self.url(LOGOUT_PATH)).await.context("Failed to log out")?;
+        *self.login.lock().unwrap() = None;
+        self.transport.clear_session(&self.base_url)
+    }
+    /// Log back in with the credentials of the last successful `authenticate`
+    ///
+    /// The server drops this session when the same user logs in elsewhere (e.g. the
+    /// website in a browser), after which it answers requests with its login page.
+    async fn reauthenticate(&self) -> Result<()> {
+        let login = self.login.lock().unwrap().clone();
+        let login = login.ok_or(SessionExpired)?;
+        let body = form_body(login.form());
+        let json = self.send_form(&self.url(LOGIN_PATH), &body).await?.ok_or(SessionExpired)?;
+        if json.error {
+            return Err(anyhow!(json.msg)).context(SessionExpired);
+        }
+        Ok(())
+    }
+    /// Every active tool, or every tool if `include_inactive` is set, from the cache if it
+    /// was fetched within the cache TTL
+    pub async fn get_tools(&self, include_inactive: bool) -> Result<Vec<Tool>> {
+        if let Some(tools) = self.cached_tools(include_inactive) {
+            return Ok(tools);
+        }
+        let hide_inactive = if include_inactive { 0 } else { 1 };
+        let tools = self
+            .get::<Vec<Tool>>(&format!("ajax.get-tools.php?term=&hide_inactive={hide_inactive}"))
+            .await
+            .context("Failed to get tool list from server")?;
+        *self.tool_cache.lock().unwrap() =
+            Some(ToolCache { fetched: Instant::now(), include_inactive, tools: tools.clone() });
+        Ok(tools)
     }
     pub async fn get_user_projects(&self) -> Result<Vec<Project>> {
         let body = [("load", "modal.tool-booking.php")];
-        let root = self
-            .post("https://admin.nanofab.ualberta.ca/ajax.load-modal.php", body)
-            .await?
-            .parse::<Element>()?;
+        let root = self.post("ajax.load-modal.php", body).await?.parse::<Element>()?;
         let projects = root
-            .iter_decendents()
-            .find_attr("id", |v| v == "sel_project_id")
-            .unwrap()
+            .find_by_id("sel_project_id")
+            .context("Project selector not found")?
             .iter_children()
-            .filter_attr("class", |v| v == "")
-            .map(|elem| {
-                let name = elem.iter_contents().find_map(Content::as_ref_text).unwrap().to_string();
-                let id = elem.get_attr("value").unwrap().to_string();
-                Project { name, id }
+            .filter_attr("class", |v| v.is_empty())
+            // An option without text has no project name to show, like a placeholder
+            .filter_map(|elem| Some((elem, elem.iter_contents().find_map(Content::as_ref_text)?)))
+            .map(|(elem, name)| {
+                let name = name.to_string();
+                let id = elem.get_attr("value").context("Project option has no value")?.to_string();
+                debug_log::extraction("project.name", &name, elem);
+                debug_log::extraction("project.id", &id, elem);
+                Ok(Project { name, id })
             })
-            .collect_vec();
+            .collect::<Result<Vec<_>>>()?;
         Ok(projects)
     }
+    /// Who the client is logged in as
+    pub async fn get_account(&self) -> Result<Account> {
+        let body = [("load", ACCOUNT_MODAL)];
+        let root = self.post("ajax.load-modal.php", body).await?.parse::<Element>()?;
+        parse_account(&root).context("Failed to read account details")
+    }
+    /// Why the user can't currently book any tool, if there is a reason the client can see
+    ///
+    /// Only project availability is checked, the site doesn't expose certification status
+    /// or which projects cover which tools through any endpoint the client uses, so the
+    /// server still has the final say when booking.
+    pub async fn booking_blocker(&self) -> Result<Option<String>> {
+        if self.get_user_projects().await?.is_empty() {
+            return Ok(Some("you have no projects to book under".to_string()));
+        }
+        Ok(None)
+    }
     pub async fn get_tool_from_label(&self, label: &str) -> Result<Tool> {
-        self.get::<Vec<Tool>>(
-            format!(
-                "https://admin.nanofab.ualberta.ca/ajax.get-tools.php?term={label}&hide_inactive=1"
-            )
-            .as_str(),
-        )
-        .await
-        .context("Failed to get tool from server")?
-        .into_iter()
-        .find(|tool| &tool.label == label)
-        .context("No tools match label")
-    }
-    pub async fn get_user_bookings(&self) -> Result<TimeTable<(String, String)>> {
-        let root = self
-            .post(
-                "https://admin.nanofab.ualberta.ca/ajax.load-modal.php",
-                [("load", "modal.user.bookings.php")],
-            )
-            .await?
-            .parse::<Element>()?;
-        let mut bookings = vec![];
-        for booking_elem in root.iter_decendents().filter_attr("id", |v| v.starts_with("booking-"))
-        {
+        self.resolve_tool_label(label, false).await
+    }
+    /// Find the tool with exactly this label, or, if `auto_select` is set, the closest
+    /// fuzzy match when it is confident enough. Failing that, the error suggests the
+    /// closest labels.
+    pub async fn resolve_tool_label(&self, label: &str, auto_select: bool) -> Result<Tool> {
+        let candidates = match self.cached_tools(false) {
+            Some(tools) => tools,
+            None => self
+                .get::<Vec<Tool>>(&format!("ajax.get-tools.php?term={label}&hide_inactive=1"))
+                .await
+                .context("Failed to get tool from server")?,
+        };
+        let exact = candidates.into_iter().find(|tool| tool.label == label);
+        if let Some(tool) = exact {
+            return Ok(tool);
+        }
+        let ranked = fuzzy::rank(label.trim(), self.get_tools(false).await?, |tool| &tool.label);
+        if let Some((tool, confidence)) = ranked.first() {
+            if auto_select && *confidence >= AUTO_SELECT_CONFIDENCE {
+                return Ok(tool.clone());
+            }
+        }
+        let suggestions = ranked
+            .iter()
+            .filter(|(_, confidence)| *confidence >= SUGGEST_CONFIDENCE)
+            .take(3)
+            .map(|(tool, _)| format!("'{}'", tool.label))
+            .join(", ");
+        if suggestions.is_empty() {
+            bail!("No tools match label '{label}'")
+        }
+        bail!("No exact match for '{label}'; did you mean {suggestions}?")
+    }
+    pub async fn get_user_bookings(&self) -> Result<TimeTable<Booking>> {
+        let resp = self.post("ajax.load-modal.php", [("load", "modal.user.bookings.php")]).await?;
+        let roots = html::parse_fragment(&resp)?;
+        let mut rows = vec![];
+        for booking_elem in iter_fragment(&roots).filter_attr("id", |v| v.starts_with("booking-")) {
             let (name_str, time_str) = booking_elem
                 .iter_decendents()
                 .filter_attr("class", |v| v == "columns small-6")
-                .map(|elem| elem.iter_contents().find_map(|c| c.as_ref_text()).unwrap())
+                .map(|elem| elem.text_content())
                 .collect_tuple()
-                .unwrap();
+                .context("User booking row has no tool and time columns")?;
             let name = name_str.trim().to_string();
-            let time =
-                parse_yearless(time_str.trim(), "%b %-d @ %-I:%M %P").expect("Time did not parse");
-            let tool = self.get_tool_from_label(&name).await?;
-            let timeslot = self.get_tool_booking_at_time(&tool, time).await?;
-            bookings.push(timeslot);
+            let time = parse_yearless(time_str.trim(), "%b %-d @ %-I:%M %P")
+                .context("User booking time did not parse")?;
+            debug_log::extraction("user_booking.tool", &name, booking_elem);
+            debug_log::extraction("user_booking.time", &time.to_string(), booking_elem);
+            rows.push((name, time));
+        }
+        // Each row needs its own tool lookup and booking search, so run a few at a time
+        let bookings = stream::iter(rows)
+            .map(|(name, time)| async move {
+                let tool = self.resolve_tool_label(&name, true).await?;
+                self.get_tool_booking_at_time(&tool, time).await
+            })
+            .buffered(USER_BOOKING_LOOKUPS)
+            .try_collect::<Vec<_>>()
+            .await?;
+        Ok(TimeTable::new(bookings))
+    }
+    pub async fn get_team_bookings(
+        &self,
+        team: &Team,
+        start_date: Option<NaiveDate>,
+    ) -> Result<TimeTable<Booking>> {
+        let mut bookings = vec![];
+        for label in &team.tools {
+            let tool = self.get_tool_from_label(label).await?;
+            let table = self.get_tool_bookings(&tool, start_date, None).await?;
+            bookings.extend(
+                table.into_iter().filter(|ts| team.member_index(&ts.meta().user_email).is_some()),
+            );
         }
         Ok(TimeTable::new(bookings))
     }
@@ -105,149 +303,539 @@ impl NanoFab {
         &self,
         tool: &Tool,
         time: NaiveDateTime,
-    ) -> Result<TimeSlot<(String, String)>> {
+    ) -> Result<TimeSlot<Booking>> {
         self.get_tool_bookings(tool, Some(time.date()), Some(time.date()))
             .await?
-            .timeslots()
             .into_iter()
-            .cloned()
             .find(|timeslot| timeslot.start() == &Some(time))
             .ok_or(anyhow!("Booking not found"))
     }
+    /// Bookings between the given lab dates, in lab time. The site caps how much it returns
+    /// at once, so a bounded range is fetched `BOOKING_CHUNK_DAYS` at a time.
     pub async fn get_tool_bookings(
         &self,
         tool: &Tool,
         start_date: Option<NaiveDate>,
         end_date: Option<NaiveDate>,
-    ) -> Result<TimeTable<(String, String)>> {
-        let mut body = vec![("tool_id[]", tool.id.clone())];
-        if let Some(start) = start_date {
-            body.push(("start_date", start.format("%Y-%m-%d").to_string()));
+    ) -> Result<TimeTable<Booking>> {
+        let mut chunks = vec![];
+        for (chunk_start, chunk_end) in booking_ranges(start_date, end_date) {
+            let tools = std::slice::from_ref(tool);
+            let roots = self.search_bookings(tools, chunk_start, chunk_end).await?;
+            chunks.push(TimeTable::new(parse_tool_bookings(&roots, tools)?));
         }
-        if let Some(end) = end_date {
-            body.push(("end_date", end.format("%Y-%m-%d").to_string()));
+        Ok(merge_booking_chunks(chunks))
+    }
+    /// Bookings of all of `tools` like `get_tool_bookings`, but asking for every tool in
+    /// the same request
+    pub async fn get_tools_bookings(
+        &self,
+        tools: &[Tool],
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<TimeTable<Booking>> {
+        if tools.is_empty() {
+            return Ok(TimeTable::new([]));
         }
-        let (nonce, nonce_key) = self.get_nonce("modal.search-tool-bookings.php").await?;
-        body.push(("nonce", nonce));
-        body.push(("nonce_key", nonce_key));
-        let root = self
-            .post("https://admin.nanofab.ualberta.ca/ajax.get-bookings.php", body)
-            .await?
-            .parse::<Element>()?;
-        let mut bookings = vec![];
-        for booking_elem in root.iter_decendents().filter_attr("id", |v| v.starts_with("booking-"))
-        {
-            let (start_str, end_str, name_str) = booking_elem
-                .iter_decendents()
-                .filter_map(|elem| elem.get_attr("title"))
-                .collect_tuple()
-                .unwrap();
-            let time_fmt = "%-I:%M%P %a %b %-d";
-            let trim_ordinals = |c: char| "stndrh".contains(c);
-            let start = parse_yearless(start_str.trim_end_matches(trim_ordinals), time_fmt)?;
-            let end = parse_yearless(end_str.trim_end_matches(trim_ordinals), time_fmt)?;
-            let (name, email) = name_str.split_once(" <br/> ").unwrap();
-            bookings.push(TimeSlot::new(
-                Some(start),
-                Some(end),
-                (name.to_string(), email.to_string()),
-            ));
+        let mut chunks = vec![];
+        for (chunk_start, chunk_end) in booking_ranges(start_date, end_date) {
+            let roots = self.search_bookings(tools, chunk_start, chunk_end).await?;
+            chunks.push(TimeTable::new(parse_tool_bookings(&roots, tools)?));
         }
-        Ok(TimeTable::new(bookings))
+        Ok(merge_booking_chunks(chunks))
+    }
+    /// Post the booking search form for `tools`, returning the fragment of result rows
+    async fn search_bookings(
+        &self,
+        tools: &[Tool],
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Vec<Element>> {
+        let (nonce, nonce_key) = self.get_nonce("modal.search-tool-bookings.php").await?;
+        let body = booking_search_form(tools, start_date, end_date, nonce, nonce_key);
+        let resp = self.post("ajax.get-bookings.php", body).await?;
+        html::parse_fragment(&resp)
+    }
+    /// Whether `tool` has no bookings anywhere from `start` to `end`, in lab time
+    pub async fn is_available(
+        &self,
+        tool: &Tool,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+    ) -> Result<bool> {
+        // From the day before, for overnight bookings that run into the window
+        let first_day = start.date().pred_opt().unwrap_or(start.date());
+        let bookings = self.get_tool_bookings(tool, Some(first_day), Some(end.date())).await?;
+        Ok(window_is_free(bookings, start, end))
+    }
+    /// Book `tool` under `project` from `start` to `end`, in lab time, returning the new
+    /// booking's id. Server-side refusals, like a clash with another booking, become errors.
+    #[cfg(any(test, feature = "booking"))]
+    pub async fn create_booking(
+        &self,
+        tool: &Tool,
+        project: &Project,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+    ) -> Result<String> {
+        let (nonce, nonce_key) = self.get_nonce("modal.tool-booking.php").await?;
+        let body = booking_form(tool, project, start, end, nonce, nonce_key);
+        let msg = self.post(BOOK_TOOL_PATH, body).await.context("Failed to book tool")?;
+        parse_booking_id(&msg)
+    }
+    /// Cancel a booking given its id, bare or as the `booking-191730` element
+    /// id of a row in the user's bookings
+    #[cfg(any(test, feature = "booking"))]
+    pub async fn cancel_booking(&self, booking_id: &str) -> Result<()> {
+        let booking_id = parse_booking_id(booking_id)?;
+        let (nonce, nonce_key) = self.get_nonce("modal.user.bookings.php").await?;
+        let body = [("booking_id", booking_id), ("nonce", nonce), ("nonce_key", nonce_key)];
+        self.post(CANCEL_BOOKING_PATH, body).await.context("Failed to cancel booking")?;
+        Ok(())
     }
     pub async fn get_nonce(&self, modal: &str) -> Result<(String, String)> {
-        let url = "https://admin.nanofab.ualberta.ca/ajax.load-modal.php";
-        let root = self.post(url, [("load", modal)]).await?.parse::<Element>()?;
-        let nonce = root
-            .iter_decendents()
-            .find_attr("name", |v| v == "nonce")
-            .unwrap()
-            .get_attr("value")
-            .unwrap()
-            .to_string();
-        let nonce_key = root
-            .iter_decendents()
-            .find_attr("name", |v| v == "nonce_key")
-            .unwrap()
-            .get_attr("value")
-            .unwrap()
-            .to_string();
+        let root = self.post("ajax.load-modal.php", [("load", modal)]).await?.parse::<Element>()?;
+        let nonce_elem = root.find_by_name("nonce").context("Nonce not found")?;
+        let nonce = nonce_elem.get_attr_ci("value").unwrap().to_string();
+        debug_log::extraction("nonce", &nonce, nonce_elem);
+        let nonce_key_elem = root.find_by_name("nonce_key").context("Nonce key not found")?;
+        let nonce_key = nonce_key_elem.get_attr_ci("value").unwrap().to_string();
+        debug_log::extraction("nonce_key", &nonce_key, nonce_key_elem);
         Ok((encode(&nonce).to_string(), nonce_key))
     }
-    pub async fn get<T: DeserializeOwned>(&self, url: &str) -> Result<T> {
-        let resp = self
-            .client
-            .get(url)
-            .send()
-            .await
-            .context("Failed to send get request")?
-            .bytes()
-            .await
-            .context("Failed to recieve bytes of response body")?;
-        serde_json::from_slice(&resp).context("Server response could not be parsed")
+    /// Get the JSON at `path`, relative to the base URL
+    pub async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        let url = &self.url(path);
+        let resp = self.send_get(url).await?;
+        let has_login = self.login.lock().unwrap().is_some();
+        // Anything but JSON is most likely the login page after the session timed out,
+        // while JSON of the wrong shape won't be fixed by logging back in
+        let resp = match looks_like_json(&resp) {
+            false if has_login => {
+                self.reauthenticate().await?;
+                self.send_get(url).await?
+            }
+            _ => resp,
+        };
+        if !looks_like_json(&resp) {
+            bail!("Server did not answer with JSON: {}", body_snippet(&resp));
+        }
+        serde_json::from_str(&resp).context("Server response could not be parsed")
+    }
+    async fn send_get(&self, url: &str) -> Result<String> {
+        self.transport.get_text(url).await
     }
+    /// Post a form to `path`, relative to the base URL
     pub async fn post(
         &self,
-        url: &str,
+        path: &str,
         body: impl IntoIterator<Item = (impl AsRef<str>, impl AsRef<str>)>,
     ) -> Result<String> {
-        let resp = self
-            .client
-            .post(url)
-            .header("Content-Type", "application/x-www-form-urlencoded")
-            .body(body.into_iter().map(|(k, v)| format!("{}={}", k.as_ref(), v.as_ref())).join("&"))
-            .send()
-            .await
-            .context("Failed to send post request")?
-            .bytes()
-            .await
-            .context("Failed to recieve bytes of response body")?;
-        let json = serde_json::from_slice::<PostResponse>(&resp)
-            .expect("Response body was not valid JSON");
-        if json.error {
-            Err(anyhow!(json.msg))
-        } else {
-            Ok(json.msg)
+        let url = &self.url(path);
+        let body = form_body(body);
+        let json = match self.send_form(url, &body).await? {
+            Some(json) => json,
+            None => {
+                self.reauthenticate().await?;
+                self.send_form(url, &body).await?.ok_or(SessionExpired)?
+            }
+        };
+        json.into_result()
+    }
+    /// Post a form, returning `None` if the response is not the usual JSON envelope
+    async fn send_form(&self, url: &str, body: &str) -> Result<Option<PostResponse>> {
+        let resp = self.transport.post_form(url, body).await?;
+        Ok(serde_json::from_str::<PostResponse>(&resp).ok())
+    }
+}
+
+/// The server answered with its login page instead of JSON and logging back in didn't
+/// help, so the user needs to log in again
+#[derive(Debug)]
+pub struct SessionExpired;
+impl Display for SessionExpired {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Session expired, please log in again")
+    }
+}
+impl std::error::Error for SessionExpired {}
+
+/// The `tools` whose labels fuzzy match `query`, best match first. Every tool matches an
+/// empty query, so that leaves them all in order.
+pub fn search_tools<'a>(tools: &'a [Tool], query: &str) -> Vec<&'a Tool> {
+    fuzzy::rank(query.trim(), tools, |tool| &tool.label).into_iter().map(|(tool, _)| tool).collect()
+}
+
+/// Every element of a parsed fragment, each top-level element followed by its descendants
+fn iter_fragment(roots: &[Element]) -> impl Iterator<Item = &Element> {
+    roots.iter().flat_map(|root| std::iter::once(root).chain(root.iter_decendents()))
+}
+
+/// Bookings in a booking search result. Each tool's rows follow a heading linking to the
+/// tool, so rows get the label of the heading before them, or of the only tool searched for.
+fn parse_tool_bookings(roots: &[Element], tools: &[Tool]) -> Result<Vec<TimeSlot<Booking>>> {
+    let mut tool_label = match tools {
+        [tool] => Some(tool.label.clone()),
+        _ => None,
+    };
+    let mut bookings = vec![];
+    for elem in iter_fragment(roots) {
+        if let Some(tool_id) = elem.get_attr("href").and_then(tool_link_id) {
+            let label = match tools.iter().find(|tool| tool.id == tool_id) {
+                Some(tool) => tool.label.clone(),
+                None => elem.text_content().trim().to_string(),
+            };
+            debug_log::extraction("booking.tool", &label, elem);
+            tool_label = Some(label);
+        } else if elem.get_attr("id").is_some_and(|id| id.starts_with("booking-")) {
+            let label = tool_label.clone().context("Booking doesn't say which tool it is for")?;
+            bookings.push(parse_booking_row(elem, label)?);
+        }
+    }
+    Ok(bookings)
+}
+
+/// The tool id in a link to a tool's page, like `equipment-detail.php?tool_id=427`
+fn tool_link_id(href: &str) -> Option<&str> {
+    let (_, query) = href.split_once("equipment-detail.php?")?;
+    query.split('&').find_map(|param| param.strip_prefix("tool_id="))
+}
+
+fn parse_booking_row(booking_elem: &Element, tool_label: String) -> Result<TimeSlot<Booking>> {
+    let id = parse_booking_id(booking_elem.get_attr("id").unwrap_or_default())?;
+    let (start_str, end_str, name_str) = booking_elem
+        .iter_decendents()
+        .filter_map(|elem| elem.get_attr("title"))
+        .collect_tuple()
+        .context("Booking row has no start/end/user titles")?;
+    let start = parse_booking_time(start_str)?;
+    let end = parse_booking_time(end_str)?;
+    let (name, email) =
+        name_str.split_once(" <br/> ").context("Booking user title has no name and email")?;
+    let group = booking_elem
+        .get_attr("class")
+        .and_then(|class| class.split_whitespace().find_map(|c| c.strip_prefix("group-")));
+    debug_log::extraction("booking.id", &id, booking_elem);
+    debug_log::extraction("booking.start", &start.to_string(), booking_elem);
+    debug_log::extraction("booking.end", &end.to_string(), booking_elem);
+    debug_log::extraction("booking.name", name, booking_elem);
+    debug_log::extraction("booking.email", email, booking_elem);
+    debug_log::extraction("booking.group", group.unwrap_or_default(), booking_elem);
+    let booking = Booking {
+        id,
+        user_name: name.to_string(),
+        user_email: email.to_string(),
+        tool_label,
+        group: group.map(String::from),
+    };
+    Ok(TimeSlot::new(Some(start), Some(end), booking))
+}
+
+/// Form fields for the booking search, with one `tool_id[]` per tool
+fn booking_search_form(
+    tools: &[Tool],
+    start_date: Option<NaiveDate>,
+    end_date: Option<NaiveDate>,
+    nonce: String,
+    nonce_key: String,
+) -> Vec<(&'static str, String)> {
+    let mut body = tools.iter().map(|tool| ("tool_id[]", tool.id.clone())).collect_vec();
+    if let Some(start) = start_date {
+        body.push(("start_date", start.format("%Y-%m-%d").to_string()));
+    }
+    if let Some(end) = end_date {
+        body.push(("end_date", end.format("%Y-%m-%d").to_string()));
+    }
+    body.push(("nonce", nonce));
+    body.push(("nonce_key", nonce_key));
+    body
+}
+
+/// Form fields for `create_booking`, named like the booking search form's
+#[cfg(any(test, feature = "booking"))]
+fn booking_form(
+    tool: &Tool,
+    project: &Project,
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+    nonce: String,
+    nonce_key: String,
+) -> Vec<(&'static str, String)> {
+    vec![
+        ("tool_id", tool.id.clone()),
+        ("project_id", project.id.clone()),
+        ("start_date", start.format("%Y-%m-%d").to_string()),
+        ("start_time", start.format("%H:%M").to_string()),
+        ("end_date", end.format("%Y-%m-%d").to_string()),
+        ("end_time", end.format("%H:%M").to_string()),
+        ("nonce", nonce),
+        ("nonce_key", nonce_key),
+    ]
+}
+
+/// The account fields of the profile form, which holds them as input values
+fn parse_account(root: &Element) -> Result<Account> {
+    let field = |name: &str| {
+        let elem = root.find_by_name(name).with_context(|| format!("No `{name}` field"))?;
+        let value = elem.get_attr_ci("value").unwrap_or_default().trim().to_string();
+        debug_log::extraction(&format!("account.{name}"), &value, elem);
+        Ok::<_, anyhow::Error>(value)
+    };
+    let name = [field("first_name")?, field("last_name")?].iter().join(" ").trim().to_string();
+    Ok(Account { name, username: field("uname")?, email: field("email")? })
+}
+
+/// The id in a booking response message, either bare or as a `booking-191730` element id
+fn parse_booking_id(msg: &str) -> Result<String> {
+    let msg = msg.trim();
+    if !msg.is_empty() && msg.chars().all(|c| c.is_ascii_digit()) {
+        return Ok(msg.to_string());
+    }
+    let (_, rest) =
+        msg.split_once("booking-").with_context(|| format!("No booking id in `{msg}`"))?;
+    let id = rest.chars().take_while(char::is_ascii_digit).collect::<String>();
+    match id.is_empty() {
+        true => bail!("No booking id in `{msg}`"),
+        false => Ok(id),
+    }
+}
+
+/// Write `contents` to `path` so only the user can read it on unix, for the files that
+/// hold session cookies or passwords
+pub(crate) fn write_private(path: &Path, contents: &str) -> std::io::Result<()> {
+    use std::io::Write;
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(path)?;
+    // The mode only applies to new files, so tighten ones written before this too
+    #[cfg(unix)]
+    file.set_permissions(std::os::unix::fs::PermissionsExt::from_mode(0o600))?;
+    file.write_all(contents.as_bytes())
+}
+
+fn form_body(body: impl IntoIterator<Item = (impl AsRef<str>, impl AsRef<str>)>) -> String {
+    body.into_iter().map(|(k, v)| format!("{}={}", k.as_ref(), v.as_ref())).join("&")
+}
+
+fn looks_like_json(body: &str) -> bool {
+    body.trim_start().starts_with(['[', '{', '"'])
+}
+
+/// The start of a response's text with any HTML tags left out, like `503 Service
+/// Unavailable` for a proxy's error page
+fn body_snippet(body: &str) -> String {
+    const MAX_CHARS: usize = 120;
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in body.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                text.push(' ');
+            }
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    let text = text.split_whitespace().join(" ");
+    match text.chars().count() {
+        0 => "(empty response)".to_string(),
+        n if n > MAX_CHARS => format!("`{}...`", text.chars().take(MAX_CHARS).collect::<String>()),
+        _ => format!("`{text}`"),
+    }
+}
+
+/// Parse a booking tooltip time like `6:00am Thu Nov 24th`, tolerating stray
+/// whitespace anywhere and an ordinal suffix on the day
+fn parse_booking_time(title: &str) -> Result<NaiveDateTime> {
+    let (time, date) = title
+        .trim()
+        .split_once(char::is_whitespace)
+        .with_context(|| format!("Booking time `{title}` has no date"))?;
+    let date = date.split_whitespace().join(" ");
+    let date = date.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    let time = parse_clock_time(time)?;
+    parse_yearless(&format!("{} {date}", time.format("%H:%M")), "%H:%M %a %b %-d")
+}
+
+/// Parse a clock time as the booking pages write it, either `6:00am` in
+/// tooltips or ` 13:00 ` in table cells
+fn parse_clock_time(time: &str) -> Result<NaiveTime> {
+    let time = time.split_whitespace().join("").to_lowercase();
+    let fmt = match time.ends_with("am") || time.ends_with("pm") {
+        true => "%-I:%M%P",
+        false => "%-H:%M",
+    };
+    NaiveTime::parse_from_str(&time, fmt).with_context(|| format!("Failed to parse time `{time}`"))
+}
+
+/// `start..=end` split into consecutive inclusive ranges of at most `max_days` days
+fn date_chunks(start: NaiveDate, end: NaiveDate, max_days: u64) -> Vec<(NaiveDate, NaiveDate)> {
+    let mut chunks = vec![];
+    let mut chunk_start = start;
+    while chunk_start <= end {
+        let chunk_end = chunk_start
+            .checked_add_days(Days::new(max_days - 1))
+            .map_or(end, |chunk_end| chunk_end.min(end));
+        chunks.push((chunk_start, chunk_end));
+        match chunk_end.succ_opt() {
+            Some(next) => chunk_start = next,
+            None => break,
         }
     }
+    chunks
+}
+
+/// Whether `start..end` fits inside one of the openings between `bookings`
+fn window_is_free<M>(mut bookings: TimeTable<M>, start: NaiveDateTime, end: NaiveDateTime) -> bool {
+    bookings.merge_overlaps(|first, _| first);
+    bookings.inverted().iter().any(|opening| {
+        matches!(
+            (opening.compare_datetime(start), opening.compare_datetime(end)),
+            (RelTime::Contains, RelTime::Contains)
+        )
+    })
+}
+
+/// The ranges to ask for bookings in, split into `BOOKING_CHUNK_DAYS` chunks when bounded
+fn booking_ranges(
+    start_date: Option<NaiveDate>,
+    end_date: Option<NaiveDate>,
+) -> Vec<(Option<NaiveDate>, Option<NaiveDate>)> {
+    let (Some(start), Some(end)) = (start_date, end_date) else {
+        return vec![(start_date, end_date)];
+    };
+    date_chunks(start, end, BOOKING_CHUNK_DAYS)
+        .into_iter()
+        .map(|(chunk_start, chunk_end)| (Some(chunk_start), Some(chunk_end)))
+        .collect()
+}
+
+/// One table from per-chunk tables, keeping a single copy of bookings that
+/// span a chunk boundary and so come back in both
+fn merge_booking_chunks(chunks: Vec<TimeTable<Booking>>) -> TimeTable<Booking> {
+    let mut seen = HashSet::new();
+    let bookings = chunks.into_iter().flatten().filter(|ts| seen.insert(ts.meta().id.clone()));
+    TimeTable::new(bookings.collect_vec())
+}
+
+/// The current wall-clock time at the lab
+pub fn lab_now() -> NaiveDateTime {
+    Utc::now().with_timezone(&LAB_TIMEZONE).naive_local()
 }
 
 fn parse_yearless(datetime_string: &str, fmt: &str) -> Result<NaiveDateTime> {
+    parse_yearless_near(datetime_string, fmt, lab_now())
+}
+
+/// Parse a date without a year, taking whichever year within ten of `reference`'s puts it
+/// closest to `reference`. The year can be off by one near New Year's without a weekday
+/// to go on, and Feb 29 only exists in some years, so the first year that parses won't do.
+fn parse_yearless_near(
+    datetime_string: &str,
+    fmt: &str,
+    reference: NaiveDateTime,
+) -> Result<NaiveDateTime> {
     let fmt_with_year = fmt.to_string() + " %Y";
-    let current_year = chrono::Local::now()
-        .format("%Y")
-        .to_string()
-        .parse::<isize>()
-        .expect("Paring current year should never fail");
-    for n in (0..10).flat_map(|n| [n, -n]) {
+    let mut closest: Option<NaiveDateTime> = None;
+    for year in reference.year() - 10..=reference.year() + 10 {
         let maybe_datetime = chrono::NaiveDateTime::parse_from_str(
-            &format!("{datetime_string} {}", current_year + n),
+            &format!("{datetime_string} {year}"),
             &fmt_with_year,
         );
         match maybe_datetime {
-            Err(e) if e.kind() == ParseErrorKind::Impossible => {}
-            Ok(dt) => return Ok(dt),
-            ok @ Err(_) => {
-                return ok.with_context(|| format!("Failed to parse `{datetime_string}`"))
+            // The weekday doesn't match, or the day doesn't exist, in this year
+            Err(e)
+                if matches!(e.kind(), ParseErrorKind::Impossible | ParseErrorKind::OutOfRange) => {}
+            Ok(dt) => {
+                let distance = |dt: NaiveDateTime| (dt - reference).abs();
+                if closest.is_none_or(|closest| distance(dt) < distance(closest)) {
+                    closest = Some(dt);
+                }
+            }
+            err @ Err(_) => {
+                return err.with_context(|| format!("Failed to parse `{datetime_string}`"))
             }
         }
     }
-    Err(anyhow!("Could not find year for `{datetime_string}`"))
+    closest.with_context(|| format!("Could not find year for `{datetime_string}`"))
 }
 
+/// A tool as the tool search endpoint lists it, for a select2 dropdown
 #[derive(Debug, Clone, Deserialize)]
 pub struct Tool {
+    /// Name of the tool, as the site shows it in bookings and as tools are looked up by
     pub label: String,
+    /// What the dropdown fills into its input, the same as `label` in practice
     pub value: String,
+    /// The dropdown's display text, which can carry the category or a status like `(Inactive)`
     pub text: String,
+    /// Number the booking forms send as `tool_id`
     pub id: String,
 }
+struct ToolCache {
+    fetched: Instant,
+    include_inactive: bool,
+    tools: Vec<Tool>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Booking {
+    /// The number from the row's `booking-<id>` element id, as `cancel_booking` takes
+    pub id: String,
+    pub user_name: String,
+    pub user_email: String,
+    pub tool_label: String,
+    /// Hash from the row's `group-<hash>` class, shared by the rows of one reservation
+    pub group: Option<String>,
+}
+impl ExportMeta for Booking {
+    fn user(&self) -> Option<&str> {
+        Some(&self.user_name)
+    }
+}
+impl Booking {
+    pub fn same_group(&self, other: &Booking) -> bool {
+        self.group.is_some() && self.group == other.group
+    }
+    /// Whether both are the same user's booking of the same tool in the same group,
+    /// whatever their ids
+    pub fn same_reservation(&self, other: &Booking) -> bool {
+        self.user_email == other.user_email
+            && self.tool_label == other.tool_label
+            && self.group == other.group
+    }
+}
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Account {
+    pub name: String,
+    pub username: String,
+    pub email: String,
+}
+impl Display for Account {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({}, {})", self.name, self.username, self.email)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Login {
     pub username: String,
     pub password: String,
 }
+impl Login {
+    fn form(&self) -> [(&str, &str); 3] {
+        [
+            ("uname", self.username.as_str()),
+            ("password", self.password.as_str()),
+            ("eaaa42a1464aa2b40a3ecfd68e2105d7", "1"),
+        ]
+    }
+}
 
 #[derive(Debug, Deserialize)]
 pub struct PostResponse {
@@ -256,8 +844,920 @@ pub struct PostResponse {
     msg: String,
 }
 
+impl PostResponse {
+    /// The message, as an error if the server flagged it as one
+    fn into_result(self) -> Result<String> {
+        match self.error {
+            true => Err(anyhow!(self.msg)),
+            false => Ok(self.msg),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Project {
     pub name: String,
     pub id: String,
 }
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum ProjectSort {
+    /// The order the server lists them in
+    #[default]
+    Server,
+    Name,
+    Id,
+}
+impl ProjectSort {
+    pub fn next(self) -> Self {
+        match self {
+            ProjectSort::Server => ProjectSort::Name,
+            ProjectSort::Name => ProjectSort::Id,
+            ProjectSort::Id => ProjectSort::Server,
+        }
+    }
+    /// Sort `projects`, which must be in server order for `Server` to restore it
+    pub fn sort(self, projects: &mut [impl Borrow<Project>]) {
+        match self {
+            ProjectSort::Server => {}
+            ProjectSort::Name => projects.sort_by_key(|p| p.borrow().name.to_lowercase()),
+            ProjectSort::Id => projects.sort_by_key(|p| {
+                let id = &p.borrow().id;
+                (id.parse::<u64>().unwrap_or(u64::MAX), id.clone())
+            }),
+        }
+    }
+}
+impl Display for ProjectSort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProjectSort::Server => f.write_str("server order"),
+            ProjectSort::Name => f.write_str("name"),
+            ProjectSort::Id => f.write_str("id"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_sort() {
+        let project = |name: &str, id: &str| Project { name: name.into(), id: id.into() };
+        let server_order =
+            || vec![project("beta", "20"), project("Alpha", "3"), project("gamma", "100")];
+        let names = |projects: &[Project]| projects.iter().map(|p| p.name.clone()).collect_vec();
+        let mut projects = server_order();
+        ProjectSort::Server.sort(&mut projects);
+        assert_eq!(names(&projects), ["beta", "Alpha", "gamma"]);
+        ProjectSort::Name.sort(&mut projects);
+        assert_eq!(names(&projects), ["Alpha", "beta", "gamma"]);
+        ProjectSort::Id.sort(&mut projects);
+        assert_eq!(names(&projects), ["Alpha", "beta", "gamma"]);
+        let mut projects = server_order();
+        ProjectSort::Id.sort(&mut projects);
+        assert_eq!(names(&projects), ["Alpha", "beta", "gamma"]);
+        assert_eq!(ProjectSort::Id.next(), ProjectSort::Server);
+    }
+
+    #[test]
+    fn test_parse_tool_bookings_groups() {
+        let row = |id: &str, group: &str, start: &str, end: &str| {
+            format!(
+                "<div id=\"booking-{id}\" class=\"table-row group-{group}\"><div class=\"row\">\
+                <div class=\"columns small-4\"><span class=\"has-tip\" title=\"{start}\"> x</span></div>\
+                <div class=\"columns small-4\"><span class=\"has-tip\" title=\"{end}\"> x</span></div>\
+                <div class=\"columns small-4\"><span class=\"has-tip\" title=\"Daniel Mildenberger <br/> dmildenb@ualberta.ca\">dmildenb</span></div>\
+                </div></div>"
+            )
+        };
+        // Rows with no wrapping element, the way a bare list of bookings comes back
+        let html = format!(
+            "{}{}{}",
+            row("191677", "5b68a4ec", "4:00pm Thu Nov 24th", "5:00pm Thu Nov 24th"),
+            row("191678", "5b68a4ec", "5:00pm Thu Nov 24th", "6:00pm Thu Nov 24th"),
+            row("191679", "4b8a09b6", "6:00pm Thu Nov 24th", "7:00pm Thu Nov 24th"),
+        );
+        let roots = html::parse_fragment(&html).unwrap();
+        let tool = tool("Heidelberg MLA150", "427");
+        let bookings = parse_tool_bookings(&roots, &[tool]).unwrap();
+        let metas = bookings.iter().map(|ts| ts.meta()).collect_vec();
+        assert_eq!(metas[0].group.as_deref(), Some("5b68a4ec"));
+        assert_eq!(metas[0].user_email, "dmildenb@ualberta.ca");
+        assert_eq!(metas[0].tool_label, "Heidelberg MLA150");
+        assert!(metas[0].same_group(metas[1]));
+        assert!(!metas[1].same_group(metas[2]));
+        let ungrouped = Booking { group: None, ..metas[0].clone() };
+        assert!(!ungrouped.same_group(&ungrouped));
+    }
+
+    #[test]
+    fn test_parse_tool_bookings_ids() {
+        let roots = html::parse_fragment(html::tests::TEST2).unwrap();
+        // No tools to go on, so the labels come from the headings
+        let bookings = parse_tool_bookings(&roots, &[]).unwrap();
+        assert_eq!(bookings.len(), 37);
+        let first = bookings[0].meta();
+        assert_eq!(first.id, "191730");
+        assert_eq!(first.user_name, "Wyatt James");
+        assert_eq!(first.user_email, "wyatt@norcada.com");
+        assert_eq!(first.tool_label, "Heidelberg MLA150");
+        assert_eq!(bookings[1].meta().id, "190677");
+        assert!(bookings.iter().map(|ts| &ts.meta().id).all_unique());
+    }
+
+    #[test]
+    fn test_parse_booking_row_malformed() {
+        let row = |html: &str| {
+            let elem = html.parse::<Element>().unwrap();
+            parse_booking_row(&elem, "MLA150".to_string()).map_err(|err| err.to_string())
+        };
+        assert_eq!(
+            row(r#"<div id="booking-1"><span title="9:00am Thu Nov 24"></span></div>"#)
+                .unwrap_err(),
+            "Booking row has no start/end/user titles"
+        );
+        assert_eq!(
+            row(r#"<div id="booking-1"><span title="9:00am Thu Nov 24"></span>
+                <span title="10:00am Thu Nov 24"></span><span title="Wyatt James"></span></div>"#)
+            .unwrap_err(),
+            "Booking user title has no name and email"
+        );
+    }
+
+    #[test]
+    fn test_parse_account() {
+        // Written to look like the other modals' forms, not captured from the site
+        let html = r#"<form id="frm-user-profile"><div class="row">
+            <div class="columns small-6"><label>First Name</label>
+            <input type="text" name="first_name" value="Wyatt"></div>
+            <div class="columns small-6"><label>Last Name</label>
+            <input type="text" name="last_name" value=" James "></div>
+            <input type="text" name="uname" value="wjames" disabled>
+            <input type="email" name="email" value="wyatt@norcada.com">
+            </div></form>"#;
+        let account = parse_account(&html.parse::<Element>().unwrap()).unwrap();
+        assert_eq!(
+            account,
+            Account {
+                name: "Wyatt James".into(),
+                username: "wjames".into(),
+                email: "wyatt@norcada.com".into(),
+            }
+        );
+        assert_eq!(account.to_string(), "Wyatt James (wjames, wyatt@norcada.com)");
+        assert!(parse_account(&"<form></form>".parse::<Element>().unwrap()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_get_account_missing_fields() {
+        fn route(request: &str) -> String {
+            match request.contains("load=modal.user.profile.php") {
+                true => fake_msg(r#"<form><input name="first_name" value="Wyatt"/></form>"#),
+                false => panic!("Unexpected request `{request}`"),
+            }
+        }
+        let client = NanoFab::with_transport(FakeTransport(route));
+        let err = client.get_account().await.unwrap_err();
+        assert_eq!(format!("{err:#}"), "Failed to read account details: No `last_name` field");
+    }
+
+    #[tokio::test]
+    async fn test_get_user_projects_unreadable_options() {
+        fn route(_: &str) -> String {
+            fake_msg(
+                r#"<form><select id="sel_project_id"><option class="" value=""></option><option class="" value="7">Etching</option></select></form>"#,
+            )
+        }
+        fn no_value(_: &str) -> String {
+            fake_msg(
+                r#"<form><select id="sel_project_id"><option class="">Etching</option></select></form>"#,
+            )
+        }
+        let client = NanoFab::with_transport(FakeTransport(route));
+        let projects = client.get_user_projects().await.unwrap();
+        assert_eq!(
+            projects.iter().map(|p| (p.name.as_str(), p.id.as_str())).collect_vec(),
+            [("Etching", "7")]
+        );
+        let client = NanoFab::with_transport(FakeTransport(no_value));
+        let err = client.get_user_projects().await.unwrap_err();
+        assert_eq!(err.to_string(), "Project option has no value");
+    }
+
+    #[test]
+    fn test_window_is_free() {
+        let dt = |s| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap();
+        let bookings = || {
+            TimeTable::new([
+                TimeSlot::new(Some(dt("2022-11-23 22:00")), Some(dt("2022-11-24 02:00")), ()),
+                TimeSlot::new(Some(dt("2022-11-24 09:00")), Some(dt("2022-11-24 11:00")), ()),
+                TimeSlot::new(Some(dt("2022-11-24 11:00")), Some(dt("2022-11-24 13:00")), ()),
+            ])
+        };
+        let free = |start, end| window_is_free(bookings(), dt(start), dt(end));
+        assert!(free("2022-11-24 06:00", "2022-11-24 09:00"));
+        assert!(free("2022-11-24 13:00", "2022-11-25 01:00"));
+        // Partly overlapping a booking
+        assert!(!free("2022-11-24 08:00", "2022-11-24 10:00"));
+        assert!(!free("2022-11-24 01:00", "2022-11-24 03:00"));
+        // Fully booked, across two back-to-back bookings
+        assert!(!free("2022-11-24 09:30", "2022-11-24 12:30"));
+        assert!(window_is_free(
+            TimeTable::<()>::new([]),
+            dt("2022-11-24 09:00"),
+            dt("2022-11-24 10:00")
+        ));
+    }
+
+    /// Shaped like `ajax.get-tools.php?term=&hide_inactive=0`, with an inactive tool
+    const TOOLS_JSON: &str = r#"[
+        {"label":"Heidelberg MLA150","value":"Heidelberg MLA150","text":"Heidelberg MLA150 - Lithography","id":"427"},
+        {"label":"Plasmalab 150","value":"Plasmalab 150","text":"Plasmalab 150 - Etch","id":"87"},
+        {"label":"Mask Aligner","value":"Mask Aligner","text":"Mask Aligner - Lithography","id":"12"}
+    ]"#;
+
+    #[tokio::test]
+    async fn test_get_tools_inactive() {
+        fn route(request: &str) -> String {
+            // Leaving inactive tools out is up to the server
+            let mut tools = serde_json::from_str::<Vec<serde_json::Value>>(TOOLS_JSON).unwrap();
+            if request.ends_with("hide_inactive=1") {
+                tools.retain(|tool| tool["id"] != "87");
+            } else {
+                assert!(request.ends_with("hide_inactive=0"));
+            }
+            serde_json::to_string(&tools).unwrap()
+        }
+        let client = NanoFab::with_transport(FakeTransport(route));
+        assert_eq!(client.get_tools(true).await.unwrap().len(), 3);
+        assert_eq!(client.get_tools(false).await.unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_search_tools() {
+        let tools = ["Mask Aligner", "Plasmalab 150", "Heidelberg MLA150", "Multi Laser Anneal"]
+            .map(|label| tool(label, &label.len().to_string()));
+        let labels = |query| search_tools(&tools, query).iter().map(|t| &t.label).collect_vec();
+        assert_eq!(labels("mla150")[0], "Heidelberg MLA150");
+        assert!(!labels("mla150").contains(&&"Mask Aligner".to_string()));
+        assert_eq!(labels("heidel mla"), ["Heidelberg MLA150"]);
+        assert_eq!(labels("plasma lab")[0], "Plasmalab 150");
+        assert_eq!(labels(" ").len(), tools.len());
+        assert!(labels("xyz").is_empty());
+    }
+
+    #[test]
+    fn test_date_chunks() {
+        let date = |s| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+        let chunks = date_chunks(date("2022-11-01"), date("2022-12-10"), 14);
+        assert_eq!(
+            chunks,
+            [
+                (date("2022-11-01"), date("2022-11-14")),
+                (date("2022-11-15"), date("2022-11-28")),
+                (date("2022-11-29"), date("2022-12-10")),
+            ]
+        );
+        let day = date("2022-11-24");
+        assert_eq!(date_chunks(day, day, 14), [(day, day)]);
+        assert!(date_chunks(day, date("2022-11-23"), 14).is_empty());
+    }
+
+    #[test]
+    fn test_merge_booking_chunks() {
+        let dt = |s| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap();
+        let booking = |id: &str, name: &str| Booking {
+            id: id.into(),
+            user_name: name.into(),
+            user_email: format!("{name}@ualberta.ca"),
+            tool_label: "Heidelberg MLA150".into(),
+            group: Some("5b68a4ec".into()),
+        };
+        let slot =
+            |start, end, id, name| TimeSlot::new(Some(dt(start)), Some(dt(end)), booking(id, name));
+        // The overnight booking straddles the chunk boundary and comes back in both
+        let first = TimeTable::new([
+            slot("2022-11-14 09:00", "2022-11-14 10:00", "1", "wjames"),
+            slot("2022-11-14 22:00", "2022-11-15 02:00", "2", "ksetzer"),
+        ]);
+        let second = TimeTable::new([
+            slot("2022-11-14 22:00", "2022-11-15 02:00", "2", "ksetzer"),
+            slot("2022-11-15 22:00", "2022-11-16 02:00", "3", "ksetzer"),
+        ]);
+        let merged = merge_booking_chunks(vec![first, second]);
+        let names = merged.iter().map(|ts| ts.meta().user_name.as_str()).collect_vec();
+        assert_eq!(names, ["wjames", "ksetzer", "ksetzer"]);
+    }
+
+    #[test]
+    fn test_parse_tools_bookings() {
+        let heading = |tool_id: &str, label: &str| {
+            format!("<h4><small><a href=\"equipment-detail.php?tool_id={tool_id}\">{label}</a></small></h4>")
+        };
+        let row = |id: &str, name: &str| {
+            format!(
+                "<div id=\"booking-{id}\" class=\"table-row\"><div class=\"row\">\
+                <span title=\"4:00pm Thu Nov 24th\"></span><span title=\"5:00pm Thu Nov 24th\"></span>\
+                <span title=\"{name} <br/> {name}@ualberta.ca\"></span></div></div>"
+            )
+        };
+        let tools = [tool("Heidelberg MLA150", "412"), tool("Plasmalab 150", "87")];
+        let html = format!(
+            "<div>{}{}{}</div><div>{}{}</div>",
+            heading("412", "MLA150"),
+            row("191677", "wjames"),
+            row("191678", "dmildenb"),
+            heading("87", "Plasmalab"),
+            row("191679", "ksetzer"),
+        );
+        let roots = html::parse_fragment(&html).unwrap();
+        let bookings = parse_tool_bookings(&roots, &tools).unwrap();
+        let labels = bookings.iter().map(|ts| ts.meta().tool_label.as_str()).collect_vec();
+        assert_eq!(labels, ["Heidelberg MLA150", "Heidelberg MLA150", "Plasmalab 150"]);
+        assert_eq!(bookings[2].meta().user_email, "ksetzer@ualberta.ca");
+        // Without headings, rows of a search for several tools can't be told apart
+        let roots = html::parse_fragment(&row("191677", "wjames")).unwrap();
+        assert!(parse_tool_bookings(&roots, &tools).is_err());
+        assert_eq!(
+            parse_tool_bookings(&roots, &tools[1..]).unwrap()[0].meta().tool_label,
+            "Plasmalab 150"
+        );
+        assert_eq!(tool_link_id("equipment-detail.php?tool_id=427&tab=1"), Some("427"));
+
+        let body = form_body(booking_search_form(&tools, None, None, "n".into(), "k".into()));
+        assert_eq!(body, "tool_id[]=412&tool_id[]=87&nonce=n&nonce_key=k");
+    }
+
+    #[test]
+    fn test_booking_form() {
+        let tool = tool("Heidelberg MLA150", "412");
+        let project = Project { name: "Waveguides".into(), id: "2087".into() };
+        let dt = |s| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap();
+        let start = dt("2022-11-24 09:00");
+        let end = dt("2022-11-24 13:30");
+        let body = booking_form(&tool, &project, start, end, "9xNGZda".into(), "key".into());
+        assert_eq!(
+            form_body(body),
+            "tool_id=412&project_id=2087&start_date=2022-11-24&start_time=09:00\
+            &end_date=2022-11-24&end_time=13:30&nonce=9xNGZda&nonce_key=key"
+        );
+    }
+
+    #[test]
+    fn test_parse_booking_id() {
+        assert_eq!(parse_booking_id(" 191730 ").unwrap(), "191730");
+        assert_eq!(parse_booking_id("<div id=\"booking-191730\">").unwrap(), "191730");
+        assert!(parse_booking_id("Booking saved").is_err());
+        assert!(parse_booking_id("booking-").is_err());
+    }
+
+    #[test]
+    fn test_post_response_into_result() {
+        let parse = |raw: &str| serde_json::from_str::<PostResponse>(raw).unwrap().into_result();
+        assert_eq!(
+            parse(r#"{"error":false,"msg":"Booking cancelled"}"#).unwrap(),
+            "Booking cancelled"
+        );
+        let rejected =
+            parse(r#"{"error":true,"msg":"Bookings can't be cancelled within 24 hours"}"#);
+        assert_eq!(
+            rejected.unwrap_err().to_string(),
+            "Bookings can't be cancelled within 24 hours"
+        );
+        assert_eq!(parse_booking_id("booking-191730").unwrap(), "191730");
+    }
+
+    /// Answers `GET <url>` and `POST <url> <body>` with `route`, without any server
+    struct FakeTransport(fn(&str) -> String);
+    impl HttpTransport for FakeTransport {
+        async fn get_text(&self, url: &str) -> Result<String> {
+            Ok((self.0)(&format!("GET {url}")))
+        }
+        async fn post_form(&self, url: &str, body: &str) -> Result<String> {
+            Ok((self.0)(&format!("POST {url} {body}")))
+        }
+    }
+
+    fn fake_msg(html: &str) -> String {
+        serde_json::json!({ "error": false, "msg": html }).to_string()
+    }
+
+    /// The modal every form post first fetches its nonce from
+    fn nonce_form() -> String {
+        fake_msg(
+            r#"<form><input name="nonce" value="n"/><input name="nonce_key" value="k"/></form>"#,
+        )
+    }
+
+    fn tool(label: &str, id: &str) -> Tool {
+        Tool { label: label.into(), value: label.into(), text: label.into(), id: id.into() }
+    }
+
+    #[tokio::test]
+    async fn test_fake_transport_tool_bookings() {
+        fn route(request: &str) -> String {
+            if request.contains("load=modal.search-tool-bookings.php") {
+                nonce_form()
+            } else if request.starts_with(
+                "POST https://admin.nanofab.ualberta.ca/ajax.get-bookings.php tool_id[]=427&",
+            ) {
+                fake_msg(html::tests::TEST2)
+            } else {
+                panic!("Unexpected request `{request}`")
+            }
+        }
+        let client = NanoFab::with_transport(FakeTransport(route));
+        let tool = tool("Heidelberg MLA150", "427");
+        let bookings = client.get_tool_bookings(&tool, None, None).await.unwrap();
+        assert_eq!(bookings.timeslots().len(), 37);
+        let first = bookings.timeslots()[0].meta();
+        assert_eq!((first.id.as_str(), first.user_name.as_str()), ("191730", "Wyatt James"));
+    }
+
+    #[tokio::test]
+    async fn test_fake_transport_chunked_bookings() {
+        fn row(id: &str, start: &str, end: &str) -> String {
+            format!(
+                "<div id=\"booking-{id}\" class=\"table-row\"><div class=\"row\">\
+                <span title=\"{start}\"></span><span title=\"{end}\"></span>\
+                <span title=\"Wyatt James <br/> wjames@ualberta.ca\"></span></div></div>"
+            )
+        }
+        fn route(request: &str) -> String {
+            let overnight = || row("102", "10:00pm Mon Nov 14th", "2:00am Tue Nov 15th");
+            if request.contains("load=modal.search-tool-bookings.php") {
+                nonce_form()
+            } else if request.contains("&start_date=2022-11-01&end_date=2022-11-14&") {
+                fake_msg(&(row("101", "9:00am Tue Nov 1st", "10:00am Tue Nov 1st") + &overnight()))
+            } else if request.contains("&start_date=2022-11-15&end_date=2022-11-28&") {
+                fake_msg(
+                    &(overnight() + &row("103", "9:00am Mon Nov 28th", "11:00pm Mon Nov 28th")),
+                )
+            } else if request.contains("&start_date=2022-11-29&end_date=2022-12-10&") {
+                fake_msg(&row("104", "9:00am Fri Dec 9th", "10:00am Fri Dec 9th"))
+            } else {
+                panic!("Unexpected request `{request}`")
+            }
+        }
+        let client = NanoFab::with_transport(FakeTransport(route));
+        let tool = tool("Heidelberg MLA150", "427");
+        let date = |d| NaiveDate::from_ymd_opt(2022, 11, d).unwrap();
+        let end = NaiveDate::from_ymd_opt(2022, 12, 10);
+        let bookings = client.get_tool_bookings(&tool, Some(date(1)), end).await.unwrap();
+        let ids = bookings.iter().map(|ts| ts.meta().id.as_str()).collect_vec();
+        assert_eq!(ids, ["101", "102", "103", "104"]);
+    }
+
+    #[tokio::test]
+    async fn test_fake_transport_team_bookings() {
+        fn row(id: &str, email: &str) -> String {
+            format!(
+                "<div id=\"booking-{id}\" class=\"table-row\"><div class=\"row\">\
+                <span title=\"9:00am Thu Nov 24th\"></span><span title=\"10:00am Thu Nov 24th\"></span>\
+                <span title=\"Someone <br/> {email}\"></span></div></div>"
+            )
+        }
+        fn route(request: &str) -> String {
+            if request.starts_with("GET https://admin.nanofab.ualberta.ca/ajax.get-tools.php") {
+                r#"[{"label":"MLA150","value":"MLA150","text":"MLA150","id":"427"}]"#.into()
+            } else if request.contains("load=modal.search-tool-bookings.php") {
+                nonce_form()
+            } else if request.contains("ajax.get-bookings.php tool_id[]=427&") {
+                fake_msg(&(row("1", "WJames@ualberta.ca") + &row("2", "dmildenb@ualberta.ca")))
+            } else {
+                panic!("Unexpected request `{request}`")
+            }
+        }
+        let client = NanoFab::with_transport(FakeTransport(route));
+        let team =
+            Team { members: vec!["wjames@ualberta.ca".into()], tools: vec!["MLA150".into()] };
+        let bookings = client.get_team_bookings(&team, None).await.unwrap();
+        let ids = bookings.iter().map(|ts| ts.meta().id.as_str()).collect_vec();
+        assert_eq!(ids, ["1"]);
+    }
+
+    #[tokio::test]
+    async fn test_fake_transport_login_page() {
+        fn route(request: &str) -> String {
+            match request.starts_with("GET ") {
+                true => "<html><body>Please log in</body></html>".into(),
+                false => panic!("Unexpected request `{request}`"),
+            }
+        }
+        let client = NanoFab::with_transport(FakeTransport(route));
+        let err = client.get_tools(false).await.unwrap_err();
+        assert!(format!("{err:#}").contains("`Please log in`"));
+    }
+
+    fn fake_logged_in(route: fn(&str) -> String) -> NanoFab<FakeTransport> {
+        let client = NanoFab::with_transport(FakeTransport(route));
+        let login = Login { username: "user".into(), password: "pass".into() };
+        *client.login.lock().unwrap() = Some(login);
+        client
+    }
+
+    #[tokio::test]
+    async fn test_get_reauthenticates_on_login_page() {
+        static LOGGED_IN: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+        fn route(request: &str) -> String {
+            use std::sync::atomic::Ordering::SeqCst;
+            if request
+                .starts_with("POST https://admin.nanofab.ualberta.ca/ajax.login.php uname=user&")
+            {
+                LOGGED_IN.store(true, SeqCst);
+                fake_msg("")
+            } else if request.starts_with("GET ") && LOGGED_IN.load(SeqCst) {
+                r#"[{"label":"MLA150","value":"MLA150","text":"MLA150","id":"427"}]"#.into()
+            } else if request.starts_with("GET ") {
+                "<html><body>Please log in</body></html>".into()
+            } else {
+                panic!("Unexpected request `{request}`")
+            }
+        }
+        let tools = fake_logged_in(route).get_tools(true).await.unwrap();
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].id, "427");
+    }
+
+    #[tokio::test]
+    async fn test_get_does_not_reauthenticate_on_unexpected_json() {
+        fn route(request: &str) -> String {
+            match request.starts_with("GET ") {
+                true => r#"{"unexpected":true}"#.into(),
+                false => panic!("Unexpected request `{request}`"),
+            }
+        }
+        let err = fake_logged_in(route).get_tools(true).await.unwrap_err();
+        assert!(format!("{err:#}").contains("could not be parsed"));
+    }
+
+    #[tokio::test]
+    async fn test_fake_transport_create_booking() {
+        fn route(request: &str) -> String {
+            if request.contains("load=modal.tool-booking.php") {
+                nonce_form()
+            } else if request
+                .starts_with("POST https://admin.nanofab.ualberta.ca/ajax.book-tool.php ")
+            {
+                assert!(request.ends_with(
+                    " tool_id=427&project_id=2087&start_date=2022-11-24&start_time=09:00\
+                    &end_date=2022-11-24&end_time=13:30&nonce=n&nonce_key=k"
+                ));
+                fake_msg(r#"<tr id="booking-191730"></tr>"#)
+            } else {
+                panic!("Unexpected request `{request}`")
+            }
+        }
+        fn reject(request: &str) -> String {
+            match request.contains("ajax.book-tool.php") {
+                true => r#"{"error":true,"msg":"This time clashes with another booking"}"#.into(),
+                false => route(request),
+            }
+        }
+        let tool = tool("Heidelberg MLA150", "427");
+        let project = Project { name: "Waveguides".into(), id: "2087".into() };
+        let dt = |s| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap();
+        let (start, end) = (dt("2022-11-24 09:00"), dt("2022-11-24 13:30"));
+        let client = NanoFab::with_transport(FakeTransport(route));
+        assert_eq!(client.create_booking(&tool, &project, start, end).await.unwrap(), "191730");
+        let client = NanoFab::with_transport(FakeTransport(reject));
+        let err = client.create_booking(&tool, &project, start, end).await.unwrap_err();
+        assert!(format!("{err:#}").contains("This time clashes with another booking"));
+    }
+
+    #[tokio::test]
+    async fn test_fake_transport_cancel_booking() {
+        fn route(request: &str) -> String {
+            if request.contains("load=modal.user.bookings.php") {
+                nonce_form()
+            } else if request.starts_with(
+                "POST https://admin.nanofab.ualberta.ca/ajax.cancel-booking.php booking_id=191730&nonce=n&nonce_key=k",
+            ) {
+                fake_msg("Booking cancelled")
+            } else {
+                panic!("Unexpected request `{request}`")
+            }
+        }
+        fn reject(request: &str) -> String {
+            match request.contains("ajax.cancel-booking.php") {
+                true => {
+                    r#"{"error":true,"msg":"Bookings can't be cancelled within 24 hours"}"#.into()
+                }
+                false => route(request),
+            }
+        }
+        let client = NanoFab::with_transport(FakeTransport(route));
+        client.cancel_booking("booking-191730").await.unwrap();
+        let client = NanoFab::with_transport(FakeTransport(reject));
+        let err = client.cancel_booking("191730").await.unwrap_err();
+        assert!(format!("{err:#}").contains("Bookings can't be cancelled within 24 hours"));
+    }
+
+    #[tokio::test]
+    async fn test_with_base_url() {
+        fn route(request: &str) -> String {
+            assert_eq!(
+                request,
+                "GET http://127.0.0.1:8080/ajax.get-tools.php?term=&hide_inactive=1"
+            );
+            r#"[{"label":"MLA150","value":"MLA150","text":"MLA150","id":"412"}]"#.to_string()
+        }
+        let client =
+            NanoFab::with_transport(FakeTransport(route)).with_base_url("http://127.0.0.1:8080/");
+        let tools = client.get_tools(false).await.unwrap();
+        assert_eq!(tools[0].id, "412");
+    }
+
+    #[tokio::test]
+    async fn test_tool_cache() {
+        static SERVED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+        fn route(_: &str) -> String {
+            // Only the first request gets the tool list
+            match SERVED.swap(true, std::sync::atomic::Ordering::SeqCst) {
+                false => r#"[{"label":"MLA150","value":"MLA150","text":"MLA150","id":"412"}]"#,
+                true => "<html></html>",
+            }
+            .to_string()
+        }
+        let client = NanoFab::with_transport(FakeTransport(route));
+        client.get_tools(false).await.unwrap();
+        assert_eq!(client.get_tools(false).await.unwrap()[0].id, "412");
+        assert_eq!(client.get_tool_from_label("MLA150").await.unwrap().id, "412");
+        client.clear_tool_cache();
+        assert!(client.get_tools(false).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_get_html_error_page() {
+        fn route(_: &str) -> String {
+            "<html><head><title>503 Service Unavailable</title></head>\
+            <body><h1>Service Unavailable</h1></body></html>"
+                .to_string()
+        }
+        let client = NanoFab::with_transport(FakeTransport(route));
+        let err = client.get::<Vec<Tool>>("ajax.get-tools.php").await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Server did not answer with JSON: `503 Service Unavailable Service Unavailable`"
+        );
+        assert_eq!(body_snippet("  "), "(empty response)");
+        assert!(body_snippet(&"x".repeat(500)).ends_with("...`"));
+    }
+
+    #[tokio::test]
+    async fn test_post_session_expired() {
+        fn route(_: &str) -> String {
+            "<html><body>Please log in</body></html>".to_string()
+        }
+        let client = NanoFab::with_transport(FakeTransport(route));
+        let err = client.cancel_booking("123").await.unwrap_err();
+        assert!(err.downcast_ref::<SessionExpired>().is_some());
+    }
+
+    // The tests below go through a real `ReqwestTransport` on a local port, since what they
+    // check lives there: the cookie jar, and requests actually running concurrently
+
+    /// Answer one request on a local port with `body`, returning the base URL to
+    /// point a client at and the request line it received
+    async fn serve_once(body: &'static str) -> (String, tokio::task::JoinHandle<String>) {
+        let (base_url, server) = serve(vec![body]).await;
+        (base_url, tokio::spawn(async { server.await.unwrap().remove(0) }))
+    }
+
+    /// Answer one request with each of `bodies` in turn, returning the raw requests
+    async fn serve(bodies: Vec<&'static str>) -> (String, tokio::task::JoinHandle<Vec<String>>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let mut requests = vec![];
+            for body in bodies {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                requests.push(read_request(&mut stream).await);
+                respond(&mut stream, body).await;
+            }
+            requests
+        });
+        (base_url, server)
+    }
+
+    /// Answer every request concurrently with `route(request)` after a short delay,
+    /// keeping track of the most requests in flight at once
+    async fn serve_routes(
+        route: fn(&str) -> String,
+    ) -> (String, std::sync::Arc<std::sync::atomic::AtomicUsize>) {
+        use std::sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        };
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+        let max = max_in_flight.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let (in_flight, max) = (in_flight.clone(), max.clone());
+                tokio::spawn(async move {
+                    let request = read_request(&mut stream).await;
+                    let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    max.fetch_max(now, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                    respond(&mut stream, &route(&request)).await;
+                });
+            }
+        });
+        (base_url, max_in_flight)
+    }
+
+    /// Read a whole request, headers and body
+    async fn read_request(stream: &mut tokio::net::TcpStream) -> String {
+        use tokio::io::AsyncReadExt;
+
+        let mut request = vec![];
+        let mut buf = vec![0; 4096];
+        loop {
+            let len = stream.read(&mut buf).await.unwrap();
+            request.extend_from_slice(&buf[..len]);
+            let text = String::from_utf8_lossy(&request).to_string();
+            let Some((head, body)) = text.split_once("\r\n\r\n") else { continue };
+            let content_length = head
+                .lines()
+                .find_map(|line| line.to_lowercase().strip_prefix("content-length: ")?.parse().ok())
+                .unwrap_or(0);
+            if len == 0 || body.len() >= content_length {
+                return text;
+            }
+        }
+    }
+
+    async fn respond(stream: &mut tokio::net::TcpStream, body: &str) {
+        use tokio::io::AsyncWriteExt;
+
+        let resp = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+            Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        );
+        stream.write_all(resp.as_bytes()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_get_user_bookings_concurrently() {
+        // Tool `n` is `TOOLn` with id `n`, booked at `8+n` o'clock
+        fn date() -> NaiveDate {
+            lab_now().date()
+        }
+        fn at(hour: u32) -> NaiveDateTime {
+            date().and_hms_opt(hour, 0, 0).unwrap()
+        }
+        fn msg(html: String) -> String {
+            serde_json::json!({ "error": false, "msg": html }).to_string()
+        }
+        fn route(request: &str) -> String {
+            let field = |name: &str| {
+                let (_, rest) = request.split_once(name)?;
+                Some(rest.split(['&', ' ']).next().unwrap().to_string())
+            };
+            if request.contains("load=modal.user.bookings.php") {
+                msg((1..=3)
+                    .map(|n| {
+                        let time = at(8 + n).format("%b %-d @ %-I:%M %P");
+                        format!(
+                            "<div id=\"booking-{n}\"><div class=\"columns small-6\">TOOL{n}</div>\
+                            <div class=\"columns small-6\">{time}</div></div>"
+                        )
+                    })
+                    .join(""))
+            } else if request.contains("load=modal.search-tool-bookings.php") {
+                nonce_form()
+            } else if let Some(label) = field("term=") {
+                let id = label.trim_start_matches("TOOL");
+                format!(r#"[{{"label":"{label}","value":"{label}","text":"{label}","id":"{id}"}}]"#)
+            } else {
+                let n = field("tool_id[]=").unwrap().parse::<u32>().unwrap();
+                let time = |dt: NaiveDateTime| dt.format("%-I:%M%P %a %b %-d").to_string();
+                msg(format!(
+                    "<div id=\"booking-{n}\"><span title=\"{}\"></span><span title=\"{}\"></span>\
+                    <span title=\"User {n} <br/> user{n}@ualberta.ca\"></span></div>",
+                    time(at(8 + n)),
+                    time(at(9 + n)),
+                ))
+            }
+        }
+        let (base_url, max_in_flight) = serve_routes(route).await;
+        let client = NanoFab::new().with_base_url(base_url);
+        let bookings = client.get_user_bookings().await.unwrap();
+        let found = bookings.iter().map(|ts| (ts.start().unwrap(), ts.meta().user_email.as_str()));
+        assert_eq!(
+            found.collect_vec(),
+            [
+                (at(9), "user1@ualberta.ca"),
+                (at(10), "user2@ualberta.ca"),
+                (at(11), "user3@ualberta.ca")
+            ]
+        );
+        assert!(max_in_flight.load(std::sync::atomic::Ordering::SeqCst) > 1);
+    }
+
+    #[tokio::test]
+    async fn test_load_cookies() {
+        let dir = std::env::temp_dir().join(format!("nanofab-cli-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("cookies.txt");
+        std::fs::write(&path, "PHPSESSID=abc123\n").unwrap();
+        let (base_url, server) = serve_once("[]").await;
+        let client = NanoFab::new().with_base_url(base_url);
+        assert!(client.load_cookies(&path).unwrap());
+        client.get_tools(false).await.unwrap();
+        let request = server.await.unwrap();
+        assert!(request.lines().any(|line| line.eq_ignore_ascii_case("cookie: PHPSESSID=abc123")));
+        client.save_cookies(&path).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "PHPSESSID=abc123");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(!client.load_cookies(&path).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_logout() {
+        let dir = std::env::temp_dir().join(format!("nanofab-cli-logout-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("cookies.txt");
+        std::fs::write(&path, "PHPSESSID=abc123").unwrap();
+        let (base_url, server) = serve(vec!["<html></html>", "<html></html>"]).await;
+        let client = NanoFab::new().with_base_url(base_url);
+        client.load_cookies(&path).unwrap();
+        client.resume(&Login { username: "user".into(), password: "pass".into() });
+        client.logout().await.unwrap();
+        let err = client.post("ajax.load-modal.php", [("load", "modal.user.bookings.php")]);
+        assert!(err.await.unwrap_err().downcast_ref::<SessionExpired>().is_some());
+        let requests = server.await.unwrap();
+        assert!(requests[0].starts_with("GET /logout.php "));
+        assert!(requests[0].to_lowercase().contains("cookie: phpsessid=abc123"));
+        assert!(!requests[1].to_lowercase().contains("cookie:"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_parse_clock_time_cell_spacing() {
+        // Table cells in the bookings page are padded like this, in 24 hour time
+        let hm = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+        assert_eq!(parse_clock_time(" 6:00").unwrap(), hm(6, 0));
+        assert_eq!(parse_clock_time(" 9:00 ").unwrap(), hm(9, 0));
+        assert_eq!(parse_clock_time(" 13:00 ").unwrap(), hm(13, 0));
+        assert_eq!(parse_clock_time("1:00pm").unwrap(), hm(13, 0));
+        assert_eq!(parse_clock_time(" 11:10 AM ").unwrap(), hm(11, 10));
+        assert!(parse_clock_time(" wjames ").is_err());
+    }
+
+    #[test]
+    fn test_parse_booking_time_spacing() {
+        for title in ["1:00pm Thu Nov 24th", " 1:00pm  Thu Nov 24th ", "\t1:00pm Thu\nNov 24"] {
+            let dt = parse_booking_time(title).unwrap();
+            assert_eq!((dt.month(), dt.day()), (11, 24), "{title:?}");
+            assert_eq!(dt.time(), NaiveTime::from_hms_opt(13, 0, 0).unwrap(), "{title:?}");
+        }
+        assert!(parse_booking_time("6:00am").is_err());
+    }
+
+    #[test]
+    fn test_parse_yearless_past_year() {
+        let dt = |s| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap();
+        // A late-December booking seen from January belongs to the previous year
+        let time_fmt = "%-I:%M%P %a %b %-d";
+        let parsed = parse_yearless_near("6:00am Wed Dec 28", time_fmt, dt("2023-01-03 12:00"));
+        assert_eq!(parsed.unwrap(), dt("2022-12-28 06:00"));
+        let parsed = parse_yearless_near("9:30pm Mon Jan 2", time_fmt, dt("2022-12-20 12:00"));
+        assert_eq!(parsed.unwrap(), dt("2023-01-02 21:30"));
+    }
+
+    #[test]
+    fn test_parse_yearless_closest_year() {
+        let dt = |s| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap();
+        // No weekday, so every year parses and only closeness can decide
+        let fmt = "%b %-d @ %-I:%M %P";
+        let parsed = parse_yearless_near("Dec 30 @ 4:00 pm", fmt, dt("2023-01-02 09:00"));
+        assert_eq!(parsed.unwrap(), dt("2022-12-30 16:00"));
+        let parsed = parse_yearless_near("Jan 2 @ 9:00 am", fmt, dt("2022-12-30 09:00"));
+        assert_eq!(parsed.unwrap(), dt("2023-01-02 09:00"));
+        let parsed = parse_yearless_near("Sep 1 @ 9:00 am", fmt, dt("2022-12-30 09:00"));
+        assert_eq!(parsed.unwrap(), dt("2022-09-01 09:00"));
+        // Leap day
+        let parsed = parse_yearless_near("Feb 29 @ 9:00 am", fmt, dt("2023-03-01 09:00"));
+        assert_eq!(parsed.unwrap(), dt("2024-02-29 09:00"));
+        let parsed = parse_yearless_near("Feb 29 @ 9:00 am", fmt, dt("2022-01-10 09:00"));
+        assert_eq!(parsed.unwrap(), dt("2020-02-29 09:00"));
+        assert!(parse_yearless_near("Feb 30 @ 9:00 am", fmt, dt("2022-01-10 09:00")).is_err());
+        assert!(parse_yearless_near("Feb 3 @ 9:00", fmt, dt("2022-01-10 09:00")).is_err());
+    }
+}
@@ -1,9 +1,16 @@
 use std::fmt::Display;
 
-use chrono::{Datelike, Days, Duration, NaiveDateTime, Weekday};
+use chrono::{
+    Datelike, Days, Duration, LocalResult, NaiveDate, NaiveDateTime, NaiveTime, Offset, TimeZone,
+    Weekday,
+};
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone)]
+/// The days `subtract_weekends` usually removes
+pub const WEEKEND: [Weekday; 2] = [Weekday::Sat, Weekday::Sun];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TimeSlot<M> {
     start: Option<NaiveDateTime>,
     end: Option<NaiveDateTime>,
@@ -19,6 +26,9 @@ impl<M> TimeSlot<M> {
     pub fn end(&self) -> &Option<NaiveDateTime> {
         &self.end
     }
+    pub fn meta(&self) -> &M {
+        &self.meta
+    }
     pub fn add_days(&mut self, days: u64) {
         if let Some(dt) = self.start.as_mut() {
             *dt = dt.checked_add_days(Days::new(days)).unwrap()
@@ -78,18 +88,249 @@ pub enum RelTime {
     After,
 }
 
+/// A free slot with the slots either side of it, from `TimeTable::gaps_with_neighbors`
+pub type Gap<'a, M> = (
+    Option<&'a TimeSlot<M>>,
+    TimeSlot<()>,
+    Option<&'a TimeSlot<M>>,
+);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TimeTable<M> {
     timeslots: Vec<TimeSlot<M>>,
 }
 impl<M> TimeTable<M> {
+    /// Build a table from slots in any order, sorting them by start
     pub fn new(timeslots: impl IntoIterator<Item = TimeSlot<M>>) -> Self {
-        Self {
-            timeslots: timeslots.into_iter().collect(),
+        let mut timeslots = timeslots.into_iter().collect_vec();
+        timeslots.sort_by_key(|ts| ts.start);
+        Self { timeslots }
+    }
+    /// Build a table from untrusted slots, checking that they are sorted,
+    /// disjoint and of non-zero width, and only unbounded at the ends
+    pub fn try_new(
+        timeslots: impl IntoIterator<Item = TimeSlot<M>>,
+    ) -> Result<Self, TimeTableError> {
+        let timeslots = timeslots.into_iter().collect_vec();
+        let last = timeslots.len().saturating_sub(1);
+        for (index, ts) in timeslots.iter().enumerate() {
+            if let (Some(start), Some(end)) = (ts.start, ts.end) {
+                if start >= end {
+                    return Err(TimeTableError::Empty { index });
+                }
+            }
+            if (ts.start.is_none() && index != 0) || (ts.end.is_none() && index != last) {
+                return Err(TimeTableError::UnboundedInside { index });
+            }
+        }
+        for (index, (prev, ts)) in timeslots.iter().tuple_windows().enumerate() {
+            let index = index + 1;
+            if ts.start < prev.start {
+                return Err(TimeTableError::Unsorted { index });
+            }
+            if ts.start < prev.end {
+                return Err(TimeTableError::Overlap { index });
+            }
+        }
+        Ok(Self { timeslots })
+    }
+    /// Build a table from arbitrary slots by dropping empty ones, sorting the
+    /// rest and merging any that overlap, keeping the earlier slot's metadata
+    pub fn normalized(timeslots: impl IntoIterator<Item = TimeSlot<M>>) -> Self {
+        let timeslots = timeslots
+            .into_iter()
+            .filter(|ts| match (ts.start, ts.end) {
+                (Some(start), Some(end)) => start < end,
+                _ => true,
+            })
+            .collect();
+        let mut table = Self { timeslots };
+        table.merge_reaching(false, |prev, _| prev);
+        table
+    }
+    /// Sort the slots and coalesce any that overlap or touch, combining their metadata with
+    /// `combine`. This restores the sorted and disjoint layout `inverted` relies on.
+    pub fn merge_overlaps(&mut self, combine: impl FnMut(M, M) -> M) {
+        self.merge_reaching(true, combine);
+    }
+    /// Sort the slots and coalesce any that overlap, and those that touch too if `touching`
+    fn merge_reaching(&mut self, touching: bool, mut combine: impl FnMut(M, M) -> M) {
+        self.timeslots.sort_by_key(|ts| ts.start);
+        let mut merged: Vec<TimeSlot<M>> = Vec::with_capacity(self.timeslots.len());
+        for ts in self.timeslots.drain(..) {
+            let Some(prev) = merged.pop() else {
+                merged.push(ts);
+                continue;
+            };
+            // `None` is -infinity as a start and +infinity as an end
+            let reaches = match (prev.end, ts.start) {
+                (Some(prev_end), Some(start)) => {
+                    start < prev_end || (touching && start == prev_end)
+                }
+                _ => true,
+            };
+            if !reaches {
+                merged.extend([prev, ts]);
+                continue;
+            }
+            let end = match (prev.end, ts.end) {
+                (Some(a), Some(b)) => Some(a.max(b)),
+                _ => None,
+            };
+            merged.push(TimeSlot::new(prev.start, end, combine(prev.meta, ts.meta)));
+        }
+        self.timeslots = merged;
+    }
+    /// The intervals covered by a slot in both tables. Both tables should be
+    /// sorted and disjoint, like the output of `inverted`.
+    pub fn intersect<MO>(&self, other: &TimeTable<MO>) -> TimeTable<()> {
+        let (a, b) = (&self.timeslots, &other.timeslots);
+        let mut timeslots = vec![];
+        let (mut i, mut j) = (0, 0);
+        while i < a.len() && j < b.len() {
+            // `None` orders before `Some`, so the later start is just the max
+            let start = a[i].start.max(b[j].start);
+            let end = match (a[i].end, b[j].end) {
+                (Some(x), Some(y)) => Some(x.min(y)),
+                (Some(x), None) | (None, Some(x)) => Some(x),
+                (None, None) => None,
+            };
+            let non_empty = match (start, end) {
+                (Some(start), Some(end)) => start < end,
+                _ => true,
+            };
+            if non_empty {
+                timeslots.push(TimeSlot::new(start, end, ()));
+            }
+            // Step past whichever slot finishes first
+            let a_first = match (a[i].end, b[j].end) {
+                (Some(x), Some(y)) => x <= y,
+                (Some(_), None) => true,
+                (None, _) => false,
+            };
+            match a_first {
+                true => i += 1,
+                false => j += 1,
+            }
+        }
+        TimeTable::new(timeslots)
+    }
+    /// The longest slot, counting unbounded slots as longer than any bounded one.
+    /// Ties go to the earliest.
+    pub fn largest_opening(&self) -> Option<&TimeSlot<M>> {
+        self.timeslots
+            .iter()
+            .rev()
+            .max_by_key(|ts| (ts.duration().is_none(), ts.duration()))
+    }
+    /// The earliest slot lasting at least `min`. Unbounded slots always qualify.
+    pub fn first_opening_at_least(&self, min: Duration) -> Option<&TimeSlot<M>> {
+        self.timeslots
+            .iter()
+            .filter(|ts| ts.duration().is_none_or(|duration| duration >= min))
+            .min_by_key(|ts| ts.start)
+    }
+    /// Bound every slot to `start..end`, filling in unbounded edges and dropping
+    /// slots that fall outside it
+    pub fn clip_to(&mut self, start: NaiveDateTime, end: NaiveDateTime) {
+        for ts in self.timeslots.iter_mut() {
+            ts.start = Some(ts.start.map_or(start, |dt| dt.max(start)));
+            ts.end = Some(ts.end.map_or(end, |dt| dt.min(end)));
+        }
+        self.timeslots.retain(|ts| ts.start < ts.end);
+    }
+    /// The fraction of `window` covered by at least one slot. Overlapping slots count once.
+    /// Unbounded window ends are taken from the earliest and latest slot times.
+    pub fn utilization(&self, window: &TimeSlot<()>) -> f64 {
+        let extent_start = || {
+            self.timeslots
+                .iter()
+                .filter_map(|ts| ts.start.or(ts.end))
+                .min()
+        };
+        let extent_end = || {
+            self.timeslots
+                .iter()
+                .filter_map(|ts| ts.end.or(ts.start))
+                .max()
+        };
+        let (Some(window_start), Some(window_end)) = (
+            window.start.or_else(extent_start),
+            window.end.or_else(extent_end),
+        ) else {
+            return 0.0;
+        };
+        if window_start >= window_end {
+            return 0.0;
+        }
+        let mut clipped = TimeTable::new(self.timeslots.iter().filter_map(|ts| {
+            let start = ts.start.unwrap_or(window_start).max(window_start);
+            let end = ts.end.unwrap_or(window_end).min(window_end);
+            (start < end).then(|| TimeSlot::new(Some(start), Some(end), ()))
+        }));
+        clipped.merge_overlaps(|_, _| ());
+        let busy: Duration = clipped.iter().filter_map(|ts| ts.duration()).sum();
+        busy.num_seconds() as f64 / (window_end - window_start).num_seconds() as f64
+    }
+    /// An iCalendar file with one event per bounded slot of a table kept in `tz`, with
+    /// times written in UTC. Each event is summarized as `summary_prefix` followed by how
+    /// long the slot lasts.
+    pub fn to_ical(&self, tz: &impl TimeZone, summary_prefix: &str) -> String {
+        let ical_time = |dt: NaiveDateTime| dt.format("%Y%m%dT%H%M%S").to_string();
+        let utc_time = |dt: NaiveDateTime| ical_time(shift_zone(dt, tz, &chrono::Utc)) + "Z";
+        let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%SZ");
+        let mut lines = vec![
+            "BEGIN:VCALENDAR".to_string(),
+            "VERSION:2.0".into(),
+            "PRODID:-//nanofab-cli//EN".into(),
+        ];
+        for ts in &self.timeslots {
+            let (Some(start), Some(end)) = (ts.start, ts.end) else {
+                continue;
+            };
+            let summary = format!("{summary_prefix} ({})", hours_minutes(end - start));
+            lines.extend([
+                "BEGIN:VEVENT".to_string(),
+                format!("UID:{}-{}@nanofab-cli", ical_time(start), ical_time(end)),
+                format!("DTSTAMP:{stamp}"),
+                format!("DTSTART:{}", utc_time(start)),
+                format!("DTEND:{}", utc_time(end)),
+                format!("SUMMARY:{}", ical_escape(&summary)),
+                "TRANSP:TRANSPARENT".into(),
+                "END:VEVENT".into(),
+            ]);
         }
+        lines.push("END:VCALENDAR".into());
+        lines.iter().map(|line| ical_fold(line)).collect()
+    }
+    /// Merge back-to-back slots with equal metadata into one
+    pub fn coalesce_adjacent(&mut self)
+    where
+        M: PartialEq,
+    {
+        self.coalesce_adjacent_by(|a, b| a == b)
+    }
+    /// Merge back-to-back slots whose metadata `same` says match, keeping the first's
+    pub fn coalesce_adjacent_by(&mut self, same: impl Fn(&M, &M) -> bool) {
+        let mut coalesced: Vec<TimeSlot<M>> = Vec::with_capacity(self.timeslots.len());
+        for ts in self.timeslots.drain(..) {
+            match coalesced.last_mut() {
+                Some(prev)
+                    if prev.end.is_some() && prev.end == ts.start && same(&prev.meta, &ts.meta) =>
+                {
+                    prev.end = ts.end
+                }
+                _ => coalesced.push(ts),
+            }
+        }
+        self.timeslots = coalesced;
     }
     pub fn timeslots(&self) -> &Vec<TimeSlot<M>> {
         &self.timeslots
     }
+    pub fn iter(&self) -> std::slice::Iter<'_, TimeSlot<M>> {
+        self.timeslots.iter()
+    }
     pub fn subtract_less_duration(&mut self, duration: Duration) {
         self.timeslots = self
             .timeslots
@@ -100,82 +341,194 @@ impl<M> TimeTable<M> {
             })
             .collect();
     }
-    pub fn inverted(self) -> TimeTable<()> {
-        match &self.timeslots[..] {
-            [] => return TimeTable::new([TimeSlot::new(None, None, ())]),
-            [ts] if ts.start().is_none() & ts.end().is_none() => return TimeTable::new([]),
-            _ => {}
+    /// Keep only the first `len` slots, returning how many were dropped
+    pub fn truncate(&mut self, len: usize) -> usize {
+        let dropped = self.timeslots.len().saturating_sub(len);
+        self.timeslots.truncate(len);
+        dropped
+    }
+    /// One line per bounded slot, labeled by `label`, under a header line for each day.
+    /// Header lines are paired with `None` and slot lines with the slot's metadata.
+    pub fn labeled_lines(&self, label: impl Fn(&M) -> String) -> Vec<(String, Option<&M>)> {
+        let mut lines = vec![];
+        let mut prev_date = None;
+        for ts in &self.timeslots {
+            let (Some(start), Some(end)) = (ts.start, ts.end) else {
+                continue;
+            };
+            if prev_date != Some(start.date()) {
+                prev_date = Some(start.date());
+                lines.push((day_header(start.date()), None));
+            }
+            let times = format!("{} - {}", start.format("%l:%M%P"), end.format("%l:%M%P"));
+            lines.push((format!("{times}  {}", label(&ts.meta)), Some(&ts.meta)));
+        }
+        lines
+    }
+    /// One `start<TAB>end<TAB>duration_minutes` line per slot, with ISO 8601
+    /// datetimes and empty fields where a slot is unbounded
+    pub fn to_tsv(&self) -> String {
+        let iso = |dt: &Option<NaiveDateTime>| match dt {
+            Some(dt) => dt.format("%Y-%m-%dT%H:%M:%S").to_string(),
+            None => String::new(),
+        };
+        self.timeslots
+            .iter()
+            .map(|ts| {
+                let minutes = match ts.duration() {
+                    Some(dur) => dur.num_minutes().to_string(),
+                    None => String::new(),
+                };
+                format!("{}\t{}\t{minutes}\n", iso(&ts.start), iso(&ts.end))
+            })
+            .collect()
+    }
+    pub fn shrink_slots(&mut self, lead: Duration, trail: Duration) {
+        for ts in self.timeslots.iter_mut() {
+            if let Some(start) = ts.start.as_mut() {
+                *start += lead;
+            }
+            if let Some(end) = ts.end.as_mut() {
+                *end -= trail;
+            }
         }
-        let mut new_timeslots = vec![];
-        if let Some(dt) = self.timeslots.first().unwrap().start() {
-            new_timeslots.push(TimeSlot::new(None, Some(*dt), ()))
+        self.timeslots.retain(|ts| match (ts.start, ts.end) {
+            (Some(start), Some(end)) => start < end,
+            _ => true,
+        });
+    }
+    pub fn inverted(self) -> TimeTable<()> {
+        debug_assert!(
+            self.timeslots
+                .iter()
+                .tuple_windows()
+                .all(|(a, b)| match (a.end, b.start) {
+                    (Some(end), Some(start)) => end <= start,
+                    _ => false,
+                }),
+            "Inverting overlapping slots, use `merge_overlaps` first"
+        );
+        TimeTable::new(
+            self.gaps_with_neighbors()
+                .into_iter()
+                .map(|(_, gap, _)| gap),
+        )
+    }
+    /// Each gap `inverted` would return, between the slot before it and the slot after it.
+    /// Leading and trailing gaps have `None` on their open side.
+    pub fn gaps_with_neighbors(&self) -> Vec<Gap<'_, M>> {
+        let (Some(first), Some(last)) = (self.timeslots.first(), self.timeslots.last()) else {
+            return vec![(None, TimeSlot::new(None, None, ()), None)];
+        };
+        let mut gaps = vec![];
+        if let Some(dt) = first.start {
+            gaps.push((None, TimeSlot::new(None, Some(dt), ()), Some(first)))
         }
         for (a, b) in self.timeslots.iter().tuple_windows() {
-            if a.end() != b.start() {
-                new_timeslots.push(TimeSlot::new(*a.end(), *b.start(), ()))
+            if a.end != b.start {
+                gaps.push((Some(a), TimeSlot::new(a.end, b.start, ()), Some(b)))
             }
         }
-        if let Some(dt) = self.timeslots.last().unwrap().end() {
-            new_timeslots.push(TimeSlot::new(Some(*dt), None, ()))
+        if let Some(dt) = last.end {
+            gaps.push((Some(last), TimeSlot::new(Some(dt), None, ()), None))
         }
-        TimeTable::new(new_timeslots)
+        gaps
     }
-    pub fn subtract_before_now(&mut self)
+    /// `subtract_before` the current wall-clock time in `tz`
+    pub fn subtract_before_now(&mut self, tz: &impl TimeZone)
     where
         M: Clone,
     {
-        let now = chrono::Local::now().naive_local();
-        let before_now = TimeSlot::new(None, Some(now), ());
-        self.subtract_timeslot(&before_now);
+        self.subtract_before(chrono::Utc::now().with_timezone(tz).naive_local());
     }
-    pub fn subtract_weekends(&mut self)
+    pub fn subtract_before(&mut self, time: NaiveDateTime)
     where
         M: Clone,
     {
-        let last_time = match self.timeslots.last().unwrap().end {
-            Some(dt) => dt,
-            None => self
-                .timeslots
-                .last()
-                .unwrap()
-                .start
-                .expect("Should be no unbounded slots inside timetable"),
+        self.subtract_timeslot(&TimeSlot::new(None, Some(time), ()));
+    }
+    /// Reread the wall-clock times of a table kept in `from` as times in `to`
+    pub fn to_zone(self, from: &impl TimeZone, to: &impl TimeZone) -> Self {
+        let shift = |dt: Option<NaiveDateTime>| dt.map(|dt| shift_zone(dt, from, to));
+        let timeslots = self
+            .timeslots
+            .into_iter()
+            .map(|ts| TimeSlot::new(shift(ts.start), shift(ts.end), ts.meta));
+        TimeTable::new(timeslots)
+    }
+    /// `to_zone` the user's own timezone, for display
+    pub fn to_local(self, from: &impl TimeZone) -> Self {
+        self.to_zone(from, &chrono::Local)
+    }
+    /// Remove every day that falls on one of `days`, midnight to midnight, across the span
+    /// of the table. Pass `WEEKEND` for the usual Saturday and Sunday.
+    pub fn subtract_weekends(&mut self, days: &[Weekday])
+    where
+        M: Clone,
+    {
+        if days.is_empty() {
+            return;
+        }
+        let (Some(first_time), Some(last_time)) =
+            (self.first_bounded_time(), self.last_bounded_time())
+        else {
+            return;
         };
-        let now = chrono::Local::now().naive_local();
-        let mut saturday_morning = now.date().and_hms_opt(0, 0, 0).unwrap();
-        while saturday_morning.weekday() != Weekday::Sat {
-            saturday_morning = saturday_morning.checked_sub_days(Days::new(1)).unwrap();
+        let first_date = first_time.date();
+        let midnight = first_date
+            .and_hms_opt(0, 0, 0)
+            .expect("Midnight should be valid");
+        let mut day = TimeSlot::new(Some(midnight), midnight.checked_add_days(Days::new(1)), ());
+        while day.start.unwrap() <= last_time {
+            if days.contains(&day.start.unwrap().weekday()) {
+                self.subtract_timeslot(&day);
+            }
+            day.add_days(1);
         }
-        let monday_morning = saturday_morning.checked_add_days(Days::new(2)).unwrap();
-        let mut weekend = TimeSlot::new(Some(saturday_morning), Some(monday_morning), ());
-        while weekend.start.unwrap() <= last_time {
-            self.subtract_timeslot(&weekend);
-            weekend.add_days(7);
+    }
+    /// Remove each of `dates`, midnight to midnight
+    pub fn subtract_holidays(&mut self, dates: &[NaiveDate])
+    where
+        M: Clone,
+    {
+        for date in dates {
+            let midnight = date.and_hms_opt(0, 0, 0).expect("Midnight should be valid");
+            let holiday =
+                TimeSlot::new(Some(midnight), midnight.checked_add_days(Days::new(1)), ());
+            self.subtract_timeslot(&holiday);
         }
     }
-    pub fn subtract_after_hours(&mut self)
+    /// `subtract_after_hours` with the usual 8am to 5pm working day
+    pub fn subtract_default_after_hours(&mut self)
+    where
+        M: Clone,
+    {
+        let open = NaiveTime::from_hms_opt(8, 0, 0).expect("Opening time should be valid");
+        let close = NaiveTime::from_hms_opt(17, 0, 0).expect("Closing time should be valid");
+        self.subtract_after_hours(open, close);
+    }
+    /// Remove the time between `close` each day and `open` the next. A `close`
+    /// before `open` is read as an overnight shift, and equal times as always open.
+    pub fn subtract_after_hours(&mut self, open: NaiveTime, close: NaiveTime)
     where
         M: Clone,
     {
-        let last_time = match self.timeslots.last().unwrap().end {
-            Some(dt) => dt,
-            None => self
-                .timeslots
-                .last()
-                .unwrap()
-                .start
-                .expect("Should be no unbounded slots inside timetable"),
+        if open == close {
+            return;
+        }
+        let (Some(first_time), Some(last_time)) =
+            (self.first_bounded_time(), self.last_bounded_time())
+        else {
+            return;
         };
-        let now = chrono::Local::now().naive_local();
-        let today = now.date();
-        let day_end = today
-            .and_hms_opt(17, 0, 0)
-            .expect("Creating day start should not fail");
-        let next_day_start = today
-            .and_hms_opt(8, 0, 0)
-            .expect("Creating day start should not fail")
-            .checked_add_days(Days::new(1))
-            .expect("adding days should not fail");
+        let first_date = first_time.date();
+        let day_end = first_date.and_time(close);
+        let mut next_day_start = first_date.and_time(open);
+        if open < close {
+            next_day_start = next_day_start
+                .checked_add_days(Days::new(1))
+                .expect("adding days should not fail");
+        }
         let mut overnight = TimeSlot::new(Some(day_end), Some(next_day_start), ());
         overnight.sub_days(2);
         while overnight.start.unwrap() <= last_time {
@@ -183,6 +536,43 @@ impl<M> TimeTable<M> {
             overnight.add_days(1);
         }
     }
+    /// Clip every day's slots so none start before `time`, using the same
+    /// day-by-day subtraction as `subtract_after_hours`
+    pub fn subtract_before_time_of_day(&mut self, time: NaiveTime)
+    where
+        M: Clone,
+    {
+        let midnight = NaiveTime::from_hms_opt(0, 0, 0).expect("Midnight should be a valid time");
+        if time == midnight {
+            return;
+        }
+        let (Some(first_time), Some(last_time)) =
+            (self.first_bounded_time(), self.last_bounded_time())
+        else {
+            return;
+        };
+        let first_date = first_time.date();
+        let mut early_morning = TimeSlot::new(
+            Some(first_date.and_time(midnight)),
+            Some(first_date.and_time(time)),
+            (),
+        );
+        early_morning.sub_days(1);
+        while early_morning.start.unwrap() <= last_time {
+            self.subtract_timeslot(&early_morning);
+            early_morning.add_days(1);
+        }
+    }
+    /// Where the day-by-day walks start, `None` for an empty or fully unbounded table
+    fn first_bounded_time(&self) -> Option<NaiveDateTime> {
+        let first = self.timeslots.first()?;
+        first.start.or(first.end)
+    }
+    /// Where the day-by-day walks stop, `None` for an empty or fully unbounded table
+    fn last_bounded_time(&self) -> Option<NaiveDateTime> {
+        let last = self.timeslots.last()?;
+        last.end.or(last.start)
+    }
     pub fn subtract_timeslot<MO>(&mut self, timeslot: &TimeSlot<MO>)
     where
         M: Clone,
@@ -218,7 +608,6 @@ impl<M> TimeTable<M> {
                             let current = self.timeslots.remove(i);
                             let new_start = current.start;
                             let new_end = Some(other_start);
-                            self.timeslots.remove(i);
                             if new_end != new_start {
                                 self.timeslots
                                     .insert(i, TimeSlot::new(new_start, new_end, current.meta));
@@ -281,52 +670,1189 @@ impl<M> TimeTable<M> {
         }
     }
 }
-impl<M> Display for TimeTable<M> {
+impl<M> IntoIterator for TimeTable<M> {
+    type Item = TimeSlot<M>;
+    type IntoIter = std::vec::IntoIter<TimeSlot<M>>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.timeslots.into_iter()
+    }
+}
+impl<'a, M> IntoIterator for &'a TimeTable<M> {
+    type Item = &'a TimeSlot<M>;
+    type IntoIter = std::slice::Iter<'a, TimeSlot<M>>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+/// A broken invariant found by `TimeTable::try_new`, naming the offending slot by index
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TimeTableError {
+    /// The slot ends at or before its start
+    Empty { index: usize },
+    /// An unbounded start anywhere but the first slot, or end anywhere but the last
+    UnboundedInside { index: usize },
+    /// The slot starts before the one ahead of it
+    Unsorted { index: usize },
+    /// The slot starts before the one ahead of it ends
+    Overlap { index: usize },
+}
+impl Display for TimeTableError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut prev_date = match self.timeslots.get(0) {
-            Some(ts) => match (ts.start(), ts.end()) {
+        match self {
+            TimeTableError::Empty { index } => {
+                write!(f, "Timeslot {index} does not end after it starts")
+            }
+            TimeTableError::UnboundedInside { index } => {
+                write!(
+                    f,
+                    "Timeslot {index} is unbounded but not at the edge of the timetable"
+                )
+            }
+            TimeTableError::Unsorted { index } => {
+                write!(f, "Timeslot {index} starts before the timeslot ahead of it")
+            }
+            TimeTableError::Overlap { index } => {
+                write!(f, "Timeslot {index} overlaps the timeslot ahead of it")
+            }
+        }
+    }
+}
+impl std::error::Error for TimeTableError {}
+
+/// Backslash-escape the characters RFC 5545 reserves in text values
+fn ical_escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// A content line ending in CRLF, folded so no line is over 75 bytes as RFC 5545 asks
+fn ical_fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            folded += "\r\n ";
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded + "\r\n"
+}
+
+fn shift_zone(dt: NaiveDateTime, from: &impl TimeZone, to: &impl TimeZone) -> NaiveDateTime {
+    let anchored = match from.from_local_datetime(&dt) {
+        LocalResult::Single(anchored) | LocalResult::Ambiguous(anchored, _) => anchored,
+        // Times skipped by a daylight saving jump keep the offset from before it,
+        // which lands them just after the jump
+        LocalResult::None => {
+            let offset = from.offset_from_utc_datetime(&dt).fix().local_minus_utc();
+            from.from_utc_datetime(&(dt - Duration::seconds(offset.into())))
+        }
+    };
+    anchored.with_timezone(to).naive_local()
+}
+
+/// The `[ Thursday Nov 24 2022 ]` header shown above each day's slots
+///
+/// The date is formatted to a string before centering, since chrono's
+/// formatter ignores width and alignment, and the day is unpadded so the
+/// centering doesn't depend on how many digits the day has.
+fn day_header(date: NaiveDate) -> String {
+    TimeTableFormatter::default().header(date)
+}
+
+/// `1h 05m` style duration
+pub fn hours_minutes(duration: Duration) -> String {
+    let minutes = duration.num_minutes();
+    format!("{}h {:02}m", minutes / 60, minutes % 60)
+}
+
+/// A duration typed as `1:30`, `1h30m`, `1h 30m`, `2h` or `45m`, or a bare number of minutes
+pub fn parse_hours_minutes(text: &str) -> Option<Duration> {
+    let text = text.trim().to_ascii_lowercase();
+    let number = |s: &str| s.trim().parse::<i64>().ok();
+    let (hours, minutes) = if let Some((hours, minutes)) = text.split_once(':') {
+        (number(hours)?, number(minutes).filter(|&m| m < 60)?)
+    } else if let Some((hours, rest)) = text.split_once('h') {
+        let minutes = match rest.trim() {
+            "" => 0,
+            rest => number(rest.strip_suffix('m')?)?,
+        };
+        (number(hours)?, minutes)
+    } else if let Some(minutes) = text.strip_suffix('m') {
+        (0, number(minutes)?)
+    } else {
+        (0, number(&text)?)
+    };
+    if hours < 0 || minutes < 0 {
+        return None;
+    }
+    Some(Duration::minutes(hours * 60 + minutes))
+}
+
+/// How `TimeTable::format_with` lays out a table. The default is what `Display` prints.
+#[derive(Debug, Clone)]
+pub struct TimeTableFormatter {
+    date_format: String,
+    time_format: String,
+    show_duration: bool,
+}
+impl Default for TimeTableFormatter {
+    fn default() -> Self {
+        Self {
+            date_format: "%A %b %-d %Y".into(),
+            time_format: "%l:%M%P".into(),
+            show_duration: false,
+        }
+    }
+}
+impl TimeTableFormatter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// `chrono` format of the date in each day's header
+    pub fn date_format(mut self, fmt: &str) -> Self {
+        self.date_format = fmt.into();
+        self
+    }
+    /// `chrono` format of slot start and end times, like `%H:%M` for a 24-hour clock
+    pub fn time_format(mut self, fmt: &str) -> Self {
+        self.time_format = fmt.into();
+        self
+    }
+    /// Follow each bounded slot with how long it lasts
+    pub fn show_duration(mut self, show: bool) -> Self {
+        self.show_duration = show;
+        self
+    }
+    fn header(&self, date: NaiveDate) -> String {
+        let date = date.format(&self.date_format).to_string();
+        format!("[ {date:^23} ]")
+    }
+    fn time(&self, dt: NaiveDateTime) -> String {
+        dt.format(&self.time_format).to_string()
+    }
+    /// Padding the width of a time, standing in for an unbounded edge
+    fn blank(&self) -> String {
+        let noon = NaiveDate::from_ymd_opt(2000, 1, 1).and_then(|date| date.and_hms_opt(12, 0, 0));
+        " ".repeat(
+            self.time(noon.expect("Noon should be valid"))
+                .chars()
+                .count(),
+        )
+    }
+}
+
+impl<M> TimeTable<M> {
+    /// One line per slot under a header for each day, with a slot that crosses
+    /// midnight split across both days
+    pub fn format_with(&self, fmt: &TimeTableFormatter) -> String {
+        let mut prev_date = match self.timeslots.first() {
+            Some(ts) => match (ts.start, ts.end) {
                 (Some(dt), _) | (None, Some(dt)) => dt.date(),
                 _ => panic!("Timeslot cannot be endless on the start and end"),
             },
-            None => return f.write_str("Empty Timetable"),
+            None => return "Empty Timetable".into(),
         };
-        f.write_fmt(format_args!(
-            "[ {:^23} ]\n",
-            prev_date.format("%A %b %e %Y")
-        ))?;
-        for (i, mdt) in self
-            .timeslots
-            .iter()
-            .flat_map(|ts| [ts.start(), ts.end()])
-            .enumerate()
-        {
-            if let Some(dt) = mdt {
-                if dt.date() != prev_date {
-                    prev_date = dt.date();
-                    if i % 2 == 1 {
-                        f.write_str(" - ")?;
-                    }
-                    f.write_str("\n")?;
-                    f.write_fmt(format_args!(
-                        "[ {:^23} ]\n",
-                        prev_date.format("%A %b %e %Y")
-                    ))?;
-                    if i % 2 == 1 {
-                        f.write_str("       ")?;
+        let mut out = fmt.header(prev_date) + "\n";
+        for ts in &self.timeslots {
+            match ts.start {
+                Some(start) => {
+                    if start.date() != prev_date {
+                        prev_date = start.date();
+                        out += &format!("\n{}\n", fmt.header(prev_date));
                     }
+                    out += &fmt.time(start);
                 }
+                None => out += &fmt.blank(),
             }
-            if i % 2 == 1 {
-                f.write_str(" - ")?;
-            }
-            match mdt {
-                Some(dt) => f.write_str(&dt.format("%l:%M%P").to_string())?,
-                None => f.write_str("       ")?,
+            out += " - ";
+            match ts.end {
+                Some(end) => {
+                    if end.date() != prev_date {
+                        prev_date = end.date();
+                        out += &format!("\n{}\n{} - ", fmt.header(prev_date), fmt.blank());
+                    }
+                    out += &fmt.time(end);
+                }
+                None => out += &fmt.blank(),
             }
-            if i % 2 == 1 {
-                f.write_str("\n")?;
+            if let Some(duration) = ts.duration().filter(|_| fmt.show_duration) {
+                out += &format!("  ({})", hours_minutes(duration));
             }
+            out += "\n";
+        }
+        out
+    }
+}
+
+impl<M> Display for TimeTable<M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.format_with(&TimeTableFormatter::default()))
+    }
+}
+
+/// What `export_csv` and `export_json` need to know about a slot's meta
+pub trait ExportMeta {
+    /// Whose slot it is, left blank in exports when `None`
+    fn user(&self) -> Option<&str> {
+        None
+    }
+}
+impl ExportMeta for () {}
+
+/// The `date,start,end,duration,user` fields of one slot for an export, with the start's
+/// date, ISO 8601 datetimes and the length in minutes, and `None` where it is unbounded
+#[derive(Serialize)]
+struct ExportRow {
+    date: Option<String>,
+    start: Option<String>,
+    end: Option<String>,
+    duration: Option<i64>,
+    user: Option<String>,
+}
+impl ExportRow {
+    fn new<M: ExportMeta>(ts: &TimeSlot<M>) -> Self {
+        let iso =
+            |dt: &Option<NaiveDateTime>| dt.map(|dt| dt.format("%Y-%m-%dT%H:%M:%S").to_string());
+        Self {
+            date: ts.start.map(|dt| dt.date().format("%Y-%m-%d").to_string()),
+            start: iso(&ts.start),
+            end: iso(&ts.end),
+            duration: ts.duration().map(|dur| dur.num_minutes()),
+            user: ts.meta.user().map(String::from),
+        }
+    }
+    fn csv_fields(self) -> [String; 5] {
+        [
+            self.date,
+            self.start,
+            self.end,
+            self.duration.map(|m| m.to_string()),
+            self.user,
+        ]
+        .map(|field| csv_field(field.as_deref().unwrap_or("")))
+    }
+}
+
+fn csv_field(field: &str) -> String {
+    match field.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", field.replace('"', "\"\"")),
+        false => field.to_string(),
+    }
+}
+
+/// Write `table` as CSV with a `date,start,end,duration,user` header, leaving the fields
+/// of unbounded ends blank
+pub fn export_csv<M: ExportMeta>(
+    table: &TimeTable<M>,
+    mut writer: impl std::io::Write,
+) -> std::io::Result<()> {
+    writeln!(writer, "date,start,end,duration,user")?;
+    for ts in table {
+        writeln!(writer, "{}", ExportRow::new(ts).csv_fields().join(","))?;
+    }
+    Ok(())
+}
+
+/// Write `table` as a JSON array of objects with the same fields as `export_csv`, which
+/// are `null` for unbounded ends
+pub fn export_json<M: ExportMeta>(
+    table: &TimeTable<M>,
+    writer: impl std::io::Write,
+) -> serde_json::Result<()> {
+    serde_json::to_writer_pretty(writer, &table.iter().map(ExportRow::new).collect_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dt(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn test_day_header_alignment() {
+        let short = day_header(NaiveDate::from_ymd_opt(2022, 11, 5).unwrap());
+        let long = day_header(NaiveDate::from_ymd_opt(2022, 11, 25).unwrap());
+        assert_eq!(short, "[   Saturday Nov 5 2022   ]");
+        assert_eq!(long, "[   Friday Nov 25 2022    ]");
+        assert_eq!(short.len(), long.len());
+    }
+
+    struct User(&'static str);
+    impl ExportMeta for User {
+        fn user(&self) -> Option<&str> {
+            Some(self.0)
+        }
+    }
+
+    #[test]
+    fn test_export_csv() {
+        let mut csv = vec![];
+        export_csv(&sample_table(), &mut csv).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            concat!(
+                "date,start,end,duration,user\n",
+                ",,2022-11-24T08:00:00,,\n",
+                "2022-11-24,2022-11-24T09:00:00,2022-11-24T10:30:00,90,\n",
+                "2022-11-24,2022-11-24T22:00:00,2022-11-25T10:00:00,720,\n",
+                "2022-11-26,2022-11-26T13:00:00,,,\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_export_csv_quotes_users() {
+        let table = TimeTable::new([TimeSlot::new(
+            Some(dt("2022-11-24 09:00")),
+            Some(dt("2022-11-24 10:00")),
+            User("James, Wyatt \"Wy\""),
+        )]);
+        let mut csv = vec![];
+        export_csv(&table, &mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert_eq!(
+            csv.lines().nth(1),
+            Some(r#"2022-11-24,2022-11-24T09:00:00,2022-11-24T10:00:00,60,"James, Wyatt ""Wy""""#)
+        );
+    }
+
+    #[test]
+    fn test_export_json() {
+        let mut json = vec![];
+        export_json(&sample_table(), &mut json).unwrap();
+        let rows: serde_json::Value = serde_json::from_slice(&json).unwrap();
+        assert_eq!(rows.as_array().unwrap().len(), 4);
+        assert_eq!(rows[0]["start"], serde_json::Value::Null);
+        assert_eq!(rows[1]["duration"], 90);
+        assert_eq!(rows[1]["end"], "2022-11-24T10:30:00");
+        assert_eq!(rows[3]["date"], "2022-11-26");
+    }
+
+    #[test]
+    fn test_parse_hours_minutes() {
+        let minutes = |m| Some(Duration::minutes(m));
+        assert_eq!(parse_hours_minutes("1:30"), minutes(90));
+        assert_eq!(parse_hours_minutes("0:05"), minutes(5));
+        assert_eq!(parse_hours_minutes("1h30m"), minutes(90));
+        assert_eq!(parse_hours_minutes(" 1H 30m "), minutes(90));
+        assert_eq!(parse_hours_minutes("2h"), minutes(120));
+        assert_eq!(parse_hours_minutes("45m"), minutes(45));
+        assert_eq!(parse_hours_minutes("45"), minutes(45));
+        assert_eq!(parse_hours_minutes("1:75"), None);
+        assert_eq!(parse_hours_minutes("-1h"), None);
+        assert_eq!(parse_hours_minutes("1h30"), None);
+        assert_eq!(parse_hours_minutes("ninety"), None);
+        assert_eq!(parse_hours_minutes(""), None);
+    }
+
+    #[test]
+    fn test_subtract_less_duration() {
+        let mut table = TimeTable::new([
+            TimeSlot::new(
+                Some(dt("2022-11-24 08:00")),
+                Some(dt("2022-11-24 08:10")),
+                (),
+            ),
+            TimeSlot::new(
+                Some(dt("2022-11-24 09:00")),
+                Some(dt("2022-11-24 10:30")),
+                (),
+            ),
+            TimeSlot::new(
+                Some(dt("2022-11-24 13:00")),
+                Some(dt("2022-11-24 14:00")),
+                (),
+            ),
+            TimeSlot::new(Some(dt("2022-11-24 16:00")), None, ()),
+        ]);
+        table.subtract_less_duration(parse_hours_minutes("1:30").unwrap());
+        let starts = table.iter().map(|ts| ts.start().unwrap()).collect_vec();
+        // Unbounded slots are always long enough
+        assert_eq!(starts, [dt("2022-11-24 09:00"), dt("2022-11-24 16:00")]);
+        assert_eq!(hours_minutes(Duration::minutes(90)), "1h 30m");
+    }
+
+    #[test]
+    fn test_to_tsv() {
+        let table = TimeTable::new([
+            TimeSlot::new(
+                Some(dt("2022-11-24 09:00")),
+                Some(dt("2022-11-24 10:30")),
+                (),
+            ),
+            TimeSlot::new(Some(dt("2022-11-24 13:00")), None, ()),
+        ]);
+        assert_eq!(
+            table.to_tsv(),
+            "2022-11-24T09:00:00\t2022-11-24T10:30:00\t90\n2022-11-24T13:00:00\t\t\n"
+        );
+    }
+
+    fn slot(start: Option<&str>, end: Option<&str>) -> TimeSlot<()> {
+        TimeSlot::new(start.map(dt), end.map(dt), ())
+    }
+
+    fn spans<M>(table: &TimeTable<M>) -> Vec<(Option<NaiveDateTime>, Option<NaiveDateTime>)> {
+        table
+            .iter()
+            .map(|ts| (*ts.start(), *ts.end()))
+            .collect_vec()
+    }
+
+    #[test]
+    fn test_try_new() {
+        let valid = [
+            slot(None, Some("2022-11-24 08:00")),
+            slot(Some("2022-11-24 08:00"), Some("2022-11-24 09:00")),
+            slot(Some("2022-11-24 10:00"), None),
+        ];
+        assert!(TimeTable::try_new(valid).is_ok());
+        assert!(TimeTable::try_new([slot(None, None)]).is_ok());
+        assert!(TimeTable::<()>::try_new([]).is_ok());
+    }
+
+    #[test]
+    fn test_try_new_violations() {
+        let err = |slots: Vec<TimeSlot<()>>| TimeTable::try_new(slots).err();
+        let nine_to_ten = || slot(Some("2022-11-24 09:00"), Some("2022-11-24 10:00"));
+        assert_eq!(
+            err(vec![
+                nine_to_ten(),
+                slot(Some("2022-11-24 11:00"), Some("2022-11-24 11:00"))
+            ]),
+            Some(TimeTableError::Empty { index: 1 })
+        );
+        assert_eq!(
+            err(vec![slot(
+                Some("2022-11-24 11:00"),
+                Some("2022-11-24 10:00")
+            )]),
+            Some(TimeTableError::Empty { index: 0 })
+        );
+        assert_eq!(
+            err(vec![nine_to_ten(), slot(None, Some("2022-11-24 12:00"))]),
+            Some(TimeTableError::UnboundedInside { index: 1 })
+        );
+        assert_eq!(
+            err(vec![slot(Some("2022-11-24 08:00"), None), nine_to_ten()]),
+            Some(TimeTableError::UnboundedInside { index: 0 })
+        );
+        assert_eq!(
+            err(vec![
+                nine_to_ten(),
+                slot(Some("2022-11-24 07:00"), Some("2022-11-24 08:00"))
+            ]),
+            Some(TimeTableError::Unsorted { index: 1 })
+        );
+        assert_eq!(
+            err(vec![
+                nine_to_ten(),
+                slot(Some("2022-11-24 09:30"), Some("2022-11-24 11:00"))
+            ]),
+            Some(TimeTableError::Overlap { index: 1 })
+        );
+    }
+
+    #[test]
+    fn test_normalized() {
+        let table = TimeTable::normalized([
+            slot(Some("2022-11-24 13:00"), None),
+            slot(None, Some("2022-11-24 07:00")),
+            slot(Some("2022-11-24 09:30"), Some("2022-11-24 11:00")),
+            slot(None, Some("2022-11-24 06:00")),
+            slot(Some("2022-11-24 09:00"), Some("2022-11-24 10:00")),
+            slot(Some("2022-11-24 12:00"), Some("2022-11-24 12:00")),
+            slot(Some("2022-11-24 14:00"), Some("2022-11-24 15:00")),
+        ]);
+        let slots = spans(&table);
+        assert_eq!(
+            slots,
+            [
+                (None, Some(dt("2022-11-24 07:00"))),
+                (Some(dt("2022-11-24 09:00")), Some(dt("2022-11-24 11:00"))),
+                (Some(dt("2022-11-24 13:00")), None),
+            ]
+        );
+        assert!(TimeTable::try_new(table.timeslots().clone()).is_ok());
+    }
+
+    #[test]
+    fn test_merge_overlaps() {
+        let slots = [
+            slot(Some("2022-11-24 13:00"), Some("2022-11-24 14:00")),
+            slot(Some("2022-11-24 09:00"), Some("2022-11-24 10:00")),
+            slot(Some("2022-11-24 09:30"), Some("2022-11-24 11:00")),
+            slot(Some("2022-11-24 11:00"), Some("2022-11-24 12:00")),
+            slot(Some("2022-11-24 13:15"), Some("2022-11-24 13:45")),
+        ];
+        let named = slots.iter().zip(["d", "a", "b", "c", "e"]);
+        let mut table =
+            TimeTable::new(named.map(|(ts, name)| TimeSlot::new(*ts.start(), *ts.end(), name)));
+        table.merge_overlaps(|a, b| if a < b { a } else { b });
+        assert_eq!(
+            spans(&table),
+            [
+                // Overlapping, then touching
+                (Some(dt("2022-11-24 09:00")), Some(dt("2022-11-24 12:00"))),
+                // Nested
+                (Some(dt("2022-11-24 13:00")), Some(dt("2022-11-24 14:00"))),
+            ]
+        );
+        assert_eq!(table.iter().map(|ts| *ts.meta()).collect_vec(), ["a", "d"]);
+    }
+
+    #[test]
+    fn test_merge_overlaps_unbounded() {
+        let mut table = TimeTable::new([
+            slot(Some("2022-11-24 12:00"), None),
+            slot(Some("2022-11-24 07:00"), Some("2022-11-24 09:00")),
+            slot(None, Some("2022-11-24 08:00")),
+            slot(Some("2022-11-24 10:00"), Some("2022-11-24 11:00")),
+            slot(Some("2022-11-24 13:00"), Some("2022-11-24 14:00")),
+        ]);
+        table.merge_overlaps(|_, _| ());
+        let slots = spans(&table);
+        assert_eq!(
+            slots,
+            [
+                (None, Some(dt("2022-11-24 09:00"))),
+                (Some(dt("2022-11-24 10:00")), Some(dt("2022-11-24 11:00"))),
+                (Some(dt("2022-11-24 12:00")), None),
+            ]
+        );
+        assert_eq!(table.inverted().timeslots().len(), 2);
+    }
+
+    #[test]
+    fn test_intersect() {
+        let first = TimeTable::new([
+            slot(None, Some("2022-11-24 09:00")),
+            slot(Some("2022-11-24 10:00"), Some("2022-11-24 12:00")),
+            slot(Some("2022-11-24 13:00"), None),
+        ]);
+        let second = TimeTable::new([
+            slot(Some("2022-11-24 08:00"), Some("2022-11-24 11:00")),
+            slot(Some("2022-11-24 11:30"), Some("2022-11-24 14:00")),
+            slot(Some("2022-11-24 15:00"), None),
+        ]);
+        let expected = [
+            (Some(dt("2022-11-24 08:00")), Some(dt("2022-11-24 09:00"))),
+            (Some(dt("2022-11-24 10:00")), Some(dt("2022-11-24 11:00"))),
+            (Some(dt("2022-11-24 11:30")), Some(dt("2022-11-24 12:00"))),
+            (Some(dt("2022-11-24 13:00")), Some(dt("2022-11-24 14:00"))),
+            (Some(dt("2022-11-24 15:00")), None),
+        ];
+        assert_eq!(spans(&first.intersect(&second)), expected);
+        assert_eq!(spans(&second.intersect(&first)), expected);
+    }
+
+    #[test]
+    fn test_intersect_unbounded() {
+        let all = TimeTable::new([slot(None, None)]);
+        let some = TimeTable::new([
+            slot(None, Some("2022-11-24 09:00")),
+            slot(Some("2022-11-24 09:00"), Some("2022-11-24 10:00")),
+        ]);
+        assert_eq!(all.intersect(&some).timeslots().len(), 2);
+        assert_eq!(all.intersect(&all).timeslots()[0].duration(), None);
+        assert!(some
+            .intersect(&TimeTable::<()>::new([]))
+            .timeslots()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_largest_opening() {
+        let mut table = TimeTable::new([
+            slot(Some("2022-11-24 09:00"), Some("2022-11-24 10:00")),
+            slot(Some("2022-11-24 11:00"), Some("2022-11-24 13:30")),
+            slot(Some("2022-11-24 14:00"), Some("2022-11-24 15:00")),
+            slot(Some("2022-11-25 09:00"), Some("2022-11-25 11:30")),
+        ]);
+        let largest = table.largest_opening().unwrap();
+        assert_eq!(*largest.start(), Some(dt("2022-11-24 11:00")));
+
+        table.timeslots.push(slot(Some("2022-11-25 12:00"), None));
+        let largest = table.largest_opening().unwrap();
+        assert_eq!(*largest.start(), Some(dt("2022-11-25 12:00")));
+
+        assert!(TimeTable::<()>::new([]).largest_opening().is_none());
+    }
+
+    #[test]
+    fn test_first_opening_at_least() {
+        let table = TimeTable::new([
+            slot(Some("2022-11-24 09:00"), Some("2022-11-24 10:00")),
+            slot(Some("2022-11-24 11:00"), Some("2022-11-24 13:00")),
+            slot(Some("2022-11-24 14:00"), Some("2022-11-24 17:00")),
+            slot(Some("2022-11-25 09:00"), None),
+        ]);
+        let start = |min| table.first_opening_at_least(min).map(|ts| *ts.start());
+        assert_eq!(
+            start(Duration::hours(1)),
+            Some(Some(dt("2022-11-24 09:00")))
+        );
+        assert_eq!(
+            start(Duration::hours(2)),
+            Some(Some(dt("2022-11-24 11:00")))
+        );
+        assert_eq!(
+            start(Duration::minutes(121)),
+            Some(Some(dt("2022-11-24 14:00")))
+        );
+        assert_eq!(
+            start(Duration::hours(3)),
+            Some(Some(dt("2022-11-24 14:00")))
+        );
+        assert_eq!(
+            start(Duration::hours(48)),
+            Some(Some(dt("2022-11-25 09:00")))
+        );
+        assert!(TimeTable::<()>::new([])
+            .first_opening_at_least(Duration::zero())
+            .is_none());
+    }
+
+    #[test]
+    fn test_into_iter() {
+        let table = TimeTable::new([
+            TimeSlot::new(
+                Some(dt("2022-11-24 09:00")),
+                Some(dt("2022-11-24 10:00")),
+                "a",
+            ),
+            TimeSlot::new(Some(dt("2022-11-24 11:00")), None, "b"),
+        ]);
+        let mut metas = vec![];
+        for ts in &table {
+            metas.push(*ts.meta());
+        }
+        assert_eq!(metas, ["a", "b"]);
+        assert_eq!(table.iter().count(), 2);
+        let owned = table.into_iter().map(|ts| ts.meta).collect_vec();
+        assert_eq!(owned, ["a", "b"]);
+    }
+
+    #[test]
+    fn test_to_zone() {
+        let lab = chrono::FixedOffset::west_opt(7 * 3600).unwrap();
+        let user = chrono::FixedOffset::west_opt(5 * 3600).unwrap();
+        let openings = TimeTable::new([
+            slot(Some("2022-11-24 09:00"), Some("2022-11-24 17:00")),
+            slot(Some("2022-11-24 22:30"), None),
+        ]);
+        let shifted = openings.clone().to_zone(&lab, &user);
+        let slots = spans(&shifted);
+        assert_eq!(
+            slots,
+            [
+                (Some(dt("2022-11-24 11:00")), Some(dt("2022-11-24 19:00"))),
+                (Some(dt("2022-11-25 00:30")), None),
+            ]
+        );
+        let back = shifted.to_zone(&user, &lab);
+        assert_eq!(back.timeslots()[1].start(), openings.timeslots()[1].start());
+    }
+
+    #[test]
+    fn test_to_zone_daylight_saving() {
+        use chrono_tz::America::Edmonton;
+        // Clocks jumped from 2am to 3am in Edmonton on March 12 2023
+        let table = TimeTable::new([slot(Some("2023-03-12 02:30"), Some("2023-03-12 04:00"))]);
+        let utc = table.to_zone(&Edmonton, &chrono::Utc);
+        assert_eq!(*utc.timeslots()[0].start(), Some(dt("2023-03-12 09:30")));
+        assert_eq!(*utc.timeslots()[0].end(), Some(dt("2023-03-12 10:00")));
+    }
+
+    #[test]
+    fn test_new_sorts() {
+        let table = TimeTable::new([
+            slot(Some("2022-11-24 15:00"), None),
+            slot(Some("2022-11-24 11:00"), Some("2022-11-24 12:00")),
+            slot(Some("2022-11-24 09:00"), Some("2022-11-24 10:00")),
+            slot(None, Some("2022-11-24 08:00")),
+        ]);
+        let gaps = spans(&table.inverted());
+        assert_eq!(
+            gaps,
+            [
+                (Some(dt("2022-11-24 08:00")), Some(dt("2022-11-24 09:00"))),
+                (Some(dt("2022-11-24 10:00")), Some(dt("2022-11-24 11:00"))),
+                (Some(dt("2022-11-24 12:00")), Some(dt("2022-11-24 15:00"))),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_coalesce_adjacent() {
+        let mut table = TimeTable::new([
+            TimeSlot::new(
+                Some(dt("2022-11-24 09:00")),
+                Some(dt("2022-11-24 10:00")),
+                "a",
+            ),
+            TimeSlot::new(
+                Some(dt("2022-11-24 10:00")),
+                Some(dt("2022-11-24 11:00")),
+                "a",
+            ),
+            TimeSlot::new(
+                Some(dt("2022-11-24 11:00")),
+                Some(dt("2022-11-24 12:00")),
+                "a",
+            ),
+            TimeSlot::new(
+                Some(dt("2022-11-24 12:00")),
+                Some(dt("2022-11-24 13:00")),
+                "b",
+            ),
+            TimeSlot::new(
+                Some(dt("2022-11-24 13:30")),
+                Some(dt("2022-11-24 14:00")),
+                "b",
+            ),
+        ]);
+        table.coalesce_adjacent();
+        let slots = table
+            .timeslots()
+            .iter()
+            .map(|ts| (*ts.start(), *ts.end(), *ts.meta()))
+            .collect_vec();
+        assert_eq!(
+            slots,
+            [
+                (
+                    Some(dt("2022-11-24 09:00")),
+                    Some(dt("2022-11-24 12:00")),
+                    "a"
+                ),
+                // Adjacent but different
+                (
+                    Some(dt("2022-11-24 12:00")),
+                    Some(dt("2022-11-24 13:00")),
+                    "b"
+                ),
+                // Same but with a gap
+                (
+                    Some(dt("2022-11-24 13:30")),
+                    Some(dt("2022-11-24 14:00")),
+                    "b"
+                ),
+            ]
+        );
+
+        table.coalesce_adjacent_by(|_, _| true);
+        assert_eq!(table.timeslots().len(), 2);
+    }
+
+    #[test]
+    fn test_to_ical() {
+        let table = TimeTable::new([
+            slot(None, Some("2022-11-24 08:00")),
+            slot(Some("2022-11-24 09:00"), Some("2022-11-24 10:30")),
+            slot(Some("2022-11-25 13:00"), Some("2022-11-25 17:00")),
+            slot(Some("2022-11-25 18:00"), None),
+        ]);
+        let ical = table.to_ical(
+            &chrono_tz::America::Edmonton,
+            "All free: MLA150, Plasmalab; long name to force folding",
+        );
+        assert!(ical.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ical.ends_with("END:VEVENT\r\nEND:VCALENDAR\r\n"));
+        assert_eq!(ical.matches("BEGIN:VEVENT\r\n").count(), 2);
+        assert!(!ical.contains("TZID"));
+        assert!(ical.contains("DTSTART:20221124T160000Z\r\n"));
+        assert!(ical.contains("DTEND:20221126T000000Z\r\n"));
+        assert!(ical.split("\r\n").all(|line| line.len() <= 75));
+        let unfolded = ical.replace("\r\n ", "");
+        assert!(unfolded.contains(
+            "SUMMARY:All free: MLA150\\, Plasmalab\\; long name to force folding (1h 30m)\r\n"
+        ));
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let table = TimeTable::new([
+            TimeSlot::new(None, Some(dt("2022-11-24 08:00")), "before".to_string()),
+            TimeSlot::new(Some(dt("2022-11-24 09:00")), None, "after".to_string()),
+        ]);
+        let raw = ron::to_string(&table).unwrap();
+        assert!(raw.contains("\"2022-11-24T09:00:00\""));
+        let parsed: TimeTable<String> = ron::from_str(&raw).unwrap();
+        let slots = |table: &TimeTable<String>| {
+            table
+                .iter()
+                .map(|ts| (*ts.start(), *ts.end(), ts.meta().clone()))
+                .collect_vec()
+        };
+        assert_eq!(slots(&parsed), slots(&table));
+    }
+
+    #[test]
+    fn test_utilization() {
+        let table = TimeTable::new([
+            slot(Some("2022-11-23 22:00"), Some("2022-11-24 02:00")),
+            slot(Some("2022-11-24 09:00"), Some("2022-11-24 14:00")),
+            slot(Some("2022-11-24 12:00"), Some("2022-11-24 16:00")),
+            slot(Some("2022-11-24 21:00"), Some("2022-11-25 01:00")),
+        ]);
+        let day = slot(Some("2022-11-24 00:00"), Some("2022-11-25 00:00"));
+        // 2h after midnight, 7h through the overlap and 3h before midnight
+        assert!((table.utilization(&day) - 0.5).abs() < 1e-9);
+        let unbounded = table.utilization(&slot(None, None));
+        assert!((unbounded - 15.0 / 27.0).abs() < 1e-9);
+        let empty = slot(Some("2022-11-26 00:00"), Some("2022-11-27 00:00"));
+        assert_eq!(table.utilization(&empty), 0.0);
+        assert_eq!(TimeTable::<()>::new([]).utilization(&slot(None, None)), 0.0);
+    }
+
+    #[test]
+    fn test_subtract_unbounded_timeslot() {
+        let table = TimeTable::new([
+            slot(Some("2022-11-24 09:00"), Some("2022-11-24 10:00")),
+            slot(Some("2022-11-24 11:00"), Some("2022-11-24 12:00")),
+            slot(Some("2022-11-24 13:00"), Some("2022-11-24 14:00")),
+        ]);
+
+        let mut after = table.clone();
+        after.subtract_timeslot(&slot(Some("2022-11-24 11:30"), None));
+        assert_eq!(
+            spans(&after),
+            [
+                (Some(dt("2022-11-24 09:00")), Some(dt("2022-11-24 10:00"))),
+                (Some(dt("2022-11-24 11:00")), Some(dt("2022-11-24 11:30"))),
+            ]
+        );
+
+        let mut before = table.clone();
+        before.subtract_timeslot(&slot(None, Some("2022-11-24 11:30")));
+        assert_eq!(
+            spans(&before),
+            [
+                (Some(dt("2022-11-24 11:30")), Some(dt("2022-11-24 12:00"))),
+                (Some(dt("2022-11-24 13:00")), Some(dt("2022-11-24 14:00"))),
+            ]
+        );
+
+        let mut last = table.clone();
+        last.subtract_timeslot(&slot(Some("2022-11-24 13:30"), None));
+        assert_eq!(spans(&last).len(), 3);
+        assert_eq!(*last.timeslots()[2].end(), Some(dt("2022-11-24 13:30")));
+    }
+
+    #[test]
+    fn test_clip_to() {
+        let mut table = TimeTable::new([
+            slot(None, Some("2022-11-20 08:00")),
+            slot(None, Some("2022-11-24 08:00")),
+            slot(Some("2022-11-24 09:00"), Some("2022-11-24 10:00")),
+            slot(Some("2022-11-24 11:00"), None),
+        ]);
+        table.clip_to(dt("2022-11-24 07:00"), dt("2022-11-25 00:00"));
+        let slots = spans(&table);
+        assert_eq!(
+            slots,
+            [
+                (Some(dt("2022-11-24 07:00")), Some(dt("2022-11-24 08:00"))),
+                (Some(dt("2022-11-24 09:00")), Some(dt("2022-11-24 10:00"))),
+                (Some(dt("2022-11-24 11:00")), Some(dt("2022-11-25 00:00"))),
+            ]
+        );
+
+        let mut everything = TimeTable::new([slot(None, None)]);
+        everything.clip_to(dt("2022-11-24 07:00"), dt("2022-11-24 08:00"));
+        assert_eq!(
+            everything.timeslots()[0].duration(),
+            Some(Duration::hours(1))
+        );
+    }
+
+    fn sample_table() -> TimeTable<()> {
+        TimeTable::new([
+            slot(None, Some("2022-11-24 08:00")),
+            slot(Some("2022-11-24 09:00"), Some("2022-11-24 10:30")),
+            slot(Some("2022-11-24 22:00"), Some("2022-11-25 10:00")),
+            slot(Some("2022-11-26 13:00"), None),
+        ])
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            format!("{}", sample_table()),
+            concat!(
+                "[  Thursday Nov 24 2022   ]\n",
+                "        -  8:00am\n",
+                " 9:00am - 10:30am\n",
+                "10:00pm - \n",
+                "[   Friday Nov 25 2022    ]\n",
+                "        - 10:00am\n",
+                "\n",
+                "[  Saturday Nov 26 2022   ]\n",
+                " 1:00pm -        \n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_format_with_24_hour_clock() {
+        let fmt = TimeTableFormatter::new()
+            .time_format("%H:%M")
+            .date_format("%a %F");
+        assert_eq!(
+            sample_table().format_with(&fmt),
+            concat!(
+                "[     Thu 2022-11-24      ]\n",
+                "      - 08:00\n",
+                "09:00 - 10:30\n",
+                "22:00 - \n",
+                "[     Fri 2022-11-25      ]\n",
+                "      - 10:00\n",
+                "\n",
+                "[     Sat 2022-11-26      ]\n",
+                "13:00 -      \n",
+            )
+        );
+        let with_duration = sample_table().format_with(&fmt.show_duration(true));
+        assert!(with_duration.contains("09:00 - 10:30  (1h 30m)\n"));
+        assert!(with_duration.contains("      - 10:00  (12h 00m)\n"));
+    }
+
+    #[test]
+    fn test_subtract_walks_on_unbounded_tables() {
+        let hour = |h: u32| NaiveTime::from_hms_opt(h, 0, 0).unwrap();
+        for slots in [vec![], vec![slot(None, None)]] {
+            let mut table = TimeTable::new(slots.clone());
+            table.subtract_weekends(&WEEKEND);
+            table.subtract_after_hours(hour(8), hour(17));
+            table.subtract_default_after_hours();
+            table.subtract_before_time_of_day(hour(7));
+            assert_eq!(table.timeslots().len(), slots.len());
         }
-        Ok(())
+    }
+
+    #[test]
+    fn test_gaps_with_neighbors() {
+        let table = TimeTable::new([
+            TimeSlot::new(
+                Some(dt("2022-11-24 09:00")),
+                Some(dt("2022-11-24 10:00")),
+                "min-wu",
+            ),
+            TimeSlot::new(
+                Some(dt("2022-11-24 10:00")),
+                Some(dt("2022-11-24 13:00")),
+                "wjames",
+            ),
+            TimeSlot::new(
+                Some(dt("2022-11-24 14:30")),
+                Some(dt("2022-11-24 16:00")),
+                "ksetzer",
+            ),
+        ]);
+        let gaps = table
+            .gaps_with_neighbors()
+            .into_iter()
+            .map(|(before, gap, after)| {
+                (
+                    before.map(|ts| *ts.meta()),
+                    *gap.start(),
+                    *gap.end(),
+                    after.map(|ts| *ts.meta()),
+                )
+            })
+            .collect_vec();
+        assert_eq!(
+            gaps,
+            [
+                (None, None, Some(dt("2022-11-24 09:00")), Some("min-wu")),
+                (
+                    Some("wjames"),
+                    Some(dt("2022-11-24 13:00")),
+                    Some(dt("2022-11-24 14:30")),
+                    Some("ksetzer")
+                ),
+                (Some("ksetzer"), Some(dt("2022-11-24 16:00")), None, None),
+            ]
+        );
+
+        let empty = TimeTable::<()>::new([]);
+        assert!(matches!(empty.gaps_with_neighbors()[..], [(None, _, None)]));
+        assert!(TimeTable::new([slot(None, None)])
+            .gaps_with_neighbors()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_truncate() {
+        let mut table = TimeTable::new((9..14).map(|hour| {
+            let start = dt(&format!("2022-11-24 {hour:02}:00"));
+            TimeSlot::new(Some(start), Some(start + Duration::minutes(30)), ())
+        }));
+        assert_eq!(table.truncate(10), 0);
+        assert_eq!(table.timeslots().len(), 5);
+        assert_eq!(table.truncate(2), 3);
+        assert_eq!(*table.timeslots()[1].start(), Some(dt("2022-11-24 10:00")));
+    }
+
+    #[test]
+    fn test_subtract_after_hours() {
+        let today = NaiveDate::from_ymd_opt(2022, 11, 24).unwrap();
+        let at = |days: u64, h: u32| {
+            let date = today.checked_add_days(Days::new(days)).unwrap();
+            date.and_hms_opt(h, 0, 0).unwrap()
+        };
+        let hour = |h: u32| NaiveTime::from_hms_opt(h, 0, 0).unwrap();
+        let mut table = TimeTable::new([TimeSlot::new(Some(at(1, 0)), Some(at(4, 0)), ())]);
+        table.subtract_after_hours(hour(6), hour(22));
+        let slots = spans(&table);
+        assert_eq!(
+            slots,
+            [
+                (Some(at(1, 6)), Some(at(1, 22))),
+                (Some(at(2, 6)), Some(at(2, 22))),
+                (Some(at(3, 6)), Some(at(3, 22))),
+            ]
+        );
+
+        let mut night_shift = TimeTable::new([TimeSlot::new(Some(at(1, 0)), Some(at(2, 12)), ())]);
+        night_shift.subtract_after_hours(hour(20), hour(6));
+        let slots = spans(&night_shift);
+        assert_eq!(
+            slots,
+            [
+                (Some(at(1, 0)), Some(at(1, 6))),
+                (Some(at(1, 20)), Some(at(2, 6))),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_subtract_weekends() {
+        // Thursday morning to Monday noon
+        let table = TimeTable::new([slot(Some("2022-11-24 09:00"), Some("2022-11-28 12:00"))]);
+
+        let mut fridays = table.clone();
+        fridays.subtract_weekends(&[Weekday::Fri]);
+        assert_eq!(
+            spans(&fridays),
+            [
+                (Some(dt("2022-11-24 09:00")), Some(dt("2022-11-25 00:00"))),
+                (Some(dt("2022-11-26 00:00")), Some(dt("2022-11-28 12:00"))),
+            ]
+        );
+
+        let mut weekend = table.clone();
+        weekend.subtract_weekends(&WEEKEND);
+        assert_eq!(
+            spans(&weekend),
+            [
+                (Some(dt("2022-11-24 09:00")), Some(dt("2022-11-26 00:00"))),
+                (Some(dt("2022-11-28 00:00")), Some(dt("2022-11-28 12:00"))),
+            ]
+        );
+
+        let mut open_all_week = table.clone();
+        open_all_week.subtract_weekends(&[]);
+        assert_eq!(spans(&open_all_week), spans(&table));
+    }
+
+    #[test]
+    fn test_subtract_holidays() {
+        let mut table = TimeTable::new([
+            slot(Some("2022-11-23 09:00"), Some("2022-11-23 17:00")),
+            slot(Some("2022-11-24 09:00"), Some("2022-11-24 12:00")),
+            slot(Some("2022-11-24 13:00"), Some("2022-11-24 17:00")),
+            slot(Some("2022-11-25 09:00"), None),
+        ]);
+        let date = |s| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+        table.subtract_holidays(&[date("2022-11-24"), date("2022-12-25"), date("2021-01-01")]);
+        let slots = spans(&table);
+        assert_eq!(
+            slots,
+            [
+                (Some(dt("2022-11-23 09:00")), Some(dt("2022-11-23 17:00"))),
+                (Some(dt("2022-11-25 09:00")), Some(dt("2022-12-25 00:00"))),
+                (Some(dt("2022-12-26 00:00")), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_subtract_before_time_of_day() {
+        let today = NaiveDate::from_ymd_opt(2022, 11, 24).unwrap();
+        let at = |days: u64, h: u32, m: u32| {
+            let date = today.checked_add_days(Days::new(days)).unwrap();
+            date.and_hms_opt(h, m, 0).unwrap()
+        };
+        let mut table = TimeTable::new([
+            TimeSlot::new(Some(at(1, 6, 0)), Some(at(1, 9, 0)), ()),
+            TimeSlot::new(Some(at(1, 10, 0)), Some(at(1, 11, 0)), ()),
+            TimeSlot::new(Some(at(2, 6, 0)), Some(at(2, 6, 45)), ()),
+            TimeSlot::new(Some(at(3, 5, 0)), None, ()),
+        ]);
+        table.subtract_before_time_of_day(NaiveTime::from_hms_opt(7, 0, 0).unwrap());
+        let slots = spans(&table);
+        assert_eq!(
+            slots,
+            [
+                (Some(at(1, 7, 0)), Some(at(1, 9, 0))),
+                (Some(at(1, 10, 0)), Some(at(1, 11, 0))),
+                (Some(at(3, 7, 0)), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_shrink_slots() {
+        let mut table = TimeTable::new([
+            TimeSlot::new(None, Some(dt("2022-11-24 08:00")), ()),
+            TimeSlot::new(
+                Some(dt("2022-11-24 09:00")),
+                Some(dt("2022-11-24 09:30")),
+                (),
+            ),
+            TimeSlot::new(
+                Some(dt("2022-11-24 10:00")),
+                Some(dt("2022-11-24 10:15")),
+                (),
+            ),
+            TimeSlot::new(Some(dt("2022-11-24 12:00")), None, ()),
+        ]);
+        table.shrink_slots(Duration::minutes(20), Duration::zero());
+        let slots = spans(&table);
+        assert_eq!(
+            slots,
+            [
+                (None, Some(dt("2022-11-24 08:00"))),
+                (Some(dt("2022-11-24 09:20")), Some(dt("2022-11-24 09:30"))),
+                (Some(dt("2022-11-24 12:20")), None),
+            ]
+        );
+        table.subtract_less_duration(Duration::minutes(15));
+        assert_eq!(table.timeslots().len(), 2);
+        assert!(table.timeslots().iter().all(|ts| ts.duration().is_none()));
+    }
+
+    #[test]
+    fn test_shrink_slots_lead_and_trail() {
+        let mut table = TimeTable::new([TimeSlot::new(
+            Some(dt("2022-11-24 09:00")),
+            Some(dt("2022-11-24 10:00")),
+            (),
+        )]);
+        table.shrink_slots(Duration::minutes(10), Duration::minutes(20));
+        let ts = &table.timeslots()[0];
+        assert_eq!(*ts.start(), Some(dt("2022-11-24 09:10")));
+        assert_eq!(*ts.end(), Some(dt("2022-11-24 09:40")));
+        table.shrink_slots(Duration::minutes(15), Duration::minutes(15));
+        assert!(table.timeslots().is_empty());
     }
 }
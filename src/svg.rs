@@ -0,0 +1,158 @@
+use std::fmt::Write;
+
+use chrono::{Days, NaiveDate, NaiveDateTime, Timelike};
+
+use crate::schedule::TimeTable;
+
+const GUTTER_WIDTH: u32 = 60;
+const HEADER_HEIGHT: u32 = 30;
+const DAY_WIDTH: u32 = 150;
+const HOUR_HEIGHT: u32 = 24;
+
+/// Minimal builder for the handful of SVG elements the timeline needs
+struct Svg {
+    width: u32,
+    height: u32,
+    body: String,
+}
+impl Svg {
+    fn new(width: u32, height: u32) -> Self {
+        Self { width, height, body: String::new() }
+    }
+    fn rect(&mut self, x: u32, y: u32, width: u32, height: u32, class: &str) -> &mut Self {
+        writeln!(
+            self.body,
+            r#"<rect class="{class}" x="{x}" y="{y}" width="{width}" height="{height}"/>"#
+        )
+        .expect("Writing to a string should not fail");
+        self
+    }
+    fn line(&mut self, x1: u32, y1: u32, x2: u32, y2: u32) -> &mut Self {
+        writeln!(self.body, r#"<line x1="{x1}" y1="{y1}" x2="{x2}" y2="{y2}"/>"#)
+            .expect("Writing to a string should not fail");
+        self
+    }
+    fn text(&mut self, x: u32, y: u32, class: &str, text: &str) -> &mut Self {
+        let text = escape(text);
+        writeln!(self.body, r#"<text class="{class}" x="{x}" y="{y}">{text}</text>"#)
+            .expect("Writing to a string should not fail");
+        self
+    }
+    fn finish(self) -> String {
+        let Self { width, height, body } = self;
+        format!(
+            concat!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+                "\n<style>",
+                "line{{stroke:#ccc}}",
+                "text{{font-family:sans-serif;font-size:11px}}",
+                ".header{{font-weight:bold}}",
+                ".booking{{fill:#9cc3e6;stroke:#2e75b6}}",
+                "</style>\n",
+                "{body}</svg>\n",
+            ),
+            w = width,
+            h = height,
+            body = body
+        )
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn minutes_into_day(dt: NaiveDateTime) -> u32 {
+    dt.hour() * 60 + dt.minute()
+}
+
+/// Render `days` days of a timetable, starting at `start`, as an SVG
+/// timeline with one column per day and one labeled block per booking
+pub fn render_timeline<M>(
+    table: &TimeTable<M>,
+    start: NaiveDate,
+    days: u64,
+    label: impl Fn(&M) -> String,
+) -> String {
+    let width = GUTTER_WIDTH + DAY_WIDTH * days as u32;
+    let height = HEADER_HEIGHT + HOUR_HEIGHT * 24;
+    let mut svg = Svg::new(width, height);
+    for hour in 0..=24 {
+        let y = HEADER_HEIGHT + hour * HOUR_HEIGHT;
+        svg.line(GUTTER_WIDTH, y, width, y);
+        if hour < 24 {
+            let time = chrono::NaiveTime::from_hms_opt(hour, 0, 0).expect("Hour is in range");
+            svg.text(4, y + 12, "hour", &time.format("%l%P").to_string());
+        }
+    }
+    for day in 0..days {
+        let x = GUTTER_WIDTH + DAY_WIDTH * day as u32;
+        let date = start.checked_add_days(Days::new(day)).expect("adding days should not fail");
+        svg.line(x, HEADER_HEIGHT, x, height);
+        svg.text(x + 4, HEADER_HEIGHT - 10, "header", &date.format("%a %b %-d").to_string());
+        let day_start = date.and_hms_opt(0, 0, 0).expect("Creating day start should not fail");
+        let day_end =
+            day_start.checked_add_days(Days::new(1)).expect("adding days should not fail");
+        for timeslot in table {
+            let slot_start = timeslot.start().unwrap_or(day_start).max(day_start);
+            let slot_end = timeslot.end().unwrap_or(day_end).min(day_end);
+            if slot_start >= slot_end {
+                continue;
+            }
+            let top = minutes_into_day(slot_start);
+            let bottom = match slot_end == day_end {
+                true => 24 * 60,
+                false => minutes_into_day(slot_end),
+            };
+            let y = HEADER_HEIGHT + top * HOUR_HEIGHT / 60;
+            let h = (bottom - top) * HOUR_HEIGHT / 60;
+            let times = format!(
+                "{}-{}",
+                slot_start.format("%-I:%M%P"),
+                timeslot.end().unwrap_or(day_end).format("%-I:%M%P")
+            );
+            svg.rect(x + 2, y, DAY_WIDTH - 4, h, "booking")
+                .text(x + 6, y + 12, "label", &label(timeslot.meta()))
+                .text(x + 6, y + 24, "time", &times);
+        }
+    }
+    svg.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schedule::TimeSlot;
+
+    fn dt(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn test_render_timeline() {
+        let table = TimeTable::new([TimeSlot::new(
+            Some(dt("2022-11-24 06:00")),
+            Some(dt("2022-11-24 08:30")),
+            "wjames & co".to_string(),
+        )]);
+        let start = NaiveDate::from_ymd_opt(2022, 11, 24).unwrap();
+        let svg = render_timeline(&table, start, 2, |name| name.clone());
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.contains(r#"<rect class="booking" x="62" y="174" width="146" height="60"/>"#));
+        assert!(svg.contains(">wjames &amp; co</text>"));
+        assert!(svg.contains(">6:00am-8:30am</text>"));
+        assert!(svg.contains(">Fri Nov 25</text>"));
+    }
+
+    #[test]
+    fn test_render_timeline_splits_overnight() {
+        let table = TimeTable::new([TimeSlot::new(
+            Some(dt("2022-11-24 22:00")),
+            Some(dt("2022-11-25 02:00")),
+            (),
+        )]);
+        let start = NaiveDate::from_ymd_opt(2022, 11, 24).unwrap();
+        let svg = render_timeline(&table, start, 2, |_| String::new());
+        assert_eq!(svg.matches(r#"<rect class="booking""#).count(), 2);
+    }
+}
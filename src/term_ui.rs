@@ -1,21 +1,60 @@
-use std::io::{stdout, Write};
+use std::{
+    future::Future,
+    io::{stdout, Write},
+    sync::Mutex,
+    time::Duration,
+};
 
-use anyhow::Result;
+use anyhow::{anyhow, bail, Result};
+use chrono::NaiveDate;
 use crossterm::{
     cursor,
-    event::{self, Event, KeyCode, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind},
-    style::{self, style, Stylize},
+    event::{
+        self, Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+    },
+    style::{self, style, Color, ContentStyle, StyledContent, Stylize},
     terminal, QueueableCommand as _,
 };
 use itertools::Itertools;
+use serde::Deserialize;
 
 pub trait QueueableCommand: crossterm::QueueableCommand {
     fn queue_maybe_highlighted(&mut self, text: &str, highlight: bool) -> Result<&mut Self> {
-        let mut styled_text = style(text);
-        if highlight {
-            styled_text = styled_text.negative();
+        self.queue_maybe_highlighted_with(text, highlight, &current_theme())
+    }
+    /// Like `queue_maybe_highlighted`, in the highlight of `theme` instead of the one set
+    /// with `set_theme`
+    fn queue_maybe_highlighted_with(
+        &mut self,
+        text: &str,
+        highlight: bool,
+        theme: &Theme,
+    ) -> Result<&mut Self> {
+        let text_style = match highlight {
+            true => theme.highlight,
+            false => TextStyle::default(),
+        };
+        self.queue(style::PrintStyledContent(text_style.apply(text)))?;
+        Ok(self)
+    }
+    /// Print `line` in `text_style` with every match of `query` highlighted, ignoring ASCII
+    /// case
+    fn queue_with_matches(
+        &mut self,
+        line: &str,
+        query: &str,
+        text_style: TextStyle,
+    ) -> Result<&mut Self> {
+        let mut rest = 0;
+        if !query.is_empty() {
+            let lowercase = line.to_ascii_lowercase();
+            for (i, _) in lowercase.match_indices(&query.to_ascii_lowercase()) {
+                self.queue(style::PrintStyledContent(text_style.apply(&line[rest..i])))?
+                    .queue_maybe_highlighted(&line[i..i + query.len()], true)?;
+                rest = i + query.len();
+            }
         }
-        self.queue(style::PrintStyledContent(styled_text))?;
+        self.queue(style::PrintStyledContent(text_style.apply(&line[rest..])))?;
         Ok(self)
     }
     fn queue_hor_selector(
@@ -46,6 +85,23 @@ pub trait QueueableCommand: crossterm::QueueableCommand {
         }
         Ok(self)
     }
+    /// `queue_ver_selector` with a `[x]` or `[ ]` in front of each option, going by `checked`
+    fn queue_ver_checklist(
+        &mut self,
+        options: &[&str],
+        checked: &[bool],
+        selected: Option<usize>,
+    ) -> Result<&mut Self> {
+        let options = options
+            .iter()
+            .zip(checked.iter().chain(std::iter::repeat(&false)))
+            .map(|(opt, &checked)| match checked {
+                true => format!("[x] {opt}"),
+                false => format!("[ ] {opt}"),
+            })
+            .collect_vec();
+        self.queue_ver_selector(&options.iter().map(String::as_str).collect_vec(), selected)
+    }
 }
 impl<T> QueueableCommand for T where T: crossterm::QueueableCommand {}
 
@@ -69,6 +125,20 @@ pub trait EventObject {
             })
         )
     }
+    /// Which row of a list drawn from `first_row` down was clicked with the left button
+    fn clicked_row(&self, first_row: u16) -> Option<usize> {
+        match self.event() {
+            Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                row,
+                ..
+            }) => row.checked_sub(first_row).map(usize::from),
+            _ => None,
+        }
+    }
+    fn is_help(&self) -> bool {
+        self.is_char() == Some('?')
+    }
     fn is_key(&self, key_code: KeyCode) -> bool {
         matches!(self.event(), Event::Key(key) if key.code == key_code)
     }
@@ -78,6 +148,15 @@ pub trait EventObject {
             _ => None,
         }
     }
+    /// Clear the whole screen on a resize, so redrawing leaves no stale artifacts behind
+    fn resize_driver(&self) -> Result<bool> {
+        if self.is_resize().is_some() {
+            stdout().queue(terminal::Clear(terminal::ClearType::All))?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
     fn is_char(&self) -> Option<char> {
         match self.event() {
             Event::Key(KeyEvent {
@@ -92,6 +171,17 @@ pub trait EventObject {
             _ => None,
         }
     }
+    /// The action of the first of `bindings` that has this event's key
+    fn binding<A: Clone>(&self, bindings: &[Binding<A>]) -> Option<A> {
+        let matches = |code: &KeyCode| match code {
+            KeyCode::Char(c) => self.is_char() == Some(*c),
+            code => self.is_key(*code),
+        };
+        let binding = bindings
+            .iter()
+            .find(|binding| binding.codes.iter().any(matches))?;
+        Some(binding.action.clone())
+    }
     fn leftright_driver(&self, selector: &mut Option<usize>, max_val: usize) -> bool {
         if self.is_key(KeyCode::Left) {
             if selector.is_none() {
@@ -134,6 +224,17 @@ pub trait EventObject {
             false
         }
     }
+    /// `j` and `k` as Down and Up for `updown_driver`. These are letters, so unlike the
+    /// arrow keys they clash with `string_driver`: a loop with both has to decide when they
+    /// move the selection and when they are typed.
+    fn vim_driver(&self, selector: &mut Option<usize>, max_val: usize) -> bool {
+        let key_code = match self.is_char() {
+            Some('j') => KeyCode::Down,
+            Some('k') => KeyCode::Up,
+            _ => return false,
+        };
+        Event::Key(KeyEvent::new(key_code, KeyModifiers::NONE)).updown_driver(selector, max_val)
+    }
     fn scroll_driver(&self, selector: &mut Option<usize>, max_val: usize) -> bool {
         if self.is_mouse_scroll_up() {
             if selector.is_none() {
@@ -156,16 +257,91 @@ pub trait EventObject {
             false
         }
     }
-    fn string_driver(&self, string: &mut String) -> bool {
+    /// Answer a `[Yes] [No]` prompt whose `selector` is on Yes at `0`. Left/Right move it and
+    /// leave the prompt open with `None`. Enter picks the selected answer, `y`/`n` pick theirs
+    /// and Esc is No.
+    fn confirm_driver(&self, selector: &mut Option<usize>) -> Option<bool> {
+        if self.leftright_driver(selector, 1) {
+            None
+        } else if self.is_key(KeyCode::Enter) {
+            Some(*selector == Some(0))
+        } else if self.is_key(KeyCode::Esc) {
+            Some(false)
+        } else {
+            match self.is_char().map(|c| c.to_ascii_lowercase()) {
+                Some('y') => Some(true),
+                Some('n') => Some(false),
+                _ => None,
+            }
+        }
+    }
+    /// Move `scroll` a `page` of lines with PageUp/PageDown, or to either end with Home/End
+    fn page_driver(&self, scroll: &mut Option<usize>, page: usize, max_val: usize) -> bool {
+        let current = scroll.unwrap_or(0).min(max_val);
+        let target = if self.is_key(KeyCode::PageUp) {
+            current.saturating_sub(page)
+        } else if self.is_key(KeyCode::PageDown) {
+            current.saturating_add(page).min(max_val)
+        } else if self.is_key(KeyCode::Home) {
+            0
+        } else if self.is_key(KeyCode::End) {
+            max_val
+        } else {
+            return false;
+        };
+        *scroll = Some(target);
+        true
+    }
+    /// Scroll `offset` sideways a few columns at a time with Left/Right
+    fn hor_scroll_driver(&self, offset: &mut usize, max_val: usize) -> bool {
+        if self.is_key(KeyCode::Left) {
+            *offset = offset.saturating_sub(HOR_SCROLL_COLUMNS);
+        } else if self.is_key(KeyCode::Right) {
+            *offset = offset.saturating_add(HOR_SCROLL_COLUMNS).min(max_val);
+        } else {
+            return false;
+        }
+        true
+    }
+    /// Move `scroll` to the next (`]`) or previous (`[`) of the sorted line
+    /// indices in `headers`, e.g. the day headers of a rendered timetable
+    fn header_jump_driver(
+        &self,
+        scroll: &mut Option<usize>,
+        headers: &[usize],
+        max_val: usize,
+    ) -> bool {
+        let current = scroll.unwrap_or(0);
+        let target = match self.is_char() {
+            Some(']') => headers.iter().find(|&&i| i > current.min(max_val)),
+            Some('[') => headers.iter().rev().find(|&&i| i < current),
+            _ => return false,
+        };
+        if let Some(&i) = target {
+            *scroll = Some(i.min(max_val));
+        }
+        true
+    }
+    /// Type into `input` at its cursor, which Left/Right and Home/End move around
+    fn string_driver(&self, input: &mut TextInput) -> bool {
         if let Some(c) = self.is_char() {
-            string.push(c);
-            true
+            input.insert(c);
         } else if self.is_key(KeyCode::Backspace) {
-            string.pop();
-            true
+            input.backspace();
+        } else if self.is_key(KeyCode::Delete) {
+            input.delete();
+        } else if self.is_key(KeyCode::Left) {
+            input.cursor = input.cursor.saturating_sub(1);
+        } else if self.is_key(KeyCode::Right) {
+            input.cursor = (input.cursor + 1).min(input.buffer.chars().count());
+        } else if self.is_key(KeyCode::Home) {
+            input.cursor = 0;
+        } else if self.is_key(KeyCode::End) {
+            input.cursor = input.buffer.chars().count();
         } else {
-            false
+            return false;
         }
+        true
     }
 }
 impl EventObject for Event {
@@ -174,6 +350,733 @@ impl EventObject for Event {
     }
 }
 
+/// The keys of the items checked in a multi-select list, in the order they were checked.
+/// Going by key rather than position keeps them checked while a search filters the list.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToggleSet<K> {
+    keys: Vec<K>,
+}
+impl<K> Default for ToggleSet<K> {
+    fn default() -> Self {
+        Self { keys: vec![] }
+    }
+}
+impl<K: PartialEq> ToggleSet<K> {
+    /// Check `key` if it isn't, otherwise uncheck it, and say whether it's now checked
+    pub fn toggle(&mut self, key: K) -> bool {
+        match self.keys.iter().position(|k| *k == key) {
+            Some(i) => {
+                self.keys.remove(i);
+                false
+            }
+            None => {
+                self.keys.push(key);
+                true
+            }
+        }
+    }
+    pub fn contains(&self, key: &K) -> bool {
+        self.keys.contains(key)
+    }
+    pub fn keys(&self) -> &[K] {
+        &self.keys
+    }
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
+
+/// A line of text being typed, with the cursor somewhere in it
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TextInput {
+    buffer: String,
+    /// Position of the cursor, in chars from the start of `buffer`
+    cursor: usize,
+}
+impl TextInput {
+    /// Input holding `text`, with the cursor at its end
+    pub fn new(text: impl Into<String>) -> Self {
+        let buffer = text.into();
+        let cursor = buffer.chars().count();
+        Self { buffer, cursor }
+    }
+    pub fn as_str(&self) -> &str {
+        &self.buffer
+    }
+    pub fn into_string(self) -> String {
+        self.buffer
+    }
+    /// Position of the cursor, in chars from the start of the text
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+    fn byte_index(&self, char_index: usize) -> usize {
+        self.buffer
+            .char_indices()
+            .nth(char_index)
+            .map_or(self.buffer.len(), |(i, _)| i)
+    }
+    fn insert(&mut self, c: char) {
+        self.buffer.insert(self.byte_index(self.cursor), c);
+        self.cursor += 1;
+    }
+    fn backspace(&mut self) {
+        if self.cursor > 0 {
+            self.cursor -= 1;
+            self.buffer.remove(self.byte_index(self.cursor));
+        }
+    }
+    fn delete(&mut self) {
+        if self.cursor < self.buffer.chars().count() {
+            self.buffer.remove(self.byte_index(self.cursor));
+        }
+    }
+}
+
+/// How `DateRangeInput` shows and reads dates
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// A start and end date being picked, each typed as `YYYY-MM-DD` or stepped a day at a time
+#[derive(Debug, Clone, PartialEq)]
+pub struct DateRangeInput {
+    fields: [TextInput; 2],
+    /// Whether the start (`0`) or the end (`1`) is being edited
+    focus: usize,
+}
+impl DateRangeInput {
+    /// Input holding `start` and `end`, with the start focused
+    pub fn new(start: NaiveDate, end: NaiveDate) -> Self {
+        let field = |date: NaiveDate| TextInput::new(date.format(DATE_FORMAT).to_string());
+        Self {
+            fields: [field(start), field(end)],
+            focus: 0,
+        }
+    }
+    pub fn fields(&self) -> &[TextInput; 2] {
+        &self.fields
+    }
+    pub fn focus(&self) -> usize {
+        self.focus
+    }
+    /// Move the focused date `days` later, or earlier when negative. Does nothing while the
+    /// field doesn't hold a date.
+    pub fn step(&mut self, days: i64) {
+        let field = &mut self.fields[self.focus];
+        let Ok(date) = NaiveDate::parse_from_str(field.as_str(), DATE_FORMAT) else {
+            return;
+        };
+        if let Some(date) = date.checked_add_signed(chrono::Duration::days(days)) {
+            *field = TextInput::new(date.format(DATE_FORMAT).to_string());
+        }
+    }
+    /// The picked start and end, if both are dates and the end isn't before the start
+    pub fn range(&self) -> Result<(NaiveDate, NaiveDate)> {
+        let [start, end] = self.fields.each_ref().map(|field| {
+            let text = field.as_str().trim();
+            NaiveDate::parse_from_str(text, DATE_FORMAT)
+                .map_err(|_| anyhow!("`{text}` is not a date like 2022-11-24"))
+        });
+        let (start, end) = (start?, end?);
+        if end < start {
+            bail!("The end date is before the start date");
+        }
+        Ok((start, end))
+    }
+    /// Tab/Shift-Tab move between the fields, Up/Down step the focused date and typing
+    /// edits it
+    pub fn driver(&mut self, event: &Event) -> bool {
+        if event.is_key(KeyCode::Tab) || event.is_key(KeyCode::BackTab) {
+            self.focus = 1 - self.focus;
+        } else if event.is_key(KeyCode::Up) {
+            self.step(1);
+        } else if event.is_key(KeyCode::Down) {
+            self.step(-1);
+        } else {
+            return event.string_driver(&mut self.fields[self.focus]);
+        }
+        true
+    }
+}
+
+/// How far Left/Right scroll a view sideways
+const HOR_SCROLL_COLUMNS: usize = 8;
+
+/// The part of `line` that shows in `width` columns when scrolled `offset` columns right.
+/// Rather than wrapping, views scroll sideways over lines too long for the terminal so that
+/// each line stays on one row and scroll positions keep counting lines. This counts every
+/// char as one column, so wide ones can overhang.
+pub fn hor_window(line: &str, offset: usize, width: usize) -> &str {
+    let start = line
+        .char_indices()
+        .nth(offset)
+        .map_or(line.len(), |(i, _)| i);
+    let line = &line[start..];
+    let end = line
+        .char_indices()
+        .nth(width)
+        .map_or(line.len(), |(i, _)| i);
+    &line[..end]
+}
+
+/// The furthest `lines` can scroll right in `width` columns and still show the end of the
+/// longest one
+pub fn max_hor_offset(lines: impl IntoIterator<Item = impl AsRef<str>>, width: usize) -> usize {
+    let longest = lines
+        .into_iter()
+        .map(|line| line.as_ref().chars().count())
+        .max()
+        .unwrap_or(0);
+    longest.saturating_sub(width)
+}
+
+/// `less`-style search through the lines of a scrolling view. `/` opens a prompt for the
+/// query, Enter jumps to the first line from the top of the view containing it, and `n`/`N`
+/// jump to the next and previous matching lines. An empty query ends the search.
+#[derive(Debug, Default)]
+pub struct LineSearch {
+    query: String,
+    /// The query being typed, while the prompt is open
+    input: Option<TextInput>,
+    /// The line of the last match, which can be below the top of the view near the end
+    current: Option<usize>,
+}
+impl LineSearch {
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+    /// Handle keys for the search, and while the prompt is open, all typing
+    pub fn driver(
+        &mut self,
+        event: &Event,
+        lines: &[impl AsRef<str>],
+        scroll: &mut Option<usize>,
+        max_val: usize,
+    ) -> bool {
+        let top = scroll.unwrap_or(0);
+        // Continue from the last match unless the view was scrolled away from it
+        let from = match self.current {
+            Some(i) if i.min(max_val) == top => i,
+            _ => top,
+        };
+        let found = if let Some(input) = &mut self.input {
+            if event.is_resize().is_some() {
+                return false;
+            } else if event.is_key(KeyCode::Enter) {
+                self.query = self.input.take().unwrap_or_default().into_string();
+                self.current = None;
+                find_line(lines, &self.query, top, true)
+            } else if event.is_key(KeyCode::Esc) {
+                self.input = None;
+                None
+            } else {
+                event.string_driver(input);
+                None
+            }
+        } else {
+            match event.is_char() {
+                Some('/') => {
+                    self.input = Some(TextInput::default());
+                    None
+                }
+                Some('n') if !self.query.is_empty() => {
+                    find_line(lines, &self.query, from + 1, true)
+                }
+                Some('N') if !self.query.is_empty() => find_line(lines, &self.query, from, false),
+                _ => return false,
+            }
+        };
+        if let Some(i) = found {
+            self.current = Some(i);
+            *scroll = Some(i.min(max_val));
+        }
+        true
+    }
+    /// Show the prompt on the bottom line while the query is being typed
+    pub fn display_prompt(&self) -> Result<()> {
+        let Some(input) = &self.input else {
+            return Ok(());
+        };
+        let row = terminal::size()?.1.saturating_sub(1);
+        stdout()
+            .queue(cursor::Show)?
+            .queue(cursor::MoveTo(0, row))?
+            .queue(style::Print("/"))?
+            .queue(style::Print(input.as_str()))?
+            .queue(terminal::Clear(terminal::ClearType::UntilNewLine))?
+            .queue(cursor::MoveToColumn(1 + input.cursor() as u16))?
+            .flush()?;
+        Ok(())
+    }
+}
+
+/// The first of `lines` from `start` on that contains `query`, ignoring ASCII case, or
+/// with `forward` false the last one before `start`. Never matches an empty `query`.
+pub fn find_line(
+    lines: &[impl AsRef<str>],
+    query: &str,
+    start: usize,
+    forward: bool,
+) -> Option<usize> {
+    if query.is_empty() {
+        return None;
+    }
+    let query = query.to_ascii_lowercase();
+    let matches = |i: &usize| lines[*i].as_ref().to_ascii_lowercase().contains(&query);
+    match forward {
+        true => (start..lines.len()).find(matches),
+        false => (0..start.min(lines.len())).rev().find(matches),
+    }
+}
+
+/// How the user left a `Pager`
+#[derive(Debug, Clone, PartialEq)]
+pub enum PagerExit {
+    /// Enter or Esc, back to wherever the pager was opened from
+    Back,
+    /// `q`, to leave the program altogether
+    Quit,
+    /// A key the pager has no use for, left to the caller before it runs the pager again
+    Key(Event),
+}
+
+/// The error for leaving the program from deep in the UI, like with `PagerExit::Quit`
+#[derive(Debug)]
+pub struct QuitRequested;
+impl std::fmt::Display for QuitRequested {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Quit requested")
+    }
+}
+impl std::error::Error for QuitRequested {}
+
+/// Keys the pager handles itself, after its drivers
+const PAGER_KEYS: &[Binding<PagerExit>] = &[
+    Binding {
+        codes: &[KeyCode::Enter, KeyCode::Esc],
+        help: ("Enter/Esc", "Back"),
+        action: PagerExit::Back,
+    },
+    Binding {
+        codes: &[KeyCode::Char('q')],
+        help: ("q", "Quit"),
+        action: PagerExit::Quit,
+    },
+];
+
+/// A full-screen view of lines under a title, which scroll with `SCROLL_KEYS`, `PAGE_KEYS`
+/// and `HOR_SCROLL_KEYS` and can be searched with `SEARCH_KEYS`. Lines starting with `[`
+/// are the day headers `HEADER_JUMP_KEYS` move between.
+#[derive(Debug)]
+pub struct Pager {
+    title: String,
+    lines: Vec<String>,
+    colors: Vec<Option<Color>>,
+    headers: Vec<usize>,
+    /// Keys the caller handles itself, for the help overlay
+    keys: Vec<KeyHelp>,
+    scroll: Option<usize>,
+    offset: usize,
+    search: LineSearch,
+    /// The terminal size as `(cols, rows)`, kept up to date while running
+    size: (usize, usize),
+}
+impl Pager {
+    pub fn new(title: impl Into<String>, lines: Vec<String>) -> Self {
+        let colors = vec![None; lines.len()];
+        Self::colored(title, lines.into_iter().zip(colors).collect())
+    }
+    /// A pager whose lines are each drawn in their own color
+    pub fn colored(title: impl Into<String>, lines: Vec<(String, Option<Color>)>) -> Self {
+        let mut pager = Self {
+            title: title.into(),
+            lines: vec![],
+            colors: vec![],
+            headers: vec![],
+            keys: vec![],
+            scroll: Some(0),
+            offset: 0,
+            search: LineSearch::default(),
+            size: (80, 24),
+        };
+        pager.set_colored_lines(lines);
+        pager
+    }
+    /// List `bindings` in the help overlay, for keys handled after `PagerExit::Key`
+    pub fn with_keys<A>(mut self, bindings: &[Binding<A>]) -> Self {
+        self.keys = help_rows(bindings);
+        self
+    }
+    pub fn set_title(&mut self, title: impl Into<String>) {
+        self.title = title.into();
+    }
+    /// Replace the lines, keeping the scroll position where it still fits
+    pub fn set_lines(&mut self, lines: Vec<String>) {
+        let colors = vec![None; lines.len()];
+        self.set_colored_lines(lines.into_iter().zip(colors).collect());
+    }
+    fn set_colored_lines(&mut self, lines: Vec<(String, Option<Color>)>) {
+        (self.lines, self.colors) = lines.into_iter().unzip();
+        self.headers = self
+            .lines
+            .iter()
+            .positions(|line| line.starts_with('['))
+            .collect();
+        self.scroll_to(self.scroll.unwrap_or(0));
+        self.offset = self.offset.min(self.max_offset());
+    }
+    /// Scroll so `line` is at the top, or as near as the end of the lines allows
+    pub fn scroll_to(&mut self, line: usize) {
+        self.scroll = Some(line.min(self.max_scroll()));
+    }
+    pub fn scroll(&self) -> usize {
+        self.scroll.unwrap_or(0)
+    }
+    /// How many lines fit below the title
+    pub fn page_len(&self) -> usize {
+        self.size.1.saturating_sub(1)
+    }
+    fn max_scroll(&self) -> usize {
+        self.lines.len().saturating_sub(self.page_len())
+    }
+    fn max_offset(&self) -> usize {
+        max_hor_offset(&self.lines, self.size.0)
+    }
+    /// Show the pager until the user leaves it or presses a key it doesn't handle
+    pub fn run(&mut self) -> Result<PagerExit> {
+        let (cols, rows) = terminal::size()?;
+        self.size = (cols as usize, rows as usize);
+        loop {
+            self.draw()?;
+            if let Some(exit) = self.handle(&event::read()?)? {
+                return Ok(exit);
+            }
+        }
+    }
+    fn draw(&self) -> Result<()> {
+        stdout()
+            .queue(cursor::Hide)?
+            .queue(cursor::MoveTo(0, 0))?
+            .queue(style::PrintStyledContent(
+                current_theme()
+                    .header
+                    .apply(hor_window(&self.title, 0, self.size.0)),
+            ))?
+            .queue(terminal::Clear(terminal::ClearType::UntilNewLine))?;
+        let visible = self.lines.iter().zip(&self.colors).skip(self.scroll());
+        for (line, color) in visible.take(self.page_len()) {
+            let window = hor_window(line, self.offset, self.size.0);
+            stdout()
+                .queue(cursor::MoveDown(1))?
+                .queue(cursor::MoveToColumn(0))?;
+            // Colored lines keep their color rather than showing search matches
+            match color {
+                Some(color) => {
+                    stdout().queue(style::PrintStyledContent(style(window).with(*color)))?
+                }
+                None => {
+                    let text_style = match line.starts_with('[') {
+                        true => current_theme().header,
+                        false => TextStyle::default(),
+                    };
+                    stdout().queue_with_matches(window, self.search.query(), text_style)?
+                }
+            };
+            stdout().queue(terminal::Clear(terminal::ClearType::UntilNewLine))?;
+        }
+        stdout()
+            .queue(terminal::Clear(terminal::ClearType::FromCursorDown))?
+            .flush()?;
+        self.search.display_prompt()
+    }
+    /// Apply `event` to the view, and say how the user left it if they did
+    fn handle(&mut self, event: &Event) -> Result<Option<PagerExit>> {
+        let (max_scroll, max_offset) = (self.max_scroll(), self.max_offset());
+        let scroll = &mut self.scroll;
+        #[allow(clippy::if_same_then_else)]
+        if self.search.driver(event, &self.lines, scroll, max_scroll) {
+        } else if event.updown_driver(scroll, max_scroll) {
+        } else if event.vim_driver(scroll, max_scroll) {
+        } else if event.scroll_driver(scroll, max_scroll) {
+        } else if event.page_driver(scroll, self.size.1.saturating_sub(1), max_scroll) {
+        } else if event.hor_scroll_driver(&mut self.offset, max_offset) {
+        } else if event.header_jump_driver(scroll, &self.headers, max_scroll) {
+        } else if event.is_help() {
+            let mut keys = vec![SCROLL_KEYS, PAGE_KEYS, HOR_SCROLL_KEYS, HEADER_JUMP_KEYS];
+            keys.push(SEARCH_KEYS);
+            keys.extend(&self.keys);
+            keys.extend(help_rows(PAGER_KEYS));
+            keys.push(HELP_KEY);
+            display_help(&keys)?;
+        } else if let Some(exit) = event.binding(PAGER_KEYS) {
+            return Ok(Some(exit));
+        } else if let Some((cols, rows)) = event.is_resize() {
+            self.size = (cols, rows);
+        } else if let Event::Key(_) = event {
+            return Ok(Some(PagerExit::Key(event.clone())));
+        }
+        Ok(None)
+    }
+}
+
+/// How some text looks. Colors are names like `"red"` and `"dark_grey"`, or
+/// `"ansi_(208)"` or `"rgb_(255,128,0)"`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct TextStyle {
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+    /// Swap the text and background colors
+    pub reverse: bool,
+    pub bold: bool,
+}
+impl TextStyle {
+    pub fn content_style(&self) -> ContentStyle {
+        let mut content_style = ContentStyle::new();
+        content_style.foreground_color = self.fg;
+        content_style.background_color = self.bg;
+        if self.reverse {
+            content_style = content_style.negative();
+        }
+        if self.bold {
+            content_style = content_style.bold();
+        }
+        content_style
+    }
+    pub fn apply<D: std::fmt::Display>(&self, content: D) -> StyledContent<D> {
+        StyledContent::new(self.content_style(), content)
+    }
+}
+
+/// The colors of the UI, from the `theme` section of the config
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Theme {
+    /// Error messages. A red background when unset.
+    pub error: TextStyle,
+    /// Selected options, search matches and the help overlay. Reverse video when unset.
+    pub highlight: TextStyle,
+    /// Titles and day headers of scrolling views. Plain when unset.
+    pub header: TextStyle,
+}
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            error: TextStyle {
+                bg: Some(Color::Red),
+                ..Default::default()
+            },
+            highlight: TextStyle {
+                reverse: true,
+                ..Default::default()
+            },
+            header: TextStyle::default(),
+        }
+    }
+}
+
+static THEME: Mutex<Option<Theme>> = Mutex::new(None);
+
+/// Draw everything from now on with `theme`
+pub fn set_theme(theme: Theme) {
+    *THEME.lock().unwrap() = Some(theme);
+}
+
+fn current_theme() -> Theme {
+    THEME.lock().unwrap().unwrap_or_default()
+}
+
+/// A key and the action it performs, as listed in the `?` help overlay
+pub type KeyHelp = (&'static str, &'static str);
+
+/// A key a view handles on top of its drivers, next to its `KeyHelp` row, so a view keeps
+/// one table of them that both its event loop and its help read
+#[derive(Debug, Clone, Copy)]
+pub struct Binding<A> {
+    pub codes: &'static [KeyCode],
+    pub help: KeyHelp,
+    pub action: A,
+}
+
+/// The `KeyHelp` rows of `bindings`, in order
+pub fn help_rows<A>(bindings: &[Binding<A>]) -> Vec<KeyHelp> {
+    bindings.iter().map(|binding| binding.help).collect()
+}
+
+pub const HELP_KEY: KeyHelp = ("?", "Show this help");
+pub const UPDOWN_KEYS: KeyHelp = ("Up/Down/j/k", "Move selection");
+pub const LEFTRIGHT_KEYS: KeyHelp = ("Left/Right", "Move selection");
+pub const SCROLL_KEYS: KeyHelp = ("Up/Down/j/k/Wheel", "Scroll");
+pub const PAGE_KEYS: KeyHelp = ("PgUp/PgDn/Home/End", "Scroll by page or to either end");
+pub const HEADER_JUMP_KEYS: KeyHelp = ("[ / ]", "Previous/next day");
+pub const HOR_SCROLL_KEYS: KeyHelp = ("Left/Right", "Scroll sideways over long lines");
+pub const SEARCH_KEYS: KeyHelp = (
+    "/ n N",
+    "Search, next/previous match (empty search to stop)",
+);
+/// What `confirm_driver` handles
+pub const CONFIRM_KEYS: &[KeyHelp] = &[
+    LEFTRIGHT_KEYS,
+    ("Enter", "Confirm choice"),
+    ("y/n", "Yes/No"),
+    ("Esc", "No"),
+];
+/// What `DateRangeInput::driver` handles besides typing
+pub const DATE_RANGE_KEYS: &[KeyHelp] = &[
+    ("Tab", "Switch between the dates"),
+    ("Up/Down", "Next/previous day"),
+];
+
+/// `display_help` for a view, listing its drivers' `driver_keys`, then its own `bindings`
+pub fn display_view_help<A>(driver_keys: &[KeyHelp], bindings: &[Binding<A>]) -> Result<()> {
+    let mut keys = driver_keys.to_vec();
+    keys.extend(help_rows(bindings));
+    keys.push(HELP_KEY);
+    display_help(&keys)
+}
+
+/// Read a line from the terminal without echoing it, like a password. The prompt goes to
+/// stderr so it stays out of piped output. Esc and Ctrl-C cancel.
+pub fn read_hidden_line(prompt: &str) -> Result<String> {
+    eprint!("{prompt}");
+    terminal::enable_raw_mode()?;
+    let line = read_hidden_keys();
+    terminal::disable_raw_mode()?;
+    eprintln!();
+    line
+}
+
+fn read_hidden_keys() -> Result<String> {
+    let mut input = TextInput::default();
+    loop {
+        let event = event::read()?;
+        let ctrl_c = matches!(&event, Event::Key(KeyEvent { code: KeyCode::Char('c'), modifiers, .. })
+            if modifiers.contains(KeyModifiers::CONTROL));
+        if event.is_key(KeyCode::Enter) {
+            return Ok(input.into_string());
+        } else if ctrl_c || event.is_key(KeyCode::Esc) {
+            bail!("Cancelled");
+        }
+        event.string_driver(&mut input);
+    }
+}
+
+/// Render a centered panel listing `keys`, and wait for any key to dismiss it
+pub fn display_help(keys: &[KeyHelp]) -> Result<()> {
+    let key_width = keys.iter().map(|(key, _)| key.len()).max().unwrap_or(0);
+    let mut lines = vec!["Keys".to_string(), String::new()];
+    lines.extend(
+        keys.iter()
+            .map(|(key, action)| format!("{key:>key_width$}  {action}")),
+    );
+    lines.push(String::new());
+    lines.push("Press any key to close".to_string());
+    let width = lines.iter().map(|line| line.len()).max().unwrap_or(0) + 4;
+    let (cols, rows) = terminal::size()?;
+    let start_col = (cols as usize).saturating_sub(width) as u16 / 2;
+    let start_row = rows.saturating_sub(lines.len() as u16) / 2;
+    stdout().queue(cursor::Hide)?;
+    for (i, line) in lines.iter().enumerate() {
+        let padded = format!("  {line:<0$}", width - 2);
+        stdout()
+            .queue(cursor::MoveTo(start_col, start_row + i as u16))?
+            .queue(style::PrintStyledContent(
+                current_theme().highlight.apply(padded),
+            ))?;
+    }
+    stdout().flush()?;
+    loop {
+        if let Event::Key(_) = event::read()? {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// The frames `Spinner` cycles through
+const SPINNER_FRAMES: [char; 4] = ['|', '/', '-', '\\'];
+const SPINNER_TICK: Duration = Duration::from_millis(100);
+
+/// An animated indicator on the bottom line while waiting on the network, so the screen
+/// doesn't look frozen
+pub struct Spinner {
+    message: String,
+    frame: usize,
+    drawn: bool,
+}
+impl Spinner {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            frame: 0,
+            drawn: false,
+        }
+    }
+    /// Move on to the next frame, wrapping around at the end
+    fn tick(&mut self) -> char {
+        self.frame = (self.frame + 1) % SPINNER_FRAMES.len();
+        SPINNER_FRAMES[self.frame]
+    }
+    /// Animate until `future` finishes, then clear the indicator and return its output
+    pub async fn run<T>(mut self, future: impl Future<Output = Result<T>>) -> Result<T> {
+        tokio::pin!(future);
+        let mut ticks = tokio::time::interval(SPINNER_TICK);
+        loop {
+            tokio::select! {
+                // Checked first so a future that is already done never draws
+                biased;
+                output = &mut future => {
+                    self.clear()?;
+                    return output;
+                }
+                _ = ticks.tick() => self.draw()?,
+            }
+        }
+    }
+    fn draw(&mut self) -> Result<()> {
+        let frame = self.tick();
+        let row = terminal::size()?.1.saturating_sub(1);
+        stdout()
+            .queue(cursor::SavePosition)?
+            .queue(cursor::MoveTo(0, row))?
+            .queue(style::Print(format!("{frame} {}", self.message)))?
+            .queue(terminal::Clear(terminal::ClearType::UntilNewLine))?
+            .queue(cursor::RestorePosition)?
+            .flush()?;
+        self.drawn = true;
+        Ok(())
+    }
+    fn clear(&self) -> Result<()> {
+        if !self.drawn {
+            return Ok(());
+        }
+        let row = terminal::size()?.1.saturating_sub(1);
+        stdout()
+            .queue(cursor::SavePosition)?
+            .queue(cursor::MoveTo(0, row))?
+            .queue(terminal::Clear(terminal::ClearType::CurrentLine))?
+            .queue(cursor::RestorePosition)?
+            .flush()?;
+        Ok(())
+    }
+}
+
+/// Wait for the next event, or give up with `None` once `timeout` passes.
+/// Without a timeout this blocks like `event::read`.
+pub fn read_event(timeout: Option<Duration>) -> Result<Option<Event>> {
+    if let Some(timeout) = timeout {
+        if !event::poll(timeout)? {
+            return Ok(None);
+        }
+    }
+    Ok(Some(event::read()?))
+}
+
+/// Look up the option a selector points at, treating `None` or an
+/// out-of-range index as no selection
+pub fn selected_option<T>(options: &[T], selector: Option<usize>) -> Option<&T> {
+    selector.and_then(|i| options.get(i))
+}
+
 pub fn display_error_msg(error: anyhow::Error) -> Result<()> {
     let buffer = format!("{error:?}");
     let mut lines = buffer.lines().collect_vec();
@@ -183,7 +1086,7 @@ pub fn display_error_msg(error: anyhow::Error) -> Result<()> {
     stdout()
         .queue(cursor::Hide)?
         .queue(cursor::MoveTo(0, start_row))?
-        .queue(style::SetBackgroundColor(style::Color::Red))?;
+        .queue(style::SetStyle(current_theme().error.content_style()))?;
     for line in &lines {
         stdout()
             .queue(style::Print(line))?
@@ -191,7 +1094,10 @@ pub fn display_error_msg(error: anyhow::Error) -> Result<()> {
             .queue(cursor::MoveDown(1))?
             .queue(cursor::MoveToColumn(0))?;
     }
-    stdout().queue(style::ResetColor)?.flush()?;
+    stdout()
+        .queue(style::ResetColor)?
+        .queue(style::SetAttribute(style::Attribute::Reset))?
+        .flush()?;
     'event_loop: loop {
         let event = event::read()?;
         if event.is_key(KeyCode::Enter) | event.is_key(KeyCode::Esc) {
@@ -200,3 +1106,472 @@ pub fn display_error_msg(error: anyhow::Error) -> Result<()> {
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(c: char) -> Event {
+        Event::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE))
+    }
+
+    fn mouse(kind: MouseEventKind, row: u16) -> Event {
+        Event::Mouse(MouseEvent {
+            kind,
+            column: 4,
+            row,
+            modifiers: KeyModifiers::NONE,
+        })
+    }
+
+    #[test]
+    fn test_clicked_row() {
+        let click = |row| mouse(MouseEventKind::Down(MouseButton::Left), row);
+        assert_eq!(click(0).clicked_row(0), Some(0));
+        assert_eq!(click(5).clicked_row(0), Some(5));
+        assert_eq!(click(5).clicked_row(2), Some(3));
+        assert_eq!(click(2).clicked_row(2), Some(0));
+        // Above the list
+        assert_eq!(click(1).clicked_row(2), None);
+        assert_eq!(
+            mouse(MouseEventKind::Down(MouseButton::Right), 5).clicked_row(2),
+            None
+        );
+        assert_eq!(
+            mouse(MouseEventKind::Up(MouseButton::Left), 5).clicked_row(2),
+            None
+        );
+        assert_eq!(mouse(MouseEventKind::ScrollDown, 5).clicked_row(2), None);
+        assert_eq!(key('x').clicked_row(0), None);
+    }
+
+    #[test]
+    fn test_binding() {
+        let enter = Event::Key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        let esc = Event::Key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+        assert_eq!(enter.binding(PAGER_KEYS), Some(PagerExit::Back));
+        assert_eq!(esc.binding(PAGER_KEYS), Some(PagerExit::Back));
+        assert_eq!(key('q').binding(PAGER_KEYS), Some(PagerExit::Quit));
+        assert_eq!(key('x').binding(PAGER_KEYS), None);
+        // Shifted letters are their capitals, like with `is_char`
+        let shifted_q = Event::Key(KeyEvent::new(KeyCode::Char('q'), KeyModifiers::SHIFT));
+        assert_eq!(shifted_q.binding(PAGER_KEYS), None);
+        assert_eq!(
+            help_rows(PAGER_KEYS),
+            [("Enter/Esc", "Back"), ("q", "Quit")]
+        );
+    }
+
+    #[test]
+    fn test_vim_driver() {
+        let mut selector = None;
+        assert!(key('j').vim_driver(&mut selector, 2));
+        assert_eq!(selector, Some(0));
+        assert!(key('j').vim_driver(&mut selector, 2));
+        assert!(key('j').vim_driver(&mut selector, 2));
+        assert!(key('j').vim_driver(&mut selector, 2));
+        assert_eq!(selector, Some(2));
+        assert!(key('k').vim_driver(&mut selector, 2));
+        assert_eq!(selector, Some(1));
+        assert!(key('k').vim_driver(&mut selector, 2));
+        assert!(key('k').vim_driver(&mut selector, 2));
+        assert_eq!(selector, Some(0));
+        assert!(!key('J').vim_driver(&mut selector, 2));
+        assert!(!key('x').vim_driver(&mut selector, 2));
+        assert_eq!(selector, Some(0));
+    }
+
+    #[test]
+    fn test_page_driver() {
+        let page_up = Event::Key(KeyEvent::new(KeyCode::PageUp, KeyModifiers::NONE));
+        let page_down = Event::Key(KeyEvent::new(KeyCode::PageDown, KeyModifiers::NONE));
+        let mut scroll = Some(0);
+        assert!(page_down.page_driver(&mut scroll, 10, 25));
+        assert_eq!(scroll, Some(10));
+        assert!(page_down.page_driver(&mut scroll, 10, 25));
+        assert!(page_down.page_driver(&mut scroll, 10, 25));
+        assert_eq!(scroll, Some(25));
+        assert!(page_up.page_driver(&mut scroll, 10, 25));
+        assert_eq!(scroll, Some(15));
+        assert!(page_up.page_driver(&mut scroll, 10, 25));
+        assert!(page_up.page_driver(&mut scroll, 10, 25));
+        assert_eq!(scroll, Some(0));
+        assert!(!key('x').page_driver(&mut scroll, 10, 25));
+        assert_eq!(scroll, Some(0));
+    }
+
+    #[test]
+    fn test_page_driver_ends() {
+        let home = Event::Key(KeyEvent::new(KeyCode::Home, KeyModifiers::NONE));
+        let end = Event::Key(KeyEvent::new(KeyCode::End, KeyModifiers::NONE));
+        let mut scroll = Some(4);
+        assert!(end.page_driver(&mut scroll, 10, 25));
+        assert_eq!(scroll, Some(25));
+        assert!(home.page_driver(&mut scroll, 10, 25));
+        assert_eq!(scroll, Some(0));
+        // Content shorter than a page can't scroll at all
+        assert!(end.page_driver(&mut scroll, 10, 0));
+        assert_eq!(scroll, Some(0));
+    }
+
+    #[test]
+    fn test_string_driver_edits_at_cursor() {
+        let left = Event::Key(KeyEvent::new(KeyCode::Left, KeyModifiers::NONE));
+        let backspace = Event::Key(KeyEvent::new(KeyCode::Backspace, KeyModifiers::NONE));
+        let delete = Event::Key(KeyEvent::new(KeyCode::Delete, KeyModifiers::NONE));
+        let mut input = TextInput::new("wjmes");
+        for _ in 0..3 {
+            assert!(left.string_driver(&mut input));
+        }
+        assert!(key('a').string_driver(&mut input));
+        assert_eq!((input.as_str(), input.cursor()), ("wjames", 3));
+        assert!(backspace.string_driver(&mut input));
+        assert!(backspace.string_driver(&mut input));
+        assert_eq!((input.as_str(), input.cursor()), ("wmes", 1));
+        assert!(delete.string_driver(&mut input));
+        assert_eq!((input.as_str(), input.cursor()), ("wes", 1));
+    }
+
+    #[test]
+    fn test_string_driver_ends() {
+        let right = Event::Key(KeyEvent::new(KeyCode::Right, KeyModifiers::NONE));
+        let home = Event::Key(KeyEvent::new(KeyCode::Home, KeyModifiers::NONE));
+        let end = Event::Key(KeyEvent::new(KeyCode::End, KeyModifiers::NONE));
+        let backspace = Event::Key(KeyEvent::new(KeyCode::Backspace, KeyModifiers::NONE));
+        let delete = Event::Key(KeyEvent::new(KeyCode::Delete, KeyModifiers::NONE));
+        let mut input = TextInput::new("μm");
+        assert!(home.string_driver(&mut input));
+        assert!(backspace.string_driver(&mut input));
+        assert!(key('1').string_driver(&mut input));
+        assert_eq!((input.as_str(), input.cursor()), ("1μm", 1));
+        assert!(end.string_driver(&mut input));
+        assert!(right.string_driver(&mut input));
+        assert!(delete.string_driver(&mut input));
+        assert_eq!((input.as_str(), input.cursor()), ("1μm", 3));
+    }
+
+    #[test]
+    fn test_date_range_input_steps() {
+        let code = |code| Event::Key(KeyEvent::new(code, KeyModifiers::NONE));
+        let date = |s| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+        let mut input = DateRangeInput::new(date("2022-11-30"), date("2022-12-31"));
+        assert!(input.driver(&code(KeyCode::Up)));
+        assert_eq!(input.fields()[0].as_str(), "2022-12-01");
+        assert!(input.driver(&code(KeyCode::Tab)));
+        assert_eq!(input.focus(), 1);
+        assert!(input.driver(&code(KeyCode::Up)));
+        assert!(input.driver(&code(KeyCode::Down)));
+        assert!(input.driver(&code(KeyCode::Down)));
+        assert_eq!(input.fields()[1].as_str(), "2022-12-30");
+        assert!(input.driver(&code(KeyCode::BackTab)));
+        assert!(input.driver(&code(KeyCode::Down)));
+        assert_eq!(
+            input.range().unwrap(),
+            (date("2022-11-30"), date("2022-12-30"))
+        );
+        // Typing edits the focused field, which can't be stepped until it is a date again
+        assert!(input.driver(&code(KeyCode::Backspace)));
+        assert!(input.driver(&code(KeyCode::Backspace)));
+        assert!(input.driver(&code(KeyCode::Up)));
+        assert_eq!(input.fields()[0].as_str(), "2022-11-");
+        assert!(input.driver(&key('3')));
+        assert!(input.driver(&key('0')));
+        assert!(!input.driver(&code(KeyCode::Esc)));
+        assert_eq!(input.fields()[0].as_str(), "2022-11-30");
+    }
+
+    #[test]
+    fn test_date_range_input_validates() {
+        let date = |s| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+        let same_day = DateRangeInput::new(date("2022-11-24"), date("2022-11-24"));
+        assert_eq!(
+            same_day.range().unwrap(),
+            (date("2022-11-24"), date("2022-11-24"))
+        );
+        let backwards = DateRangeInput::new(date("2022-11-24"), date("2022-11-23"));
+        assert!(backwards.range().is_err());
+        let mut typo = DateRangeInput::new(date("2022-11-24"), date("2022-11-30"));
+        typo.driver(&Event::Key(KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE)));
+        typo.driver(&key('1'));
+        let err = typo.range().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`2022-11-301` is not a date like 2022-11-24"
+        );
+    }
+
+    #[test]
+    fn test_find_line() {
+        let lines = vec![
+            "[Thu Nov 24]",
+            "  6:00am wjames",
+            "[Fri Nov 25]",
+            "  9:00am WJames",
+        ];
+        assert_eq!(find_line(&lines, "wjames", 0, true), Some(1));
+        assert_eq!(find_line(&lines, "wjames", 2, true), Some(3));
+        assert_eq!(find_line(&lines, "wjames", 4, true), None);
+        assert_eq!(find_line(&lines, "wjames", 3, false), Some(1));
+        assert_eq!(find_line(&lines, "wjames", 1, false), None);
+        assert_eq!(find_line(&lines, "Nov 25", 10, false), Some(2));
+        assert_eq!(find_line(&lines, "", 0, true), None);
+        assert_eq!(find_line(&lines, "rwood", 0, true), None);
+    }
+
+    #[test]
+    fn test_line_search_driver() {
+        let enter = Event::Key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        let lines = vec!["a", "match", "b", "match", "c", "match"];
+        let mut search = LineSearch::default();
+        let mut scroll = Some(0);
+        // `n` does nothing until there's a query
+        assert!(!search.driver(&key('n'), &lines, &mut scroll, 4));
+        for c in "/match".chars() {
+            assert!(search.driver(&key(c), &lines, &mut scroll, 4));
+        }
+        assert_eq!(scroll, Some(0));
+        assert!(search.driver(&enter, &lines, &mut scroll, 4));
+        assert_eq!((search.query(), scroll), ("match", Some(1)));
+        assert!(search.driver(&key('n'), &lines, &mut scroll, 4));
+        assert_eq!(scroll, Some(3));
+        // The last match is past the end of the scroll, but `N` still goes back from it
+        assert!(search.driver(&key('n'), &lines, &mut scroll, 4));
+        assert_eq!(scroll, Some(4));
+        assert!(search.driver(&key('n'), &lines, &mut scroll, 4));
+        assert_eq!(scroll, Some(4));
+        assert!(search.driver(&key('N'), &lines, &mut scroll, 4));
+        assert_eq!(scroll, Some(3));
+        // An empty query ends the search without moving
+        assert!(search.driver(&key('/'), &lines, &mut scroll, 4));
+        assert!(search.driver(&enter, &lines, &mut scroll, 4));
+        assert_eq!((search.query(), scroll), ("", Some(3)));
+        assert!(!search.driver(&key('n'), &lines, &mut scroll, 4));
+    }
+
+    #[test]
+    fn test_hor_window() {
+        let line = "  6:00am-7:00am Wyatt James <wjames@ualberta.ca>";
+        assert_eq!(hor_window(line, 0, 15), "  6:00am-7:00am");
+        assert_eq!(hor_window(line, 16, 11), "Wyatt James");
+        assert_eq!(hor_window(line, 40, 20), "erta.ca>");
+        assert_eq!(hor_window(line, 60, 20), "");
+        assert_eq!(hor_window("1μm μm", 2, 3), "m μ");
+    }
+
+    #[test]
+    fn test_hor_scroll_driver() {
+        let left = Event::Key(KeyEvent::new(KeyCode::Left, KeyModifiers::NONE));
+        let right = Event::Key(KeyEvent::new(KeyCode::Right, KeyModifiers::NONE));
+        let lines = vec!["short", "a line twenty long..", ""];
+        let max_val = max_hor_offset(&lines, 8);
+        assert_eq!(max_val, 12);
+        assert_eq!(max_hor_offset(&lines, 30), 0);
+        let mut offset = 0;
+        assert!(right.hor_scroll_driver(&mut offset, max_val));
+        assert_eq!(offset, 8);
+        assert!(right.hor_scroll_driver(&mut offset, max_val));
+        assert_eq!(offset, 12);
+        assert!(left.hor_scroll_driver(&mut offset, max_val));
+        assert!(left.hor_scroll_driver(&mut offset, max_val));
+        assert_eq!(offset, 0);
+        assert!(!key('x').hor_scroll_driver(&mut offset, max_val));
+    }
+
+    fn numbered_pager(count: usize) -> Pager {
+        let mut pager = Pager::new("Title", (0..count).map(|i| format!("line {i}")).collect());
+        // Room for the title and 10 lines
+        pager.size = (80, 11);
+        pager
+    }
+
+    #[test]
+    fn test_pager_scrolling() {
+        let key_code = |code| Event::Key(KeyEvent::new(code, KeyModifiers::NONE));
+        let mut pager = numbered_pager(25);
+        assert_eq!(pager.handle(&key_code(KeyCode::Down)).unwrap(), None);
+        assert_eq!(pager.scroll(), 1);
+        pager.handle(&key_code(KeyCode::PageDown)).unwrap();
+        assert_eq!(pager.scroll(), 11);
+        pager.handle(&key_code(KeyCode::PageDown)).unwrap();
+        assert_eq!(pager.scroll(), 15);
+        pager.handle(&key('k')).unwrap();
+        assert_eq!(pager.scroll(), 14);
+        pager.handle(&key_code(KeyCode::Home)).unwrap();
+        assert_eq!(pager.scroll(), 0);
+        // A taller terminal fits more lines, so there's less to scroll
+        pager.handle(&Event::Resize(80, 21)).unwrap();
+        pager.handle(&key_code(KeyCode::End)).unwrap();
+        assert_eq!(pager.scroll(), 5);
+        // Fewer lines than before pull the scroll back
+        pager.set_lines(vec!["only line".to_string()]);
+        assert_eq!(pager.scroll(), 0);
+    }
+
+    #[test]
+    fn test_pager_exits() {
+        let key_code = |code| Event::Key(KeyEvent::new(code, KeyModifiers::NONE));
+        let mut pager = numbered_pager(25);
+        assert_eq!(
+            pager.handle(&key_code(KeyCode::Esc)).unwrap(),
+            Some(PagerExit::Back)
+        );
+        assert_eq!(
+            pager.handle(&key_code(KeyCode::Enter)).unwrap(),
+            Some(PagerExit::Back)
+        );
+        assert_eq!(pager.handle(&key('q')).unwrap(), Some(PagerExit::Quit));
+        assert_eq!(
+            pager.handle(&key('e')).unwrap(),
+            Some(PagerExit::Key(key('e')))
+        );
+        // While typing a search, keys go to the query instead
+        pager.handle(&key('/')).unwrap();
+        assert_eq!(pager.handle(&key('q')).unwrap(), None);
+        pager.handle(&key_code(KeyCode::Backspace)).unwrap();
+        assert_eq!(pager.handle(&key('2')).unwrap(), None);
+        pager.handle(&key_code(KeyCode::Enter)).unwrap();
+        assert_eq!(pager.scroll(), 2);
+        pager.handle(&key('n')).unwrap();
+        assert_eq!(pager.scroll(), 12);
+    }
+
+    #[test]
+    fn test_theme_highlight() {
+        let highlighted = |theme: &Theme| {
+            let mut out = vec![];
+            out.queue_maybe_highlighted_with("MLA150", true, theme)
+                .unwrap();
+            String::from_utf8(out).unwrap()
+        };
+        let default = highlighted(&Theme::default());
+        assert!(default.contains("\x1b[7m"));
+        let custom = highlighted(&Theme {
+            highlight: TextStyle {
+                fg: Some(Color::Black),
+                bg: Some(Color::Yellow),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        assert_ne!(custom, default);
+        assert!(!custom.contains("\x1b[7m"));
+        assert!(custom.contains("MLA150"));
+    }
+
+    #[test]
+    fn test_theme_from_ron() {
+        let theme: Theme =
+            ron::from_str(r#"(highlight: (fg: Some("black"), bold: true))"#).unwrap();
+        assert_eq!(theme.highlight.fg, Some(Color::Black));
+        assert!(theme.highlight.bold && !theme.highlight.reverse);
+        assert_eq!(theme.error, Theme::default().error);
+    }
+
+    #[test]
+    fn test_spinner_tick() {
+        let mut spinner = Spinner::new("Loading");
+        let frames = (0..5).map(|_| spinner.tick()).collect::<String>();
+        assert_eq!(frames, "/-\\|/");
+    }
+
+    #[tokio::test]
+    async fn test_spinner_ready_future() {
+        let spinner = Spinner::new("Loading");
+        assert_eq!(spinner.run(async { Ok(5) }).await.unwrap(), 5);
+        let spinner = Spinner::new("Loading");
+        let result = spinner
+            .run(async { Err::<(), _>(anyhow::anyhow!("offline")) })
+            .await;
+        assert_eq!(result.unwrap_err().to_string(), "offline");
+    }
+
+    #[test]
+    fn test_confirm_driver() {
+        let key_code = |code| Event::Key(KeyEvent::new(code, KeyModifiers::NONE));
+        let mut selector = Some(1);
+        assert_eq!(key('y').confirm_driver(&mut selector), Some(true));
+        assert_eq!(key('Y').confirm_driver(&mut selector), Some(true));
+        assert_eq!(key('n').confirm_driver(&mut selector), Some(false));
+        assert_eq!(key('x').confirm_driver(&mut selector), None);
+        assert_eq!(
+            key_code(KeyCode::Enter).confirm_driver(&mut selector),
+            Some(false)
+        );
+        assert_eq!(key_code(KeyCode::Left).confirm_driver(&mut selector), None);
+        assert_eq!(selector, Some(0));
+        assert_eq!(
+            key_code(KeyCode::Enter).confirm_driver(&mut selector),
+            Some(true)
+        );
+        // Esc is No even with Yes selected
+        assert_eq!(
+            key_code(KeyCode::Esc).confirm_driver(&mut selector),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn test_toggle_set() {
+        let mut toggled = ToggleSet::default();
+        assert!(toggled.is_empty());
+        assert!(toggled.toggle("116"));
+        assert!(toggled.toggle("42"));
+        assert!(toggled.toggle("7"));
+        assert!(!toggled.toggle("42"));
+        assert_eq!(toggled.keys(), ["116", "7"]);
+        assert!(toggled.contains(&"7") && !toggled.contains(&"42"));
+        // Checking again puts it at the end
+        assert!(toggled.toggle("42"));
+        assert_eq!(toggled.keys(), ["116", "7", "42"]);
+    }
+
+    #[test]
+    fn test_queue_ver_checklist() {
+        let mut out = vec![];
+        out.queue_ver_checklist(&["MLA150", "Plasmalab"], &[true], None)
+            .unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("[x] MLA150") && out.contains("[ ] Plasmalab"));
+    }
+
+    #[test]
+    fn test_header_jump_driver() {
+        let headers = [0, 4, 9];
+        let mut scroll = Some(0);
+        assert!(key(']').header_jump_driver(&mut scroll, &headers, 20));
+        assert_eq!(scroll, Some(4));
+        assert!(key(']').header_jump_driver(&mut scroll, &headers, 20));
+        assert_eq!(scroll, Some(9));
+        assert!(key(']').header_jump_driver(&mut scroll, &headers, 20));
+        assert_eq!(scroll, Some(9));
+        scroll = Some(6);
+        assert!(key('[').header_jump_driver(&mut scroll, &headers, 20));
+        assert_eq!(scroll, Some(4));
+        assert!(key('[').header_jump_driver(&mut scroll, &headers, 20));
+        assert_eq!(scroll, Some(0));
+        assert!(!key('x').header_jump_driver(&mut scroll, &headers, 20));
+    }
+
+    #[test]
+    fn test_header_jump_driver_clamps() {
+        let headers = [0, 4, 9];
+        let mut scroll = Some(0);
+        key(']').header_jump_driver(&mut scroll, &headers, 3);
+        assert_eq!(scroll, Some(3));
+        key(']').header_jump_driver(&mut scroll, &headers, 3);
+        assert_eq!(scroll, Some(3));
+    }
+
+    #[test]
+    fn test_selected_option() {
+        let options = ["List Tool Openings", "Exit"];
+        assert_eq!(selected_option(&options, None), None);
+        assert_eq!(
+            selected_option(&options, Some(0)),
+            Some(&"List Tool Openings")
+        );
+        assert_eq!(selected_option(&options, Some(1)), Some(&"Exit"));
+        assert_eq!(selected_option(&options, Some(2)), None);
+        assert_eq!(selected_option::<&str>(&[], Some(0)), None);
+    }
+}
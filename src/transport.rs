@@ -0,0 +1,191 @@
+use std::{future::Future, sync::Arc, time::Duration};
+
+use anyhow::{Context, Result};
+use reqwest::{
+    cookie::{CookieStore, Jar},
+    Client, StatusCode, Url,
+};
+
+/// How `NanoFab` reaches the server, so tests can answer its requests with canned pages
+/// instead of a live site
+pub trait HttpTransport {
+    /// The body of the page at `url`
+    fn get_text(&self, url: &str) -> impl Future<Output = Result<String>> + Send;
+    /// The body of the response to posting the url-encoded form `body` to `url`
+    fn post_form(&self, url: &str, body: &str) -> impl Future<Output = Result<String>> + Send;
+    /// Forget any session kept for the site at `base_url`, like its cookies
+    fn clear_session(&self, _base_url: &str) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// The real transport, keeping the session in a cookie jar
+pub struct ReqwestTransport {
+    client: Client,
+    pub(crate) cookies: Arc<Jar>,
+    pub(crate) retries: u32,
+    pub(crate) retry_delay: Duration,
+}
+impl Default for ReqwestTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl ReqwestTransport {
+    pub fn new() -> Self {
+        let cookies = Arc::new(Jar::default());
+        Self {
+            client: reqwest::ClientBuilder::new()
+                .cookie_provider(cookies.clone())
+                .build()
+                .expect("Creating the client should not fail"),
+            cookies,
+            retries: 0,
+            retry_delay: Duration::ZERO,
+        }
+    }
+    /// Send a request, retrying the failures `retryable` accepts as set by
+    /// `NanoFab::with_retries`
+    async fn send(
+        &self,
+        request: impl Fn() -> reqwest::RequestBuilder,
+        retryable: fn(&reqwest::Error) -> bool,
+    ) -> reqwest::Result<String> {
+        retry_with_backoff(self.retries, self.retry_delay, retryable, || async {
+            let resp = request().send().await?;
+            let resp = match TRANSIENT_STATUSES.contains(&resp.status()) {
+                true => resp.error_for_status()?,
+                false => resp,
+            };
+            resp.text().await
+        })
+        .await
+    }
+}
+impl HttpTransport for ReqwestTransport {
+    async fn get_text(&self, url: &str) -> Result<String> {
+        self.send(|| self.client.get(url), is_transient).await.context("Failed to send get request")
+    }
+    async fn post_form(&self, url: &str, body: &str) -> Result<String> {
+        let request = || {
+            self.client
+                .post(url)
+                .header("Content-Type", "application/x-www-form-urlencoded")
+                .body(body.to_string())
+        };
+        // A post that timed out may still have booked or cancelled something, so only
+        // resend it if it never reached the server
+        self.send(request, is_unsent).await.context("Failed to send post request")
+    }
+    fn clear_session(&self, base_url: &str) -> Result<()> {
+        let url = Url::parse(base_url).with_context(|| format!("`{base_url}` is not a URL"))?;
+        let Some(header) = self.cookies.cookies(&url) else { return Ok(()) };
+        let cookies = header.to_str().context("Cookie was not valid text")?;
+        for (name, _) in cookies.split("; ").filter_map(|cookie| cookie.split_once('=')) {
+            // The jar has no way to remove a cookie, but drops any that have expired
+            self.cookies.add_cookie_str(&format!("{name}=; Max-Age=0"), &url);
+        }
+        Ok(())
+    }
+}
+
+/// Statuses a proxy answers with while the site is briefly unreachable
+const TRANSIENT_STATUSES: [StatusCode; 3] =
+    [StatusCode::BAD_GATEWAY, StatusCode::SERVICE_UNAVAILABLE, StatusCode::GATEWAY_TIMEOUT];
+
+fn is_transient(err: &reqwest::Error) -> bool {
+    err.is_timeout()
+        || err.is_connect()
+        || err.status().is_some_and(|status| TRANSIENT_STATUSES.contains(&status))
+}
+
+fn is_unsent(err: &reqwest::Error) -> bool {
+    err.is_connect()
+}
+
+/// Run `attempt` until it succeeds, fails with an error `is_transient` rejects, or has
+/// been retried `retries` times, doubling the wait from `base_delay` between tries
+async fn retry_with_backoff<T, E, Fut>(
+    retries: u32,
+    base_delay: Duration,
+    is_transient: impl Fn(&E) -> bool,
+    mut attempt: impl FnMut() -> Fut,
+) -> Result<T, E>
+where
+    Fut: Future<Output = Result<T, E>>,
+{
+    let mut delay = base_delay;
+    for _ in 0..retries {
+        match attempt().await {
+            Err(err) if is_transient(&err) => {
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+            }
+            result => return result,
+        }
+    }
+    attempt().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_retry_with_backoff() {
+        let delay = Duration::from_millis(1);
+        let flaky = |failures: u32| {
+            let calls = std::cell::Cell::new(0);
+            move || {
+                calls.set(calls.get() + 1);
+                let calls = calls.get();
+                async move {
+                    match calls <= failures {
+                        true => Err("timed out"),
+                        false => Ok(calls),
+                    }
+                }
+            }
+        };
+        // Fails twice then succeeds on the third call
+        let result = retry_with_backoff(3, delay, |_| true, flaky(2)).await;
+        assert_eq!(result, Ok(3));
+        let result = retry_with_backoff(1, delay, |_| true, flaky(2)).await;
+        assert_eq!(result, Err("timed out"));
+        // Errors that aren't transient fail without retrying
+        let result = retry_with_backoff(3, delay, |_| false, flaky(2)).await;
+        assert_eq!(result, Err("timed out"));
+    }
+
+    #[tokio::test]
+    async fn test_post_not_retried_after_timeout() {
+        // A server that takes every request but never answers
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let accepted = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = accepted.clone();
+        tokio::spawn(async move {
+            let mut held = vec![];
+            while let Ok((stream, _)) = listener.accept().await {
+                counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                held.push(stream);
+            }
+        });
+        let cookies = Arc::new(Jar::default());
+        let transport = ReqwestTransport {
+            client: reqwest::ClientBuilder::new()
+                .timeout(Duration::from_millis(100))
+                .build()
+                .unwrap(),
+            cookies,
+            retries: 3,
+            retry_delay: Duration::from_millis(1),
+        };
+        let count = || accepted.load(std::sync::atomic::Ordering::SeqCst);
+        assert!(transport.post_form(&url, "id=1").await.is_err());
+        assert_eq!(count(), 1);
+        // Gets are safe to send again
+        assert!(transport.get_text(&url).await.is_err());
+        assert_eq!(count(), 5);
+    }
+}